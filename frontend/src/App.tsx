@@ -9,6 +9,9 @@ import { MemoryFile, AppState, DocFile, EditableFile, DocsNode } from './types'
 
 type ViewMode = 'memory' | 'docs'
 
+// Deeper levels are loaded by the tree as nodes are expanded
+const INITIAL_TREE_DEPTH = 2
+
 function App() {
   const [state, setState] = useState<AppState>({
     tree: null,
@@ -34,7 +37,7 @@ function App() {
       setState(prev => ({ ...prev, loading: true, error: null }))
 
       const [tree, memoryFiles, recommendations] = await Promise.all([
-        api.fetchTree(),
+        api.fetchTree({ depth: INITIAL_TREE_DEPTH }),
         api.fetchMemoryFiles(),
         api.fetchRecommendations()
       ])
@@ -74,7 +77,7 @@ function App() {
     }
   }, [viewMode, docsTree])
 
-  const selectFile = useCallback((file: MemoryFile | null) => {
+  const selectFile = useCallback(async (file: MemoryFile | null) => {
     console.log('Selecting file:', file)
     // If selecting a file, get the latest version from memoryFiles
     if (file) {
@@ -82,6 +85,20 @@ function App() {
         const latestFile = prev.memoryFiles.find(f => f.path === file.path)
         return { ...prev, selectedFile: latestFile || file }
       })
+
+      // The list only carries metadata, so load the content on first selection
+      if (file.exists && file.content === undefined) {
+        try {
+          const detail = await api.fetchMemoryFile(file.path)
+          setState(prev => ({
+            ...prev,
+            memoryFiles: prev.memoryFiles.map(f => f.path === detail.path ? { ...f, ...detail } : f),
+            selectedFile: prev.selectedFile?.path === detail.path ? { ...prev.selectedFile, ...detail } : prev.selectedFile
+          }))
+        } catch (error) {
+          console.error('Failed to load memory file:', error)
+        }
+      }
     } else {
       setState(prev => ({ ...prev, selectedFile: null }))
     }
@@ -102,7 +119,7 @@ function App() {
       
       if (fileExistsOnDisk) {
         // Update existing file
-        result = await api.updateMemoryFile(path, content, isHtml)
+        result = await api.updateMemoryFile(path, content, existingFile?.content_hash ?? '', isHtml)
       } else {
         // Create new file - only when there's actual content
         if (!content || content.trim().length === 0) {
@@ -115,11 +132,12 @@ function App() {
       
       // Update local state with the returned markdown content
       const updatedContent = result.content
+      const updatedHash = result.content_hash ?? ''
       
       // Reload tree and memory files if this was a new file creation
       if (wasCreated) {
         const [tree, memoryFiles] = await Promise.all([
-          api.fetchTree(),
+          api.fetchTree({ depth: INITIAL_TREE_DEPTH }),
           api.fetchMemoryFiles()
         ])
         
@@ -150,6 +168,7 @@ function App() {
               ...updatedFiles[fileIndex],
               content: updatedContent,
               content_html: isHtml ? content : '',
+              content_hash: updatedHash,
               exists: true
             }
             return { ...prev, memoryFiles: updatedFiles }
@@ -159,6 +178,7 @@ function App() {
               path,
               content: updatedContent,
               content_html: isHtml ? content : '',
+              content_hash: updatedHash,
               exists: true,
               parent_path: path.substring(0, path.lastIndexOf('/'))
             }
@@ -196,7 +216,7 @@ function App() {
       if (result.deleted) {
         // Reload tree and memory files after deletion
         const [tree, memoryFiles] = await Promise.all([
-          api.fetchTree(),
+          api.fetchTree({ depth: INITIAL_TREE_DEPTH }),
           api.fetchMemoryFiles()
         ])
 
@@ -217,6 +237,21 @@ function App() {
     }
   }, [])
 
+  const deleteDoc = useCallback(async (path: string): Promise<boolean> => {
+    try {
+      const result = await api.deleteDocFile(path)
+      if (result.deleted) {
+        setSelectedDocFile(prev => prev?.path === path ? null : prev)
+        await loadDocsTree()
+        return true
+      }
+      return false
+    } catch (error) {
+      console.error('Failed to delete doc:', error)
+      return false
+    }
+  }, [])
+
   const handleDocFileSelect = useCallback(async (path: string) => {
     try {
       const docFile = await fetchDocFile(path)
@@ -230,17 +265,18 @@ function App() {
     const name = window.prompt('Document name (without .md):')
     if (!name) return
 
-    const docPath = folderPath === '.' ? `${name}.md` : `${folderPath}/${name}.md`
+    const docPath = `${folderPath}/${name}.md`
 
     try {
       // Create the file with empty content
-      await api.createDocFile(docPath, '', false)
+      const created = await api.createDocFile(docPath, '', false)
 
       // Create a DocFile object and select it
       const newDoc: DocFile = {
         path: docPath,
         content: '',
         content_html: '',
+        content_hash: created.content_hash ?? '',
         exists: true
       }
       setSelectedDocFile(newDoc)
@@ -272,8 +308,8 @@ function App() {
 
         if (fileExists) {
           // Update existing doc
-          const result = await api.updateDocFile(path, content, isHtml)
-          setSelectedDocFile(prev => prev ? { ...prev, content: result.content, exists: true } : null)
+          const result = await api.updateDocFile(path, content, selectedDocFile?.content_hash ?? '', isHtml)
+          setSelectedDocFile(prev => prev ? { ...prev, content: result.content, content_hash: result.content_hash, exists: true } : null)
           return { success: true, content: result.content }
         } else {
           // Create new doc
@@ -283,7 +319,7 @@ function App() {
           const result = await api.createDocFile(path, content, isHtml)
           // Reload docs tree after creation
           // TODO: implement tree refresh
-          setSelectedDocFile(prev => prev ? { ...prev, content: result.content, exists: true } : null)
+          setSelectedDocFile(prev => prev ? { ...prev, content: result.content, content_hash: result.content_hash ?? '', exists: true } : null)
           return { success: true, content: result.content }
         }
       } catch (error) {
@@ -351,7 +387,7 @@ function App() {
         <Editor
           file={getEditableFile()}
           onSave={handleSave}
-          onDelete={viewMode === 'memory' ? deleteFile : undefined}
+          onDelete={viewMode === 'memory' ? deleteFile : deleteDoc}
         />
       </div>
     </div>
@@ -0,0 +1,45 @@
+import { AdrStatus, AdrSummary } from '../types'
+import { apiFetch } from './auth'
+
+const API_BASE = '/api/v1'
+
+async function readError(response: Response, fallback: string): Promise<Error> {
+  const body = await response.json().catch(() => null)
+  return new Error(body?.error || `${fallback}: ${response.status}`)
+}
+
+export async function fetchAdrs(): Promise<AdrSummary[]> {
+  const response = await apiFetch(`${API_BASE}/adr`)
+  if (!response.ok) {
+    throw await readError(response, 'Failed to fetch ADRs')
+  }
+  return response.json()
+}
+
+export async function createAdr(title: string, supersedes: number[] = []): Promise<AdrSummary> {
+  const response = await apiFetch(`${API_BASE}/adr`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ title, supersedes }),
+  })
+  if (!response.ok) {
+    throw await readError(response, 'Failed to create ADR')
+  }
+  return response.json()
+}
+
+export async function setAdrStatus(
+  number: number,
+  status: AdrStatus,
+  supersededBy?: number
+): Promise<AdrSummary> {
+  const response = await apiFetch(`${API_BASE}/adr/${number}/status`, {
+    method: 'PUT',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ status, superseded_by: supersededBy ?? null }),
+  })
+  if (!response.ok) {
+    throw await readError(response, 'Failed to update ADR status')
+  }
+  return response.json()
+}
@@ -0,0 +1,24 @@
+import { ErrorResponse } from '../types'
+
+// The server injects a per-launch access token into index.html; every /api call must present it
+const token = document
+  .querySelector<HTMLMetaElement>('meta[name="cc-atlas-token"]')
+  ?.content
+
+export function apiFetch(input: string, init: RequestInit = {}): Promise<Response> {
+  const headers = new Headers(init.headers)
+  if (token) {
+    headers.set('Authorization', `Bearer ${token}`)
+  }
+  return fetch(input, { ...init, headers })
+}
+
+/** The JSON error body of a failed response, if it has one */
+export async function readError(response: Response): Promise<ErrorResponse | null> {
+  try {
+    const body = await response.clone().json()
+    return typeof body?.code === 'string' ? body as ErrorResponse : null
+  } catch {
+    return null
+  }
+}
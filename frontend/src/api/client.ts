@@ -2,6 +2,7 @@
 export {
   fetchTree,
   fetchMemoryFiles,
+  fetchMemoryFile,
   updateMemoryFile,
   createMemoryFile,
   deleteMemoryFile,
@@ -14,4 +15,19 @@ export {
   fetchDocFile,
   createDocFile,
   updateDocFile,
-} from './docs'
\ No newline at end of file
+  deleteDocFile,
+} from './docs'
+// Re-export search APIs
+export { searchFiles } from './search'
+
+// Re-export move APIs
+export { moveFile } from './move'
+
+// Re-export link graph APIs
+export { fetchBacklinks, fetchBrokenLinks } from './links'
+
+// Re-export ADR APIs
+export { fetchAdrs, createAdr, setAdrStatus } from './adr'
+
+// Re-export plan APIs
+export { fetchPlans, fetchPlan, togglePlanTask } from './plans'
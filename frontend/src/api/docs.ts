@@ -1,9 +1,24 @@
 import { DocsNode, DocFile } from '../types'
+import { apiFetch, readError } from './auth'
 
-const API_BASE = '/api'
+const API_BASE = '/api/v1'
 
-export async function fetchDocsTree(): Promise<DocsNode | null> {
-  const response = await fetch(`${API_BASE}/docs/tree`)
+export interface DocsTreeOptions {
+  status?: string
+  owner?: string
+  tag?: string
+  sort?: 'name' | 'title' | 'status' | 'owner' | 'updated'
+  order?: 'asc' | 'desc'
+}
+
+export async function fetchDocsTree(options: DocsTreeOptions = {}): Promise<DocsNode | null> {
+  const params = new URLSearchParams()
+  for (const [key, value] of Object.entries(options)) {
+    if (value) params.set(key, value)
+  }
+
+  const query = params.toString()
+  const response = await apiFetch(`${API_BASE}/docs/tree${query ? `?${query}` : ''}`)
   if (!response.ok) {
     throw new Error('Failed to fetch docs tree')
   }
@@ -11,7 +26,7 @@ export async function fetchDocsTree(): Promise<DocsNode | null> {
 }
 
 export async function fetchDocFile(path: string): Promise<DocFile> {
-  const response = await fetch(`${API_BASE}/docs/files/${path}`)
+  const response = await apiFetch(`${API_BASE}/docs/files/${path}`)
   if (!response.ok) {
     if (response.status === 404) {
       throw new Error('Doc file not found')
@@ -25,14 +40,14 @@ export async function createDocFile(
   path: string,
   content: string,
   isHtml: boolean = false
-): Promise<{ content: string; created: boolean }> {
+): Promise<{ content: string; content_hash?: string; created: boolean }> {
   const body = isHtml
     ? { content_html: content }
     : { content };
 
   console.log('Creating doc at:', path, 'isHtml:', isHtml, 'content length:', content.length)
 
-  const response = await fetch(`${API_BASE}/docs/files/${path}`, {
+  const response = await apiFetch(`${API_BASE}/docs/files/${path}`, {
     method: 'POST',
     headers: {
       'Content-Type': 'application/json',
@@ -41,8 +56,7 @@ export async function createDocFile(
   })
 
   if (!response.ok) {
-    // If we get 409 Conflict, the file already exists
-    if (response.status === 409) {
+    if ((await readError(response))?.code === 'file_exists') {
       console.log('Doc file already exists')
       throw new Error('File already exists')
     }
@@ -61,23 +75,28 @@ export async function createDocFile(
 export async function updateDocFile(
   path: string,
   content: string,
+  baseHash: string,
   isHtml: boolean = false
-): Promise<{ content: string }> {
+): Promise<{ content: string; content_hash: string }> {
   const body = isHtml
     ? { content_html: content }
     : { content };
 
   console.log('Saving doc to:', path, 'isHtml:', isHtml, 'content length:', content.length)
 
-  const response = await fetch(`${API_BASE}/docs/files/${path}`, {
+  const response = await apiFetch(`${API_BASE}/docs/files/${path}`, {
     method: 'PUT',
     headers: {
       'Content-Type': 'application/json',
+      'If-Match': `"${baseHash}"`,
     },
     body: JSON.stringify(body),
   })
 
   if (!response.ok) {
+    if (response.status === 409) {
+      throw new Error('File was modified externally. Reload before saving.')
+    }
     console.error('Save failed with status:', response.status)
     const text = await response.text()
     console.error('Response:', text)
@@ -88,3 +107,18 @@ export async function updateDocFile(
   console.log('Save successful, returned content length:', result.content?.length)
   return result
 }
+
+export async function deleteDocFile(path: string): Promise<{ deleted: boolean; trash_id: string }> {
+  const response = await apiFetch(`${API_BASE}/docs/files/${path}`, {
+    method: 'DELETE',
+  })
+
+  if (!response.ok) {
+    if (response.status === 404) {
+      throw new Error('Doc file not found')
+    }
+    throw new Error(`Failed to delete doc file: ${response.status}`)
+  }
+
+  return response.json()
+}
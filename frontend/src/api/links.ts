@@ -0,0 +1,21 @@
+import { BacklinksResponse, BrokenLinkReport } from '../types'
+import { apiFetch } from './auth'
+
+const API_BASE = '/api/v1'
+
+export async function fetchBacklinks(path: string): Promise<BacklinksResponse> {
+  const params = new URLSearchParams({ path })
+  const response = await apiFetch(`${API_BASE}/links/backlinks?${params}`)
+  if (!response.ok) {
+    throw new Error(`Failed to fetch backlinks: ${response.status}`)
+  }
+  return response.json()
+}
+
+export async function fetchBrokenLinks(): Promise<BrokenLinkReport> {
+  const response = await apiFetch(`${API_BASE}/links/broken`)
+  if (!response.ok) {
+    throw new Error(`Failed to fetch broken links: ${response.status}`)
+  }
+  return response.json()
+}
@@ -1,43 +1,70 @@
-import { DirectoryInfo, MemoryFile } from '../types'
+import { DirectoryInfo, MemoryFile, MemoryFileDetail } from '../types'
+import { apiFetch } from './auth'
 
-const API_BASE = '/api'
+const API_BASE = '/api/v1'
 
-export async function fetchTree(): Promise<DirectoryInfo> {
-  const response = await fetch(`${API_BASE}/tree`)
+export interface TreeOptions {
+  path?: string
+  depth?: number
+  includeFiles?: boolean
+}
+
+export async function fetchTree(options: TreeOptions = {}): Promise<DirectoryInfo> {
+  const params = new URLSearchParams()
+  if (options.path) params.set('path', options.path)
+  if (options.depth !== undefined) params.set('depth', String(options.depth))
+  if (options.includeFiles) params.set('include_files', 'true')
+
+  const query = params.toString()
+  const response = await apiFetch(`${API_BASE}/tree${query ? `?${query}` : ''}`)
   if (!response.ok) {
     throw new Error('Failed to fetch tree')
   }
   return response.json()
 }
 
-export async function fetchMemoryFiles(): Promise<MemoryFile[]> {
-  const response = await fetch(`${API_BASE}/memory-files`)
+export async function fetchMemoryFiles(includeContent: boolean = false): Promise<MemoryFile[]> {
+  const query = includeContent ? '?include=content' : ''
+  const response = await apiFetch(`${API_BASE}/memory-files${query}`)
   if (!response.ok) {
     throw new Error('Failed to fetch memory files')
   }
   return response.json()
 }
 
+export async function fetchMemoryFile(path: string): Promise<MemoryFileDetail> {
+  const response = await apiFetch(`${API_BASE}/memory-files/${path}`)
+  if (!response.ok) {
+    throw new Error(`Failed to fetch memory file: ${response.status}`)
+  }
+  return response.json()
+}
+
 export async function updateMemoryFile(
   path: string,
   content: string,
+  baseHash: string,
   isHtml: boolean = false
-): Promise<{ content: string }> {
+): Promise<{ content: string; content_hash: string }> {
   const body = isHtml
     ? { content_html: content }
     : { content };
 
   console.log('Saving to:', path, 'isHtml:', isHtml, 'content length:', content.length)
 
-  const response = await fetch(`${API_BASE}/memory-files/${path}`, {
+  const response = await apiFetch(`${API_BASE}/memory-files/${path}`, {
     method: 'PUT',
     headers: {
       'Content-Type': 'application/json',
+      'If-Match': `"${baseHash}"`,
     },
     body: JSON.stringify(body),
   })
 
   if (!response.ok) {
+    if (response.status === 409) {
+      throw new Error('File was modified externally. Reload before saving.')
+    }
     console.error('Save failed with status:', response.status)
     const text = await response.text()
     console.error('Response:', text)
@@ -50,7 +77,7 @@ export async function updateMemoryFile(
 
   if (!text) {
     console.warn('Empty response from server, returning empty content')
-    return { content: '' }
+    return { content: '', content_hash: '' }
   }
 
   try {
@@ -68,14 +95,14 @@ export async function createMemoryFile(
   path: string,
   content: string,
   isHtml: boolean = false
-): Promise<{ content: string; created: boolean }> {
+): Promise<{ content: string; content_hash?: string; created: boolean }> {
   const body = isHtml
     ? { content_html: content }
     : { content };
 
   console.log('Creating file at:', path, 'isHtml:', isHtml, 'content length:', content.length)
 
-  const response = await fetch(`${API_BASE}/memory-files/${path}`, {
+  const response = await apiFetch(`${API_BASE}/memory-files/${path}`, {
     method: 'POST',
     headers: {
       'Content-Type': 'application/json',
@@ -105,7 +132,7 @@ export async function createMemoryFile(
 export async function deleteMemoryFile(path: string): Promise<{ deleted: boolean }> {
   console.log('Deleting file at:', path)
 
-  const response = await fetch(`${API_BASE}/memory-files/${path}`, {
+  const response = await apiFetch(`${API_BASE}/memory-files/${path}`, {
     method: 'DELETE',
   })
 
@@ -125,7 +152,7 @@ export async function deleteMemoryFile(path: string): Promise<{ deleted: boolean
 }
 
 export async function fetchRecommendations(): Promise<string[]> {
-  const response = await fetch(`${API_BASE}/recommendations`)
+  const response = await apiFetch(`${API_BASE}/recommendations`)
   if (!response.ok) {
     throw new Error('Failed to fetch recommendations')
   }
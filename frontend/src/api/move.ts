@@ -0,0 +1,26 @@
+import { MoveResult } from '../types'
+import { apiFetch, readError } from './auth'
+
+const API_BASE = '/api/v1'
+
+export async function moveFile(
+  kind: 'memory' | 'docs',
+  from: string,
+  to: string,
+  dryRun: boolean = false
+): Promise<MoveResult> {
+  const response = await apiFetch(`${API_BASE}/move`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ kind, from, to, dry_run: dryRun }),
+  })
+
+  if (!response.ok) {
+    const error = await readError(response)
+    if (error?.code === 'file_exists') {
+      throw new Error(`${to} already exists`)
+    }
+    throw new Error(error?.error ?? `Failed to move ${from}: ${response.status}`)
+  }
+  return response.json()
+}
@@ -0,0 +1,42 @@
+import { PlanSummary, PlanTask } from '../types'
+import { apiFetch } from './auth'
+
+const API_BASE = '/api/v1'
+
+async function readError(response: Response, fallback: string): Promise<Error> {
+  const body = await response.json().catch(() => null)
+  return new Error(body?.error || `${fallback}: ${response.status}`)
+}
+
+export async function fetchPlans(includeTasks = false): Promise<PlanSummary[]> {
+  const query = includeTasks ? '?include=tasks' : ''
+  const response = await apiFetch(`${API_BASE}/plans${query}`)
+  if (!response.ok) {
+    throw await readError(response, 'Failed to fetch plans')
+  }
+  return response.json()
+}
+
+export async function fetchPlan(path: string): Promise<PlanSummary> {
+  const response = await apiFetch(`${API_BASE}/plans/${path}`)
+  if (!response.ok) {
+    throw await readError(response, 'Failed to fetch plan')
+  }
+  return response.json()
+}
+
+export async function togglePlanTask(
+  plan: PlanSummary,
+  task: PlanTask,
+  checked?: boolean
+): Promise<PlanSummary> {
+  const response = await apiFetch(`${API_BASE}/plans/toggle`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', 'If-Match': `"${plan.content_hash}"` },
+    body: JSON.stringify({ path: plan.path, line: task.line, text: task.text, checked: checked ?? null }),
+  })
+  if (!response.ok) {
+    throw await readError(response, 'Failed to update task')
+  }
+  return response.json()
+}
@@ -0,0 +1,25 @@
+import { SearchResponse } from '../types'
+import { apiFetch } from './auth'
+
+const API_BASE = '/api/v1'
+
+export interface SearchOptions {
+  kind?: 'memory' | 'docs'
+  scope?: 'project' | 'directory' | 'local'
+  dir?: string
+  limit?: number
+}
+
+export async function searchFiles(query: string, options: SearchOptions = {}): Promise<SearchResponse> {
+  const params = new URLSearchParams({ q: query })
+  if (options.kind) params.set('kind', options.kind)
+  if (options.scope) params.set('scope', options.scope)
+  if (options.dir) params.set('dir', options.dir)
+  if (options.limit !== undefined) params.set('limit', String(options.limit))
+
+  const response = await apiFetch(`${API_BASE}/search?${params}`)
+  if (!response.ok) {
+    throw new Error(`Search failed: ${response.status}`)
+  }
+  return response.json()
+}
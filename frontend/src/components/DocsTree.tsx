@@ -24,8 +24,8 @@ export const DocsTree: React.FC<DocsTreeProps> = ({
   onCreateDoc
 }) => {
   const [expandedPaths, setExpandedPaths] = useState<Set<string>>(() => {
-    // Start with root expanded
-    return new Set(tree ? [tree.path] : [])
+    // Start with every doc root expanded
+    return new Set(tree ? tree.children.filter(c => c.is_root).map(c => c.path) : [])
   })
 
   const toggleExpanded = (path: string) => {
@@ -43,20 +43,23 @@ export const DocsTree: React.FC<DocsTreeProps> = ({
   if (!tree) {
     return (
       <div className="sidebar-empty">
-        <p>No docs/ directory found</p>
+        <p>No documentation folders found</p>
       </div>
     )
   }
 
-  // Render children directly (skip the root "docs" node)
+  // Render the doc roots directly (skip the virtual top node)
+  const firstRoot = tree.children.find(c => c.is_root)
+
   return (
     <>
       <div className="docs-tree-header">
         <span className="docs-tree-title">Documents</span>
         <button
           className="docs-tree-add-btn"
-          onClick={() => onCreateDoc('.')}
-          title="New document in root"
+          onClick={() => firstRoot && onCreateDoc(firstRoot.path)}
+          disabled={!firstRoot}
+          title={firstRoot ? `New document in ${firstRoot.name}` : 'No documentation folder'}
         >
           <HiPlus />
         </button>
@@ -152,8 +155,8 @@ const TreeNode: React.FC<TreeNodeProps> = ({
           )}
         </span>
 
-        <span className="tree-node-name">
-          {node.name}
+        <span className="tree-node-name" title={node.metadata?.status}>
+          {node.metadata?.title || node.name}
         </span>
 
         {!node.is_file && isHovered && (
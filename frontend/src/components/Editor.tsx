@@ -87,6 +87,9 @@ export const Editor: React.FC<EditorProps> = ({ file, onSave, onDelete }) => {
     // Update fileExists whenever it changes (including after file creation)
     setFileExists(file.exists || false)
 
+    // Memory files from the list arrive without content; wait for it to load
+    if (file.exists && file.content === undefined) return
+
     // Only reload content when switching to a different file
     if (file.path !== lastLoadedPath.current) {
       console.log('Loading new file into editor:', file.path, 'exists:', file.exists)
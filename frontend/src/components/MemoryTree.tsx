@@ -1,5 +1,6 @@
-import React, { useState, useMemo } from 'react'
+import React, { useState, useMemo, useCallback } from 'react'
 import { DirectoryInfo, MemoryFile } from '../types'
+import { fetchTree } from '../api/client'
 import {
   HiChevronRight,
   HiChevronDown,
@@ -31,7 +32,23 @@ export const MemoryTree: React.FC<MemoryTreeProps> = ({
     return new Set(tree ? [tree.path] : [])
   })
 
-  const toggleExpanded = (path: string) => {
+  // Children fetched on demand for nodes the initial tree didn't include
+  const [loadedChildren, setLoadedChildren] = useState<Record<string, DirectoryInfo[]>>({})
+
+  const loadChildren = useCallback(async (node: DirectoryInfo) => {
+    try {
+      const subtree = await fetchTree({ path: node.path, depth: 1 })
+      setLoadedChildren(prev => ({ ...prev, [node.path]: subtree.children }))
+    } catch (error) {
+      console.error('Failed to load children for', node.path, error)
+    }
+  }, [])
+
+  const toggleExpanded = (node: DirectoryInfo) => {
+    const path = node.path
+    if (node.has_children && node.children.length === 0 && !loadedChildren[path]) {
+      loadChildren(node)
+    }
     setExpandedPaths(prev => {
       const next = new Set(prev)
       if (next.has(path)) {
@@ -54,6 +71,7 @@ export const MemoryTree: React.FC<MemoryTreeProps> = ({
       onSelectFile={onSelectFile}
       expandedPaths={expandedPaths}
       onToggleExpanded={toggleExpanded}
+      loadedChildren={loadedChildren}
       level={0}
     />
   )
@@ -66,7 +84,8 @@ interface TreeNodeProps {
   selectedPath: string | null
   onSelectFile: (file: MemoryFile | null) => void
   expandedPaths: Set<string>
-  onToggleExpanded: (path: string) => void
+  onToggleExpanded: (node: DirectoryInfo) => void
+  loadedChildren: Record<string, DirectoryInfo[]>
   level: number
 }
 
@@ -78,6 +97,7 @@ const TreeNode: React.FC<TreeNodeProps> = ({
   onSelectFile,
   expandedPaths,
   onToggleExpanded,
+  loadedChildren,
   level
 }) => {
   const memoryFile = useMemo(() => {
@@ -119,14 +139,15 @@ const TreeNode: React.FC<TreeNodeProps> = ({
   }, [memoryFiles, node.path])
 
   const isExpanded = expandedPaths.has(node.path)
-  const hasChildren = node.children.length > 0
+  const children = node.children.length > 0 ? node.children : loadedChildren[node.path] ?? []
+  const hasChildren = node.has_children || children.length > 0
   const isRecommended = recommendations.includes(node.path)
   const isSelected = selectedPath === memoryFile?.path
 
   const handleToggle = (e: React.MouseEvent) => {
     e.stopPropagation()
     if (hasChildren) {
-      onToggleExpanded(node.path)
+      onToggleExpanded(node)
     }
   }
 
@@ -136,7 +157,7 @@ const TreeNode: React.FC<TreeNodeProps> = ({
     const target = e.target as HTMLElement
     if (target.closest('.tree-node-chevron') || target.closest('.tree-node-icon')) {
       if (hasChildren) {
-        onToggleExpanded(node.path)
+        onToggleExpanded(node)
       }
       return
     }
@@ -157,6 +178,7 @@ const TreeNode: React.FC<TreeNodeProps> = ({
         path: newPath,
         content: '',
         content_html: '',
+        content_hash: '',
         exists: false,
         parent_path: normalizedPath
       }
@@ -226,7 +248,7 @@ const TreeNode: React.FC<TreeNodeProps> = ({
 
       {isExpanded && hasChildren && (
         <div className="tree-node-children">
-          {node.children.map(child => (
+          {children.map(child => (
             <TreeNode
               key={child.path}
               node={child}
@@ -236,6 +258,7 @@ const TreeNode: React.FC<TreeNodeProps> = ({
               onSelectFile={onSelectFile}
               expandedPaths={expandedPaths}
               onToggleExpanded={onToggleExpanded}
+              loadedChildren={loadedChildren}
               level={level + 1}
             />
           ))}
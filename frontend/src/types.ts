@@ -1,20 +1,53 @@
 // Core data models
+// Mirrors the Rust models; /api/v1/openapi.json is the source of truth
+export interface FileStats {
+  file_count: number
+  total_lines: number
+  depth: number
+}
+
 export interface DirectoryInfo {
   name: string
   path: string
   has_memory: boolean
-  should_recommend: boolean
-  file_count: number
-  total_lines: number
+  has_children: boolean  // Children may exist even when `children` is empty (not loaded yet)
+  should_recommend: boolean  // Complex enough to deserve a CLAUDE.md but has none
+  stats: FileStats
   children: DirectoryInfo[]
+  files?: string[]       // Only with include_files
+}
+
+export interface TocEntry {
+  level: number         // 1 for `#`, up to 6
+  text: string
+  anchor: string        // The heading's id in content_html
 }
 
 export interface MemoryFile {
   path: string
-  content: string
-  content_html: string
+  content?: string      // Omitted from the list endpoint; loaded per file on selection
+  content_html?: string
+  toc?: TocEntry[]      // Headings of content_html, with their anchor ids
+  content_hash: string
   exists: boolean
   parent_path: string
+  scope?: 'project' | 'directory' | 'local'
+  size?: number
+  modified_at?: string | null
+}
+
+export interface Staleness {
+  stale: boolean
+  newest_change: string | null
+  newest_change_at: string | null
+  days_behind: number
+}
+
+export interface MemoryFileDetail extends MemoryFile {
+  content: string
+  content_html: string
+  stats: FileStats
+  staleness: Staleness
 }
 
 // Application state
@@ -78,14 +111,134 @@ export interface DocsNode {
   path: string
   name: string
   is_file: boolean
+  is_root: boolean    // A documentation root such as "docs" or "packages/web/docs"
+  metadata?: DocMetadata  // Front matter, files only
   children: DocsNode[]
 }
 
+export interface DocMetadata {
+  title?: string
+  status?: string
+  owner?: string
+  tags?: string[]
+  updated?: string
+  [key: string]: unknown  // Any other front matter keys
+}
+
 export interface DocFile {
-  path: string        // Relative to docs/: "guides/setup.md" not "docs/guides/setup.md"
+  path: string        // Project-relative, starting with its doc root: "docs/guides/setup.md"
   content: string
   content_html: string
+  toc: TocEntry[]
+  content_hash: string  // SHA-256 of content, sent back as If-Match on save
   exists: boolean     // True when file exists on disk
+  metadata?: DocMetadata
+}
+
+// Move types
+export interface LinkChange {
+  line: number
+  before: string        // Link target as written
+  after: string
+}
+
+export interface MoveResult {
+  dry_run: boolean
+  moved: { from: string; to: string }[]         // Project-relative
+  rewrites: { path: string; changes: LinkChange[] }[]
+}
+
+// Search types
+export interface SearchHit {
+  kind: 'memory' | 'docs'
+  path: string          // Project-relative
+  scope: 'project' | 'directory' | 'local' | null
+  headings: string[]    // Enclosing headings, outermost first
+  line: number
+  snippet: string
+  highlights: [number, number][]  // Code point ranges in `snippet`
+  score: number
+}
+
+export interface SearchResponse {
+  query: string
+  total: number
+  results: SearchHit[]
+}
+
+// ADR types
+export type AdrStatus = 'proposed' | 'accepted' | 'superseded' | 'deprecated'
+
+export interface AdrSummary {
+  number: number
+  title: string
+  status: AdrStatus
+  path: string                  // Project-relative
+  date: string | null           // YYYY-MM-DD of the last status change
+  supersedes: number[]
+  superseded_by: number | null
+}
+
+// Plan types
+export interface PlanTask {
+  line: number            // 1-based, as sent back when toggling
+  text: string
+  checked: boolean
+  depth: number           // Nesting level, 0 for top-level items
+}
+
+export interface PlanSummary {
+  path: string            // Project-relative
+  title: string
+  status: string          // Declared by the plan, else 'not_started' | 'in_progress' | 'done'
+  total: number
+  done: number
+  open: number
+  percent: number
+  updated_at: string
+  content_hash: string
+  tasks?: PlanTask[]      // With include=tasks, and always for a single plan
+}
+
+// Link graph types
+export interface LinkInfo {
+  source: string          // Project-relative file containing the link
+  line: number
+  kind: 'inline' | 'reference' | 'import'
+  target: string          // As written
+  resolved: string | null // Project-relative target; null if it climbs out of the project
+  anchor: string | null
+  status: 'ok' | 'missing_file' | 'missing_anchor' | 'outside_project'
+}
+
+export interface BacklinksResponse {
+  path: string
+  backlinks: LinkInfo[]
+  outgoing: LinkInfo[]
+}
+
+export interface BrokenLinkReport {
+  files_scanned: number
+  links_checked: number
+  broken: LinkInfo[]
+}
+
+// Error types
+export type ErrorCode =
+  | 'invalid_request' | 'invalid_path' | 'path_outside_project' | 'path_outside_docs'
+  | 'not_a_memory_file' | 'missing_content' | 'invalid_metadata' | 'invalid_move'
+  | 'unresolved_conflicts' | 'empty_query' | 'not_a_task' | 'unauthorized' | 'permission_denied'
+  | 'not_found' | 'file_not_found' | 'directory_not_found' | 'chat_not_found'
+  | 'revision_not_found' | 'trash_entry_not_found' | 'adr_not_found' | 'plan_not_found'
+  | 'method_not_allowed' | 'file_exists' | 'hash_mismatch' | 'task_changed'
+  | 'invalid_transition' | 'base_hash_required' | 'io_error' | 'internal'
+
+export interface ErrorResponse {
+  error: string           // Human-readable, including its causes
+  code: ErrorCode         // Stable; branch on this rather than `error`
+  request_id?: string     // Also in the X-Request-Id header
+  details?: Record<string, unknown>
+  conflict?: unknown      // hash_mismatch only
 }
 
 // Editable file union type for Editor
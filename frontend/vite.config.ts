@@ -8,8 +8,13 @@ export default defineConfig({
     proxy: {
       '/api': {
         target: 'http://localhost:3999',
-        changeOrigin: true
-      }
+        changeOrigin: true,
+        // dev.sh shares one token between the backend and this proxy
+        headers: process.env.CC_ATLAS_TOKEN
+          ? { Authorization: `Bearer ${process.env.CC_ATLAS_TOKEN}` }
+          : undefined
+      },
+      '/highlight.css': 'http://localhost:3999'
     }
   }
 })
\ No newline at end of file
@@ -34,6 +34,9 @@ cleanup() {
 
 trap cleanup INT TERM
 
+# Share one API token between the backend and the Vite proxy
+export CC_ATLAS_TOKEN="${CC_ATLAS_TOKEN:-$(openssl rand -hex 32)}"
+
 # Change to cc-atlas root directory
 cd "$CC_ATLAS_ROOT"
 
@@ -43,9 +46,9 @@ if [ ! -d "frontend/node_modules" ]; then
     (cd frontend && npm install)
 fi
 
-# Start backend in development mode
+# Start backend in development mode, serving frontend/dist from disk rather than the embedded copy
 echo -e "${GREEN}Starting Rust backend (dev)...${NC}"
-cargo run -- serve --port $PORT --project "$PROJECT_PATH" &
+cargo run -- serve --port $PORT --project "$PROJECT_PATH" --frontend-dir frontend/dist &
 BACKEND_PID=$!
 
 sleep 2
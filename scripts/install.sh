@@ -26,17 +26,17 @@ if ! command -v npm &> /dev/null; then
     exit 1
 fi
 
-# Build the Rust binary in release mode
-echo -e "${BLUE}Building cc-atlas...${NC}"
-cd "$CC_ATLAS_ROOT"
-cargo build --release
-
-# Install frontend dependencies and build
+# Install frontend dependencies and build; the binary embeds the result
 echo -e "${BLUE}Building frontend...${NC}"
 cd "$CC_ATLAS_ROOT/frontend"
 npm install
 npm run build
 
+# Build the Rust binary in release mode
+echo -e "${BLUE}Building cc-atlas...${NC}"
+cd "$CC_ATLAS_ROOT"
+cargo build --release
+
 # Determine installation directory
 # Try user's cargo bin first (no sudo needed), then /usr/local/bin
 if [ -d "$HOME/.cargo/bin" ]; then
@@ -69,52 +69,28 @@ if ! command -v npm &> /dev/null; then
     exit 1
 fi
 
-# Function to cleanup on exit
-cleanup() {
-    echo -e "\n${YELLOW}Shutting down cc-atlas...${NC}"
-    kill $BACKEND_PID 2>/dev/null
-    exit
-}
-
-# Set up trap to cleanup on Ctrl+C
-trap cleanup INT TERM
-
 # Change to cc-atlas root directory
 cd "$CC_ATLAS_ROOT"
 
-# Build backend if needed
-echo -e "${BLUE}Building Rust backend...${NC}"
-cargo build --release
-
 # Install frontend dependencies if needed
 if [ ! -d "frontend/node_modules" ]; then
     echo -e "${BLUE}Installing frontend dependencies...${NC}"
     (cd frontend && npm install)
 fi
 
-# Build frontend if needed
+# Build frontend if needed; it must exist before the backend build embeds it
 if [ ! -d "frontend/dist" ]; then
     echo -e "${BLUE}Building frontend...${NC}"
     (cd frontend && npm run build)
 fi
 
-# Start backend (which now serves frontend too)
-echo -e "${GREEN}Starting cc-atlas server on port $PORT...${NC}"
-./target/release/cc-atlas serve --port $PORT --project "$PROJECT_PATH" &
-BACKEND_PID=$!
-
-# Wait a moment for backend to start
-sleep 2
+# Build backend if needed
+echo -e "${BLUE}Building Rust backend...${NC}"
+cargo build --release
 
-echo ""
-echo -e "${GREEN}═══════════════════════════════════════════${NC}"
-echo -e "${GREEN}cc-atlas is running!${NC}"
-echo -e "${GREEN}═══════════════════════════════════════════${NC}"
-echo -e "URL:      ${BLUE}http://localhost:$PORT${NC}"
-echo -e "Project:  ${BLUE}$PROJECT_PATH${NC}"
-echo ""
+# Run the server in the foreground; it serves the embedded frontend, logs the URL
+# (another port if $PORT is taken) and shuts down cleanly on Ctrl+C
+echo -e "${GREEN}Starting cc-atlas server...${NC}"
 echo -e "${YELLOW}Press Ctrl+C to stop${NC}"
 echo ""
-
-# Wait for backend process
-wait $BACKEND_PID
\ No newline at end of file
+exec ./target/release/cc-atlas serve --port $PORT --project "$PROJECT_PATH"
\ No newline at end of file
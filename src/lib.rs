@@ -1,5 +1,7 @@
 pub mod models;
+pub mod server;
+pub mod services;
 pub mod utils;
 
 #[cfg(test)]
-mod tests;
\ No newline at end of file
+mod tests;
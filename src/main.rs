@@ -1,14 +1,21 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{parser::ValueSource, CommandFactory, FromArgMatches, Parser, Subcommand};
+use serde::de::DeserializeOwned;
 
-mod models;
-mod server;
-mod services;
-mod utils;
+use cc_atlas::{models, server, services, utils};
 
-use services::{analyzer, chat_exporter};
-use models::ExportOptions;
-use std::path::PathBuf;
+use models::{
+    BacklinksResponse, BrokenLinkReport, ExportOptions, FileKind, LinkStatus, MemoryScope, RetentionPolicy, SearchHit,
+    SearchResponse, ServerInfo,
+};
+use server::telemetry::{self, LogFormat};
+use server::{auth, daemon, ServerConfig};
+use services::link_graph::LinkGraph;
+use services::{adr, analyzer, chat_exporter, history, search, trash};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use utils::adr::{parse_status, status_name};
+use utils::search::SearchFilters;
 
 #[derive(Parser)]
 #[command(name = "cc-atlas")]
@@ -16,6 +23,26 @@ use std::path::PathBuf;
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    #[arg(
+        long,
+        global = true,
+        env = "CC_ATLAS_LOG",
+        default_value = "info",
+        help = "Log level or filter, e.g. debug or cc_atlas=debug,tower=warn"
+    )]
+    log_level: String,
+
+    #[arg(long, global = true, env = "CC_ATLAS_LOG_FORMAT", value_enum, default_value_t = LogFormat::Pretty)]
+    log_format: LogFormat,
+
+    #[arg(
+        long,
+        global = true,
+        env = "CC_ATLAS_SOCKET",
+        help = "Unix socket: serve listens on it; status, stop, search and links ask the server behind it"
+    )]
+    socket: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -24,11 +51,53 @@ enum Commands {
     Serve {
         #[arg(long, default_value_t = 3999)]
         port: u16,
-        
+
         #[arg(short, long, default_value = ".")]
         project: String,
+
+        #[arg(long, default_value = "127.0.0.1", help = "Address to bind; use 0.0.0.0 to expose on the network")]
+        host: String,
+
+        #[arg(long, env = "CC_ATLAS_TOKEN", help = "Fixed API access token (random per launch by default)")]
+        token: Option<String>,
+
+        #[arg(long = "allow-origin", help = "Additional origin allowed to call the API (repeatable)")]
+        allow_origins: Vec<String>,
+
+        #[arg(
+            long,
+            help = "Serve the dashboard from this directory instead of the built-in copy (for frontend development)"
+        )]
+        frontend_dir: Option<PathBuf>,
+
+        #[arg(long, help = "Run in the background, logging to .cc-atlas/server.log")]
+        daemon: bool,
+
+        #[arg(long, default_value = "600", value_parser = parse_mode, help = "Permissions of the --socket file, in octal; 660 lets the group connect")]
+        socket_mode: u32,
+
+        #[arg(long, help = "Only listen on --socket, without opening a TCP port")]
+        no_tcp: bool,
+    },
+
+    /// Show whether a server is running for a project
+    Status {
+        #[arg(short, long, default_value = ".")]
+        project: PathBuf,
     },
-    
+
+    /// Stop the project's server, letting in-flight requests finish
+    Stop {
+        #[arg(short, long, default_value = ".")]
+        project: PathBuf,
+    },
+
+    /// Open the running server's dashboard in the browser
+    Open {
+        #[arg(short, long, default_value = ".")]
+        project: PathBuf,
+    },
+
     /// Analyze project without starting server
     Analyze {
         #[arg(default_value = ".")]
@@ -52,16 +121,241 @@ enum Commands {
         #[arg(short, long, help = "Custom name for exported file")]
         name: Option<String>,
     },
+
+    /// Browse and restore previous versions of memory and doc files
+    History {
+        #[command(subcommand)]
+        command: HistoryCommand,
+
+        #[arg(short, long, default_value = ".", global = true)]
+        project: PathBuf,
+    },
+
+    /// List, restore or purge deleted memory and doc files
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommand,
+
+        #[arg(short, long, default_value = ".", global = true)]
+        project: PathBuf,
+    },
+
+    /// Full-text search across memory files and docs
+    Search {
+        #[arg(required = true, num_args = 1.., help = "Words to search for")]
+        query: Vec<String>,
+
+        #[arg(long, value_parser = ["memory", "docs"], help = "Only search memory files or docs")]
+        kind: Option<String>,
+
+        #[arg(long, value_parser = ["project", "directory", "local"], help = "Only search memory files of this scope")]
+        scope: Option<String>,
+
+        #[arg(long, help = "Only search files at or below this project-relative directory")]
+        dir: Option<String>,
+
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+
+        #[arg(short, long, default_value = ".")]
+        project: PathBuf,
+    },
+
+    /// Create and track Architecture Decision Records
+    Adr {
+        #[command(subcommand)]
+        command: AdrCommand,
+
+        #[arg(short, long, default_value = ".", global = true)]
+        project: PathBuf,
+    },
+
+    /// Check links between memory files and docs
+    Links {
+        #[command(subcommand)]
+        command: LinksCommand,
+
+        #[arg(short, long, default_value = ".", global = true)]
+        project: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum AdrCommand {
+    /// List ADRs with their status
+    List,
+
+    /// Create the next-numbered ADR from the template
+    New {
+        #[arg(required = true, num_args = 1.., help = "Decision title")]
+        title: Vec<String>,
+
+        #[arg(long, help = "Number of an ADR this one supersedes (repeatable)")]
+        supersedes: Vec<u32>,
+    },
+
+    /// Change an ADR's status
+    Status {
+        number: u32,
+
+        #[arg(value_parser = ["proposed", "accepted", "superseded", "deprecated"])]
+        status: String,
+
+        #[arg(long, help = "The ADR that replaces this one, when superseding")]
+        by: Option<u32>,
+    },
+
+    /// Regenerate the ADR index page
+    Index,
+}
+
+#[derive(Subcommand)]
+enum LinksCommand {
+    /// List links to missing files or headings; exits non-zero if there are any
+    Broken,
+
+    /// Show which files link to a file, and what it links to
+    Backlinks {
+        #[arg(help = "Project-relative path")]
+        path: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum TrashCommand {
+    /// List deleted items, most recent first
+    List,
+
+    /// Move a deleted item back to its original path
+    Restore { id: String },
+
+    /// Permanently delete one item, or everything with --all
+    Purge {
+        #[arg(required_unless_present = "all")]
+        id: Option<String>,
+
+        #[arg(long, conflicts_with = "id")]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum HistoryCommand {
+    /// List revisions, newest first
+    List {
+        #[arg(help = "Only show revisions of this project-relative path")]
+        path: Option<String>,
+    },
+
+    /// Show a unified diff between two revisions
+    Diff {
+        from: String,
+
+        #[arg(default_value = "current", help = "Revision id, or \"current\" for the file on disk")]
+        to: String,
+    },
+
+    /// Restore a file to a previous revision
+    Restore { id: String },
+
+    /// Drop revisions outside the retention policy
+    Prune {
+        #[arg(long, default_value_t = RetentionPolicy::default().max_per_file)]
+        keep: usize,
+
+        #[arg(long, default_value_t = RetentionPolicy::default().max_age_days)]
+        max_age_days: u64,
+    },
+}
+
+impl Commands {
+    /// Whether the command listens on, or asks the server behind, `--socket`
+    fn uses_socket(&self) -> bool {
+        matches!(
+            self,
+            Commands::Serve { .. }
+                | Commands::Status { .. }
+                | Commands::Stop { .. }
+                | Commands::Search { .. }
+                | Commands::Links { .. }
+        )
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let cli = Cli::parse();
-    
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    telemetry::init_logging(&cli.log_level, cli.log_format)?;
+    let socket = cli.socket;
+    // CC_ATLAS_SOCKET may be exported for every command; only an explicit --socket that would be ignored is an error
+    if matches.value_source("socket") == Some(ValueSource::CommandLine)
+        && !cli.command.as_ref().is_none_or(Commands::uses_socket)
+    {
+        anyhow::bail!("--socket only applies to serve, status, stop, search and links");
+    }
+
     match cli.command {
-        Some(Commands::Serve { port, project }) => {
-            println!("Starting cc-atlas server on port {} for project: {}", port, project);
-            server::run(port, project).await?;
+        Some(Commands::Serve { daemon: true, project, token, no_tcp, .. }) => {
+            let root = Path::new(&project).canonicalize()?;
+            let token = match token {
+                Some(token) => token,
+                None => auth::generate_token()?,
+            };
+            let info = daemon::spawn(&root, &token)?;
+            println!("✅ cc-atlas running at {} (pid {})", daemon::address(&info), info.pid);
+            if !no_tcp && std::io::stdout().is_terminal() {
+                println!("   Token: {}", token);
+            }
+            println!("   Logs: {}", daemon::log_path(&root).display());
+        }
+        Some(Commands::Serve {
+            port,
+            project,
+            host,
+            token,
+            allow_origins,
+            frontend_dir,
+            daemon: false,
+            socket_mode,
+            no_tcp,
+        }) => {
+            server::run(ServerConfig {
+                host,
+                port,
+                project,
+                token,
+                allowed_origins: allow_origins,
+                frontend_dir,
+                socket,
+                socket_mode,
+                tcp: !no_tcp,
+            })
+            .await?;
+        }
+        Some(Commands::Status { project }) => {
+            handle_status(&project, socket.as_deref()).await?;
+        }
+        Some(Commands::Stop { project }) => {
+            handle_stop(&project, socket.as_deref()).await?;
+        }
+        Some(Commands::Open { project }) => {
+            let root = project.canonicalize()?;
+            let Some(info) = daemon::running(&root)? else {
+                anyhow::bail!(
+                    "cc-atlas is not running for {}; start it with `cc-atlas serve --daemon`",
+                    root.display()
+                );
+            };
+            let Some(url) = &info.url else {
+                anyhow::bail!(
+                    "cc-atlas for {} only listens on {}; there is no URL to open",
+                    info.project,
+                    daemon::address(&info)
+                );
+            };
+            println!("Opening {}", url);
+            daemon::open_browser(url)?;
         }
         Some(Commands::Analyze { path }) => {
             println!("Analyzing project at: {}", path);
@@ -73,16 +367,138 @@ async fn main() -> Result<()> {
         Some(Commands::ExportChat { identifier, project, name }) => {
             handle_export_chat(&identifier, &project, name.as_deref())?;
         }
+        Some(Commands::History { command, project }) => {
+            handle_history(command, &project)?;
+        }
+        Some(Commands::Trash { command, project }) => {
+            handle_trash(command, &project)?;
+        }
+        Some(Commands::Search { query, kind, scope, dir, limit, project }) => match socket.as_deref() {
+            // The server searches its own project, so --project doesn't apply
+            Some(socket) => {
+                let mut params = vec![("q", query.join(" ")), ("limit", limit.to_string())];
+                params.extend(kind.map(|k| ("kind", k)));
+                params.extend(scope.map(|s| ("scope", s)));
+                params.extend(dir.map(|d| ("dir", d)));
+                handle_remote_search(socket, &params).await?;
+            }
+            None => {
+                let filters = SearchFilters {
+                    kind: kind.map(|k| if k == "docs" { FileKind::Docs } else { FileKind::Memory }),
+                    scope: scope.map(|s| match s.as_str() {
+                        "project" => MemoryScope::Project,
+                        "local" => MemoryScope::Local,
+                        _ => MemoryScope::Directory,
+                    }),
+                    dir,
+                };
+                handle_search(&query.join(" "), &filters, limit, &project)?;
+            }
+        },
+        Some(Commands::Adr { command, project }) => {
+            handle_adr(command, &project)?;
+        }
+        Some(Commands::Links { command, project }) => {
+            handle_links(command, &project, socket.as_deref()).await?;
+        }
         None => {
-            println!("Starting cc-atlas server on default port 3999");
-            server::run(3999, ".".to_string()).await?;
+            server::run(ServerConfig {
+                host: "127.0.0.1".to_string(),
+                port: 3999,
+                project: ".".to_string(),
+                token: std::env::var("CC_ATLAS_TOKEN").ok(),
+                allowed_origins: Vec::new(),
+                frontend_dir: None,
+                socket,
+                socket_mode: 0o600,
+                tcp: true,
+            })
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_status(project: &Path, socket: Option<&Path>) -> Result<()> {
+    let info = match socket {
+        Some(socket) => remote_get::<ServerInfo>(socket, "/server").await.ok(),
+        None => daemon::running(&project.canonicalize()?)?,
+    };
+    let Some(info) = info else {
+        match socket {
+            Some(socket) => println!("No cc-atlas server is listening on {}", socket.display()),
+            None => println!("cc-atlas is not running for {}", project.canonicalize()?.display()),
+        }
+        std::process::exit(1);
+    };
+
+    println!("cc-atlas is running for {}", info.project);
+    if let Some(url) = &info.url {
+        println!("   URL:     {}", url);
+    }
+    if let Some(socket) = &info.socket {
+        println!("   Socket:  {}", socket.display());
+    }
+    println!("   PID:     {}", info.pid);
+    println!("   Started: {}", info.started_at);
+    if let Some(log_file) = &info.log_file {
+        println!("   Logs:    {}", log_file.display());
+    }
+
+    Ok(())
+}
+
+async fn handle_stop(project: &Path, socket: Option<&Path>) -> Result<()> {
+    let stopped = match socket {
+        Some(socket) => {
+            let info: ServerInfo = remote_post(socket, "/server/shutdown").await?;
+            daemon::wait_for_exit(info.pid)?;
+            Some(info)
         }
+        None => daemon::stop(&project.canonicalize()?)?,
+    };
+
+    match stopped {
+        Some(info) => println!("✅ Stopped cc-atlas (pid {}) for {}", info.pid, info.project),
+        None => println!("cc-atlas is not running for {}", project.canonicalize()?.display()),
     }
-    
     Ok(())
 }
 
-fn handle_list_chats(project: &PathBuf) -> Result<()> {
+/// `GET` from the server listening on `--socket`
+#[cfg(unix)]
+async fn remote_get<T: DeserializeOwned>(socket: &Path, path: &str) -> Result<T> {
+    server::socket::get(socket, path).await
+}
+
+#[cfg(not(unix))]
+async fn remote_get<T: DeserializeOwned>(_socket: &Path, _path: &str) -> Result<T> {
+    anyhow::bail!("--socket is only supported on Unix")
+}
+
+/// `POST` to the server listening on `--socket`
+#[cfg(unix)]
+async fn remote_post<T: DeserializeOwned>(socket: &Path, path: &str) -> Result<T> {
+    server::socket::post(socket, path).await
+}
+
+#[cfg(not(unix))]
+async fn remote_post<T: DeserializeOwned>(_socket: &Path, _path: &str) -> Result<T> {
+    anyhow::bail!("--socket is only supported on Unix")
+}
+
+/// Parse a file mode written in octal, as `chmod` takes it
+fn parse_mode(value: &str) -> Result<u32, String> {
+    let mode = u32::from_str_radix(value.trim_start_matches("0o"), 8)
+        .map_err(|_| format!("{:?} is not an octal file mode", value))?;
+    if mode > 0o777 {
+        return Err(format!("{:o} is not a file mode", mode));
+    }
+    Ok(mode)
+}
+
+fn handle_list_chats(project: &Path) -> Result<()> {
     let chats = chat_exporter::find_project_chats(project)?;
 
     if chats.is_empty() {
@@ -101,7 +517,7 @@ fn handle_list_chats(project: &PathBuf) -> Result<()> {
     Ok(())
 }
 
-fn handle_export_chat(identifier: &str, project: &PathBuf, custom_name: Option<&str>) -> Result<()> {
+fn handle_export_chat(identifier: &str, project: &Path, custom_name: Option<&str>) -> Result<()> {
     // Check if identifier is a number (index) or session ID
     let session_id = if let Ok(index) = identifier.parse::<usize>() {
         // It's an index - look up the session ID
@@ -117,18 +533,238 @@ fn handle_export_chat(identifier: &str, project: &PathBuf, custom_name: Option<&
         identifier.to_string()
     };
 
-    let result = chat_exporter::export_chat(
-        &session_id,
-        project,
-        &ExportOptions::default(),
-        custom_name
-    )?;
-
-    println!("✅ Exported {} message{} to:",
-        result.message_count,
-        if result.message_count == 1 { "" } else { "s" }
-    );
+    let result = chat_exporter::export_chat(&session_id, project, &ExportOptions::default(), custom_name)?;
+
+    println!("✅ Exported {} message{} to:", result.message_count, if result.message_count == 1 { "" } else { "s" });
     println!("   {}", result.output_path.display());
 
     Ok(())
-}
\ No newline at end of file
+}
+
+fn handle_history(command: HistoryCommand, project: &Path) -> Result<()> {
+    match command {
+        HistoryCommand::List { path } => {
+            let revisions = history::list_revisions(project, path.as_deref())?;
+
+            if revisions.is_empty() {
+                println!("No revisions recorded.");
+                return Ok(());
+            }
+
+            for revision in revisions {
+                println!(
+                    "{}  {}  {:<8} {} ({} bytes, {})",
+                    revision.id, revision.created_at, revision.operation, revision.path, revision.size, revision.source
+                );
+            }
+        }
+        HistoryCommand::Diff { from, to } => {
+            let diff = history::diff_revisions(project, &from, &to)?;
+            print!("{}", diff.unified);
+        }
+        HistoryCommand::Restore { id } => {
+            let restored = history::restore_revision(project, &id, "cli")?;
+            println!("✅ Restored {} to revision {}", restored.revision.path, restored.revision.id);
+        }
+        HistoryCommand::Prune { keep, max_age_days } => {
+            let policy = RetentionPolicy { max_per_file: keep, max_age_days };
+            let removed = history::prune(project, &policy, None)?;
+            println!("Pruned {} revision{}", removed, if removed == 1 { "" } else { "s" });
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_trash(command: TrashCommand, project: &Path) -> Result<()> {
+    match command {
+        TrashCommand::List => {
+            let entries = trash::list_trash(project)?;
+
+            if entries.is_empty() {
+                println!("Trash is empty.");
+                return Ok(());
+            }
+
+            for entry in entries {
+                println!("{}  {}  {}{}", entry.id, entry.deleted_at, entry.path, if entry.is_dir { "/" } else { "" });
+            }
+        }
+        TrashCommand::Restore { id } => {
+            let entry = trash::restore(project, &id)?;
+            println!("✅ Restored {}", entry.path);
+        }
+        TrashCommand::Purge { id, all: _ } => {
+            let purged = trash::purge(project, id.as_deref())?;
+            println!("Purged {} item{}", purged.len(), if purged.len() == 1 { "" } else { "s" });
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_adr(command: AdrCommand, project: &Path) -> Result<()> {
+    match command {
+        AdrCommand::List => {
+            let adrs = adr::list(project)?;
+
+            if adrs.is_empty() {
+                println!("No ADRs in {}.", adr::adr_dir(project)?.display());
+                return Ok(());
+            }
+
+            for record in adrs {
+                let note = record.superseded_by.map(|n| format!(" by {:04}", n)).unwrap_or_default();
+                println!(
+                    "{:04}  {:<11} {}  ({}{})",
+                    record.number,
+                    status_name(record.status),
+                    record.title,
+                    record.path,
+                    note
+                );
+            }
+        }
+        AdrCommand::New { title, supersedes } => {
+            let record = adr::create(project, &title.join(" "), &supersedes, "cli")?;
+            println!("✅ Created ADR {:04}: {}", record.number, record.path);
+        }
+        AdrCommand::Status { number, status, by } => {
+            let status = parse_status(&status).expect("validated by clap");
+            let record = adr::set_status(project, number, status, by, "cli")?;
+            println!("✅ ADR {:04} is now {}", record.number, status_name(record.status));
+        }
+        AdrCommand::Index => {
+            let index = adr::write_index(project)?;
+            println!("✅ Wrote {}", index.display());
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_links(command: LinksCommand, project: &Path, socket: Option<&Path>) -> Result<()> {
+    match command {
+        LinksCommand::Broken => {
+            let report: BrokenLinkReport = match socket {
+                Some(socket) => remote_get(socket, "/links/broken").await?,
+                None => LinkGraph::build(project)?.broken_report(),
+            };
+            for link in &report.broken {
+                println!("{}:{}  {}  ({})", link.source, link.line, link.target, describe_status(link.status));
+            }
+
+            println!(
+                "{} broken of {} links in {} files",
+                report.broken.len(),
+                report.links_checked,
+                report.files_scanned
+            );
+            if !report.broken.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        LinksCommand::Backlinks { path } => {
+            let response: BacklinksResponse = match socket {
+                Some(socket) => {
+                    let query = serde_urlencoded::to_string([("path", &path)])?;
+                    remote_get(socket, &format!("/links/backlinks?{}", query)).await?
+                }
+                None => LinkGraph::build(project)?.backlinks(&path),
+            };
+
+            if response.backlinks.is_empty() {
+                println!("Nothing links to {}.", response.path);
+            }
+            for link in &response.backlinks {
+                println!("← {}:{}  {}", link.source, link.line, link.target);
+            }
+            for link in &response.outgoing {
+                let note = match link.status {
+                    LinkStatus::Ok => String::new(),
+                    status => format!("  ({})", describe_status(status)),
+                };
+                println!("→ {}:{}  {}{}", link.source, link.line, link.target, note);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn describe_status(status: LinkStatus) -> &'static str {
+    match status {
+        LinkStatus::Ok => "ok",
+        LinkStatus::MissingFile => "missing file",
+        LinkStatus::MissingAnchor => "missing heading",
+        LinkStatus::OutsideProject => "outside project",
+    }
+}
+
+fn handle_search(query: &str, filters: &SearchFilters, limit: usize, project: &Path) -> Result<()> {
+    let index = search::build_index(project)?;
+    if index.is_empty() {
+        println!("No memory files or docs to search.");
+        return Ok(());
+    }
+
+    let (total, hits) = index.search(query, filters, limit);
+    if hits.is_empty() {
+        println!("No matches for \"{}\" in {} files.", query, index.len());
+        return Ok(());
+    }
+
+    print_hits(&hits, total);
+    Ok(())
+}
+
+async fn handle_remote_search(socket: &Path, params: &[(&str, String)]) -> Result<()> {
+    let query = serde_urlencoded::to_string(params)?;
+    let response: SearchResponse = remote_get(socket, &format!("/search?{}", query)).await?;
+    if response.results.is_empty() {
+        println!("No matches for \"{}\".", response.query);
+        return Ok(());
+    }
+
+    print_hits(&response.results, response.total);
+    Ok(())
+}
+
+fn print_hits(hits: &[SearchHit], total: usize) {
+    let color = std::io::stdout().is_terminal();
+    for hit in hits {
+        let location = format!("{}:{}", hit.path, hit.line);
+        if hit.headings.is_empty() {
+            println!("{}", location);
+        } else {
+            println!("{}  ({})", location, hit.headings.join(" › "));
+        }
+        println!("    {}", emphasize(hit, color));
+    }
+
+    if total > hits.len() {
+        println!("\nShowing {} of {} matches; use --limit to see more.", hits.len(), total);
+    }
+}
+
+/// Render a snippet with its highlighted ranges in bold when writing to a terminal
+fn emphasize(hit: &SearchHit, color: bool) -> String {
+    if !color {
+        return hit.snippet.clone();
+    }
+
+    let mut out = String::new();
+    for (i, c) in hit.snippet.chars().enumerate() {
+        if hit.highlights.iter().any(|[_, end]| *end == i) {
+            out.push_str("\x1b[0m");
+        }
+        if hit.highlights.iter().any(|[start, _]| *start == i) {
+            out.push_str("\x1b[1;33m");
+        }
+        out.push(c);
+    }
+    if hit.highlights.iter().any(|[_, end]| *end == hit.snippet.chars().count()) {
+        out.push_str("\x1b[0m");
+    }
+    out
+}
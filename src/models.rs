@@ -1,64 +1,675 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
+use utoipa::ToSchema;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryFile {
     pub path: PathBuf,
     pub content: String,
-    pub content_html: Option<String>,  // Cached HTML version
+    pub content_html: Option<String>, // Cached HTML version
     pub relative_path: String,
+    pub content_hash: String,
     pub stats: FileStats,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct MemoryFileResponse {
     pub path: String,
-    pub content: String,
-    pub content_html: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>, // Only included on request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_html: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub toc: Option<Vec<TocEntry>>, // Headings of content_html, with their anchors
+    pub content_hash: String,
     pub exists: bool,
     pub parent_path: String,
+    pub scope: MemoryScope,
+    pub size: u64,                   // Bytes on disk
+    pub modified_at: Option<String>, // RFC 3339
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A heading in rendered markdown, in document order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TocEntry {
+    pub level: u8,      // 1 for `#`, up to 6
+    pub text: String,   // Plain text, without inline markup
+    pub anchor: String, // The heading's `id`, for `#anchor` links
+}
+
+/// Full view of a single memory file
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MemoryFileDetail {
+    #[serde(flatten)]
+    pub file: MemoryFileResponse,
+    pub stats: FileStats, // Stats of the directory the file documents
+    pub staleness: Staleness,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryScope {
+    Project,   // CLAUDE.md at the project root
+    Directory, // CLAUDE.md in a subdirectory
+    Local,     // CLAUDE.local.md, personal and usually git-ignored
+}
+
+/// How far the code around a memory file has moved on since it was last edited
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Staleness {
+    pub stale: bool,
+    pub newest_change: Option<String>, // Most recently modified file next to or below it
+    pub newest_change_at: Option<String>, // RFC 3339
+    pub days_behind: u64,              // Days between the memory file and that change
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct FileStats {
     pub file_count: usize,
     pub total_lines: usize,
     pub depth: usize,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DirectoryInfo {
+    #[schema(value_type = String)]
     pub path: PathBuf,
     pub name: String,
     pub has_memory: bool,
+    pub has_children: bool,     // True even when `children` was not loaded
+    pub should_recommend: bool, // Complex enough to deserve a CLAUDE.md but has none
+    #[schema(no_recursion)]
     pub children: Vec<DirectoryInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub files: Option<Vec<String>>, // File names, only when requested
     pub stats: FileStats,
 }
 
+// ===== File Write Models =====
+
+/// Body of a memory file or doc create/update; send `content` (markdown) or `content_html`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct SaveFileRequest {
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub content_html: Option<String>, // From the rich editor; converted to markdown
+    #[serde(default)]
+    pub base_hash: Option<String>, // Updates only, unless sent as If-Match
+    #[serde(default)]
+    pub metadata: Option<DocMetadata>, // Docs only; replaces the front matter
+}
+
+/// A save as it ended up on disk
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SavedFile {
+    pub content: String,
+    pub content_hash: String, // Also sent as the ETag
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreatedFile {
+    pub path: String,
+    pub content: String,
+    pub content_hash: String,
+    pub created: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeletedFile {
+    pub path: String,
+    pub deleted: bool,
+    pub trash_id: String, // Restore with POST /trash/{id}/restore
+}
+
 // ===== Docs Models =====
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DocsNode {
-    pub path: String,           // Relative to docs/
-    pub name: String,           // Display name (no .md suffix for files)
+    pub path: String, // Relative to the project root
+    pub name: String, // Display name (no .md suffix for files)
     pub is_file: bool,
+    #[serde(default)]
+    pub is_root: bool, // A documentation root rather than a folder inside one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<DocMetadata>, // Front matter, files only
+    #[schema(no_recursion)]
     pub children: Vec<DocsNode>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DocFileResponse {
     pub path: String,
-    pub content: String,
-    pub content_html: String,
+    pub content: String,      // Full file, front matter included
+    pub content_html: String, // Body only
+    pub toc: Vec<TocEntry>,
+    pub content_hash: String,
     pub exists: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<DocMetadata>,
 }
 
-// ===== Chat Export Models =====
+/// YAML front matter of a doc
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DocMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated: Option<String>, // As written, usually YYYY-MM-DD
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>, // Any other keys, kept for round-tripping
+}
+
+/// Front matter field the docs tree can be sorted by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DocSortField {
+    #[default]
+    Name,
+    Title,
+    Status,
+    Owner,
+    Updated,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+// ===== Config Models =====
+
+/// Per-project settings read from `.cc-atlas/config.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectConfig {
+    #[serde(default)]
+    pub docs_roots: Option<Vec<DocRootConfig>>, // Auto-detected when absent
+    #[serde(default)]
+    pub adr_dir: Option<String>, // Project-relative; found among the doc roots when absent
+    #[serde(default)]
+    pub plans_dir: Option<String>, // Likewise, defaulting to `docs/plans`
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocRootConfig {
+    pub path: String, // Project-relative directory
+    #[serde(default)]
+    pub name: Option<String>, // Display name; defaults to the path
+}
+
+// ===== Change Detection Models =====
+
+/// Returned with a 409 when a save was based on a stale version of the file
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FileConflict {
+    pub path: String,
+    pub base_hash: String,
+    pub base_content: Option<String>, // None if the server no longer knows the base
+    pub disk_hash: Option<String>,
+    pub disk_content: Option<String>, // None if the file was deleted externally
+    pub submitted_content: String,
+    pub merge: Option<MergeResult>, // Attempted three-way merge when all versions are known
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct CheckAllRequest {
+    #[serde(default)]
+    pub memory: HashMap<String, String>, // Relative path -> last known hash
+    #[serde(default)]
+    pub docs: HashMap<String, String>, // Relative to docs/ -> last known hash
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FileHashStatus {
+    pub path: String,
+    pub kind: FileKind,
+    pub current_hash: Option<String>, // None if the file no longer exists
+    pub changed: bool,
+}
+
+// ===== Error Models =====
+
+/// Stable, machine-readable reason for a failed request; messages may change, codes don't
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidRequest, // Malformed body, query or path parameters
+    InvalidPath,    // Absolute, empty or otherwise unusable path
+    PathOutsideProject,
+    PathOutsideDocs, // Not inside any documentation root
+    NotAMemoryFile,
+    MissingContent,  // Neither `content` nor `content_html` given
+    InvalidMetadata, // Front matter that can't be read or written
+    InvalidMove,
+    UnresolvedConflicts,
+    EmptyQuery,
+    NotATask,
+    Unauthorized,
+    PermissionDenied,
+    NotFound, // No such endpoint
+    FileNotFound,
+    DirectoryNotFound,
+    ChatNotFound,
+    RevisionNotFound,
+    TrashEntryNotFound,
+    AdrNotFound,
+    PlanNotFound,
+    MethodNotAllowed,
+    FileExists,
+    HashMismatch, // Changed on disk since the version the edit is based on
+    TaskChanged,
+    InvalidTransition,
+    BaseHashRequired,
+    IoError,
+    Internal,
+}
+
+/// Body of every error response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ErrorResponse {
+    pub error: String, // Human-readable message, including its causes
+    pub code: ErrorCode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>, // Also sent as X-Request-Id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>, // Extra fields for some codes, e.g. the conflicting `path`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conflict: Option<FileConflict>, // `hash_mismatch` only
+}
+
+// ===== Merge Models =====
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MergeRequest {
+    pub base: String,
+    pub mine: String,
+    pub theirs: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MergeResult {
+    pub clean: bool,
+    pub content: String, // Merged text, with conflict markers if not clean
+    pub conflicts: Vec<MergeConflict>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MergeConflict {
+    pub id: usize,
+    pub start_line: usize, // 1-based line of the `<<<<<<<` marker in `content`
+    pub base: String,
+    pub mine: String,
+    pub theirs: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HunkChoice {
+    Mine,
+    Theirs,
+    Base,
+    Both, // Mine followed by theirs
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HunkResolution {
+    pub hunk: usize,
+    pub take: Option<HunkChoice>,
+    pub content: Option<String>, // Hand-edited replacement, overrides `take`
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolveStrategy {
+    KeepMine,
+    TakeTheirs,
+    Merge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FileKind {
+    #[default]
+    Memory,
+    Docs,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ResolveRequest {
+    #[serde(default)]
+    pub kind: FileKind,
+    pub path: String,
+    pub strategy: ResolveStrategy,
+    pub base: String,
+    pub mine: String,
+    #[serde(default)]
+    pub disk_hash: Option<String>, // Version of "theirs" the user resolved against; else If-Match
+    #[serde(default)]
+    pub resolutions: Vec<HunkResolution>,
+}
+
+// ===== History Models =====
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Revision {
+    pub id: String,
+    pub path: String,       // Relative to the project root
+    pub hash: String,       // Content address in the object store
+    pub timestamp: u64,     // Unix seconds
+    pub created_at: String, // RFC 3339, for display
+    pub operation: String,  // What was about to happen: update, delete, resolve, restore
+    pub source: String,     // "api" or "cli"
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RevisionContent {
+    pub revision: Revision,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RevisionDiff {
+    pub path: String,
+    pub from: String,
+    pub to: String, // Revision id, or "current" for the file on disk
+    pub unified: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub max_per_file: usize,
+    pub max_age_days: u64,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self { max_per_file: 50, max_age_days: 90 }
+    }
+}
+
+// ===== Move Models =====
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MoveRequest {
+    #[serde(default)]
+    pub kind: FileKind,
+    pub from: String, // Same form as the memory or docs endpoints use
+    pub to: String,
+    #[serde(default)]
+    pub dry_run: bool, // Report what would change without touching disk
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MoveResult {
+    pub dry_run: bool,
+    pub moved: Vec<MovedPath>,
+    pub rewrites: Vec<FileRewrite>, // Files whose links or @imports were updated
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MovedPath {
+    pub from: String, // Project-relative
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FileRewrite {
+    pub path: String, // Project-relative, after the move
+    pub changes: Vec<LinkChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LinkChange {
+    pub line: usize,    // 1-based
+    pub before: String, // Link target as written
+    pub after: String,
+}
+
+// ===== Batch Models =====
+
+/// One step of a batch edit; memory file paths are project-relative
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BatchOperation {
+    Create { path: String, content: String },
+    Update { path: String, content: String, base_hash: String },
+    Delete { path: String, base_hash: String },
+    Move { from: String, to: String, base_hash: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchRequest {
+    pub operations: Vec<BatchOperation>, // Applied in order; later ones see earlier ones' results
+    #[serde(default)]
+    pub dry_run: bool, // Validate and preview without touching disk
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchChange {
+    Created,
+    Updated,
+    Deleted,
+    Moved,
+}
+
+/// The net effect of a batch on one file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BatchFileChange {
+    pub path: String,
+    pub change: BatchChange,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>, // Where a moved file was before the batch
+    pub content_hash: Option<String>, // None once deleted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trash_id: Option<String>, // Where a deleted file went; not set on dry runs
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchResult {
+    pub dry_run: bool,
+    pub files: Vec<BatchFileChange>, // Sorted by path
+    pub diff: String,                // Unified diff of every changed file
+}
+
+// ===== ADR Models =====
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AdrStatus {
+    Proposed,
+    Accepted,
+    Superseded,
+    Deprecated,
+}
+
+/// An Architecture Decision Record as listed in the index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AdrSummary {
+    pub number: u32,
+    pub title: String,
+    pub status: AdrStatus,
+    pub path: String,         // Project-relative
+    pub date: Option<String>, // YYYY-MM-DD of the last status change
+    pub supersedes: Vec<u32>,
+    pub superseded_by: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CreateAdrRequest {
+    pub title: String,
+    #[serde(default)]
+    pub supersedes: Vec<u32>, // Earlier ADRs this one replaces
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct AdrStatusRequest {
+    pub status: AdrStatus,
+    #[serde(default)]
+    pub superseded_by: Option<u32>, // Required when moving to `superseded`
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AdrIndexResponse {
+    pub path: String, // Project-relative index page
+}
+
+// ===== Plan Models =====
+
+/// A task-list checkbox in a plan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PlanTask {
+    pub line: usize, // 1-based
+    pub text: String,
+    pub checked: bool,
+    pub depth: usize, // Nesting level, 0 for top-level items
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PlanSummary {
+    pub path: String, // Project-relative
+    pub title: String,
+    pub status: String, // From the plan if it declares one, else not_started, in_progress or done
+    pub total: usize,
+    pub done: usize,
+    pub open: usize,
+    pub percent: u8,        // Rounded down, so 100 only when everything is done
+    pub updated_at: String, // File modification time, RFC 3339
+    pub content_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tasks: Option<Vec<PlanTask>>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ToggleTaskRequest {
+    pub path: String,
+    pub line: usize,
+    #[serde(default)]
+    pub checked: Option<bool>, // Flips the box when omitted
+    #[serde(default)]
+    pub text: Option<String>, // Task text as last seen; refused if the line now says something else
+    #[serde(default)]
+    pub base_hash: Option<String>, // Or an If-Match header; refused if the file has changed since
+}
+
+// ===== Link Graph Models =====
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkKind {
+    Inline,    // [text](target) and ![alt](target)
+    Reference, // [id]: target
+    Import,    // @path in memory files
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkStatus {
+    Ok,
+    MissingFile,
+    MissingAnchor,  // File exists but has no heading with that slug
+    OutsideProject, // Climbs above the project root
+}
+
+/// One link from a memory file or doc, resolved against the filesystem
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LinkInfo {
+    pub source: String, // Project-relative file containing the link
+    pub line: usize,
+    pub kind: LinkKind,
+    pub target: String,           // As written
+    pub resolved: Option<String>, // Project-relative target path; the source itself for `#anchor` links
+    pub anchor: Option<String>,   // Fragment without the `#`
+    pub status: LinkStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BacklinksResponse {
+    pub path: String,
+    pub backlinks: Vec<LinkInfo>, // Links elsewhere pointing at this file
+    pub outgoing: Vec<LinkInfo>,  // Links in this file
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BrokenLinkReport {
+    pub files_scanned: usize,
+    pub links_checked: usize,
+    pub broken: Vec<LinkInfo>,
+}
+
+// ===== Search Models =====
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SearchHit {
+    pub kind: FileKind,
+    pub path: String,               // Same form as the memory or docs endpoints use
+    pub scope: Option<MemoryScope>, // Memory files only
+    pub headings: Vec<String>,      // Headings enclosing the match, outermost first
+    pub line: usize,                // 1-based line of the snippet
+    pub snippet: String,
+    pub highlights: Vec<[usize; 2]>, // [start, end) char ranges in `snippet` to emphasise
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SearchResponse {
+    pub query: String,
+    pub total: usize, // Matches before `limit` was applied
+    pub results: Vec<SearchHit>,
+}
+
+// ===== Trash Models =====
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TrashEntry {
+    pub id: String,
+    pub path: String, // Original location, relative to the project root
+    pub is_dir: bool,
+    pub timestamp: u64,     // Unix seconds of deletion
+    pub deleted_at: String, // RFC 3339, for display
+    pub source: String,     // "api" or "cli"
+}
+
+// ===== Server Models =====
+
+/// A running server, as recorded in `.cc-atlas/server.json` and served at `/server`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ServerInfo {
+    pub pid: u32,
+    pub project: String,
+    pub started_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>, // None when only listening on a socket
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub socket: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub log_file: Option<PathBuf>, // Set for servers started with --daemon
+}
+
+// ===== Chat Export Models =====
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ChatMetadata {
     pub session_id: String,
+    #[schema(value_type = String)]
     pub file_path: PathBuf,
     pub title: String,
     pub message_count: usize,
@@ -76,17 +687,37 @@ pub struct ExportOptions {
 
 impl Default for ExportOptions {
     fn default() -> Self {
-        Self {
-            include_tools: true,
-            include_timestamps: false,
-            include_thinking: false,
-            max_tool_files: 5,
+        Self { include_tools: true, include_timestamps: false, include_thinking: false, max_tool_files: 5 }
+    }
+}
+
+/// Export settings; anything omitted keeps its default
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ExportChatRequest {
+    #[serde(default)]
+    pub include_tools: Option<bool>, // Default true
+    #[serde(default)]
+    pub include_timestamps: Option<bool>, // Default true
+    #[serde(default)]
+    pub include_thinking: Option<bool>, // Default false
+    #[serde(default)]
+    pub max_tool_files: Option<usize>, // Default 5
+}
+
+impl ExportChatRequest {
+    pub fn options(&self) -> ExportOptions {
+        ExportOptions {
+            include_tools: self.include_tools.unwrap_or(true),
+            include_timestamps: self.include_timestamps.unwrap_or(true),
+            include_thinking: self.include_thinking.unwrap_or(false),
+            max_tool_files: self.max_tool_files.unwrap_or(5),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ExportResult {
+    #[schema(value_type = String)]
     pub output_path: PathBuf,
     pub message_count: usize,
     pub export_size: u64,
@@ -113,7 +744,7 @@ pub struct ChatEntry {
 #[derive(Debug, Clone, Deserialize)]
 pub struct MessageContent {
     pub role: String,
-    pub content: Value,  // Can be string or array
+    pub content: Value, // Can be string or array
     pub model: Option<String>,
 }
 
@@ -140,4 +771,4 @@ pub struct SessionMetadata {
     pub message_count: usize,
     pub models_used: Vec<String>,
     pub date_range: (String, String),
-}
\ No newline at end of file
+}
@@ -0,0 +1,70 @@
+use axum::{
+    extract::{Request, State},
+    http::header,
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+use super::{error::ServerError, AppState};
+use crate::models::ErrorCode;
+
+const TOKEN_BYTES: usize = 32;
+
+/// Names this machine is always reachable under; others must be the configured `--host`
+pub const LOOPBACK_HOSTS: [&str; 3] = ["localhost", "127.0.0.1", "[::1]"];
+
+/// Generate a random per-launch access token
+pub fn generate_token() -> anyhow::Result<String> {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    getrandom::getrandom(&mut bytes).map_err(|e| anyhow::anyhow!("Failed to generate access token: {}", e))?;
+
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Marks requests that arrived over the Unix socket, where the socket's file permissions decide who may connect
+#[derive(Debug, Clone, Copy)]
+pub struct TrustedTransport;
+
+/// Reject `/api` requests that don't carry the launch token as a bearer token, unless they came over the socket
+pub async fn require_token(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ServerError> {
+    if request.extensions().get::<TrustedTransport>().is_some() {
+        return Ok(next.run(request).await);
+    }
+
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim);
+
+    match presented {
+        Some(token) if tokens_match(token, &state.access_token) => Ok(next.run(request).await),
+        Some(_) => Err(ServerError::new(ErrorCode::Unauthorized, "Invalid access token")),
+        None => Err(ServerError::new(ErrorCode::Unauthorized, "Missing access token")),
+    }
+}
+
+/// Compare without short-circuiting so timing doesn't leak a matching prefix
+fn tokens_match(presented: &str, expected: &str) -> bool {
+    presented.len() == expected.len()
+        && presented.bytes().zip(expected.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Whether a request's `Host` names this server, rather than, say, a rebound DNS name an
+/// attacker's page resolves to this machine. The port is ignored.
+pub fn is_allowed_host(host: Option<&str>, allowed: &[String]) -> bool {
+    let Some(host) = host else {
+        return false;
+    };
+    let name = match host.find(']') {
+        Some(end) if host.starts_with('[') => &host[..=end],
+        _ => host.split(':').next().unwrap_or_default(),
+    };
+    allowed.iter().any(|a| a.eq_ignore_ascii_case(name))
+}
@@ -0,0 +1,300 @@
+use anyhow::{Context, Result};
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::models::ServerInfo;
+use crate::utils::{time, writer};
+
+const STATE_DIR: &str = ".cc-atlas";
+const PID_FILE: &str = "server.pid";
+const INFO_FILE: &str = "server.json";
+const LOG_FILE: &str = "server.log";
+
+/// How long `serve --daemon` waits for the server to come up, and `stop` for it to exit
+const START_TIMEOUT: Duration = Duration::from_secs(15);
+const STOP_TIMEOUT: Duration = Duration::from_secs(15);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Set for the background process, so it records its log file
+const DAEMON_ENV: &str = "CC_ATLAS_DAEMONIZED";
+/// Read by `serve --token`
+const TOKEN_ENV: &str = "CC_ATLAS_TOKEN";
+
+/// Whether this process was started by `serve --daemon`
+pub fn is_daemon() -> bool {
+    std::env::var_os(DAEMON_ENV).is_some()
+}
+
+pub fn log_path(project_root: &Path) -> PathBuf {
+    project_root.join(STATE_DIR).join(LOG_FILE)
+}
+
+/// Fail if another live server already serves the project
+pub fn ensure_not_running(project_root: &Path) -> Result<()> {
+    match running(project_root)? {
+        Some(existing) => anyhow::bail!(
+            "cc-atlas is already running for this project at {} (pid {}); stop it with `cc-atlas stop`",
+            address(&existing),
+            existing.pid
+        ),
+        None => Ok(()),
+    }
+}
+
+/// Describe this process as the project's server, listening on `tcp` and/or `socket`
+pub fn describe(project_root: &Path, tcp: Option<(&str, u16)>, socket: Option<&Path>, daemon: bool) -> ServerInfo {
+    let log_file = log_path(project_root);
+    ServerInfo {
+        pid: std::process::id(),
+        project: project_root.to_string_lossy().to_string(),
+        started_at: time::format_rfc3339(time::now_unix()),
+        url: tcp.map(|(host, port)| format!("http://{}:{}", browser_host(host), port)),
+        host: tcp.map(|(host, _)| host.to_string()),
+        port: tcp.map(|(_, port)| port),
+        socket: socket.map(Path::to_path_buf),
+        log_file: daemon.then_some(log_file),
+    }
+}
+
+/// Record the server in the pidfile and `server.json`, for `status`, `stop` and `open`
+pub fn register(project_root: &Path, info: &ServerInfo) -> Result<()> {
+    let dir = project_root.join(STATE_DIR);
+    writer::write_atomic(&dir.join(INFO_FILE), serde_json::to_string_pretty(info)?.as_bytes())?;
+    writer::write_atomic(&dir.join(PID_FILE), format!("{}\n", info.pid).as_bytes())?;
+    Ok(())
+}
+
+/// Where to reach a server: its URL, else its socket
+pub fn address(info: &ServerInfo) -> String {
+    match (&info.url, &info.socket) {
+        (Some(url), _) => url.clone(),
+        (None, Some(socket)) => format!("unix:{}", socket.display()),
+        (None, None) => "an unknown address".to_string(),
+    }
+}
+
+/// Remove the pidfile and server info, unless another process has taken them over since
+pub fn unregister(project_root: &Path) {
+    let dir = project_root.join(STATE_DIR);
+    if read_pid(&dir).is_some_and(|pid| pid == std::process::id()) {
+        let _ = fs::remove_file(dir.join(PID_FILE));
+        let _ = fs::remove_file(dir.join(INFO_FILE));
+    }
+}
+
+/// The project's server, if one is alive; stale files from a crashed server, or whose pid now
+/// belongs to another program, are cleaned up
+pub fn running(project_root: &Path) -> Result<Option<ServerInfo>> {
+    let dir = project_root.join(STATE_DIR);
+    let Some(pid) = read_pid(&dir) else {
+        return Ok(None);
+    };
+
+    if !is_server(pid) {
+        let _ = fs::remove_file(dir.join(PID_FILE));
+        let _ = fs::remove_file(dir.join(INFO_FILE));
+        return Ok(None);
+    }
+
+    let info = fs::read_to_string(dir.join(INFO_FILE))
+        .ok()
+        .and_then(|json| serde_json::from_str::<ServerInfo>(&json).ok())
+        .filter(|info| info.pid == pid);
+    Ok(info)
+}
+
+/// Re-run this command in the background, detached from the terminal, and wait until it is serving;
+/// the server gets `token` through its environment, so it never has to show it in its log
+pub fn spawn(project_root: &Path, token: &str) -> Result<ServerInfo> {
+    ensure_not_running(project_root)?;
+
+    let log_file = log_path(project_root);
+    fs::create_dir_all(log_file.parent().unwrap_or(project_root))?;
+    let log = open_log(&log_file).with_context(|| format!("Failed to open log file {}", log_file.display()))?;
+
+    let args: Vec<_> = std::env::args_os().skip(1).filter(|arg| arg != "--daemon").collect();
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .args(args)
+        .env(DAEMON_ENV, "1")
+        .env(TOKEN_ENV, token)
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log);
+    #[cfg(unix)]
+    {
+        // Its own process group, so Ctrl+C in this terminal doesn't reach it
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = command.spawn().context("Failed to start the server process")?;
+
+    let started = Instant::now();
+    while started.elapsed() < START_TIMEOUT {
+        if let Some(status) = child.try_wait()? {
+            anyhow::bail!("Server exited during startup ({}); see {}", status, log_file.display());
+        }
+        if let Some(info) = running(project_root)?.filter(|info| info.pid == child.id()) {
+            return Ok(info);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+
+    anyhow::bail!("Server did not start within {}s; see {}", START_TIMEOUT.as_secs(), log_file.display())
+}
+
+/// Ask the project's server to shut down and wait for it to finish in-flight requests
+pub fn stop(project_root: &Path) -> Result<Option<ServerInfo>> {
+    let Some(info) = running(project_root)? else {
+        return Ok(None);
+    };
+
+    terminate(info.pid)?;
+    wait_for_exit(info.pid)?;
+    Ok(Some(info))
+}
+
+/// Wait for a server that was asked to stop to finish its in-flight requests and exit
+pub fn wait_for_exit(pid: u32) -> Result<()> {
+    let started = Instant::now();
+    while is_server(pid) {
+        if started.elapsed() >= STOP_TIMEOUT {
+            anyhow::bail!("Server (pid {}) did not stop within {}s", pid, STOP_TIMEOUT.as_secs());
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    Ok(())
+}
+
+/// Open a URL in the default browser
+pub fn open_browser(url: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+
+    let status =
+        command.arg(url).stdout(Stdio::null()).stderr(Stdio::null()).status().context("Failed to launch a browser")?;
+    if !status.success() {
+        anyhow::bail!("Failed to open {} ({})", url, status);
+    }
+    Ok(())
+}
+
+/// Resolves on Ctrl+C or SIGTERM
+pub async fn shutdown_signal() {
+    let interrupt = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = interrupt => {}
+        _ = terminate => {}
+    }
+}
+
+/// A wildcard bind address isn't something a browser can open
+fn browser_host(host: &str) -> &str {
+    match host {
+        "0.0.0.0" | "::" | "[::]" => "127.0.0.1",
+        host => host,
+    }
+}
+
+fn read_pid(dir: &Path) -> Option<u32> {
+    fs::read_to_string(dir.join(PID_FILE)).ok()?.trim().parse().ok()
+}
+
+/// The log may hold paths and request details, so only the owner can read it
+pub fn open_log(path: &Path) -> std::io::Result<fs::File> {
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let log = options.open(path)?;
+        log.set_permissions(fs::Permissions::from_mode(0o600))?; // Created by an older version
+        Ok(log)
+    }
+    #[cfg(not(unix))]
+    options.open(path)
+}
+
+/// Whether `pid` is alive and still runs this program, rather than something that reused a dead server's pid
+fn is_server(pid: u32) -> bool {
+    let ours = std::env::args_os().next().and_then(|arg0| program_name(Path::new(&arg0)));
+    is_alive(pid) && ours.is_some() && command(pid).and_then(|arg0| program_name(Path::new(&arg0))) == ours
+}
+
+fn program_name(path: &Path) -> Option<String> {
+    Some(path.file_name()?.to_string_lossy().to_string())
+}
+
+/// The program a process was started as, i.e. its `argv[0]`
+#[cfg(target_os = "linux")]
+fn command(pid: u32) -> Option<String> {
+    let cmdline = fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    let arg0 = cmdline.split(|b| *b == 0).next()?;
+    Some(String::from_utf8_lossy(arg0).to_string())
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn command(pid: u32) -> Option<String> {
+    let output = Command::new("ps").args(["-o", "comm=", "-p", &pid.to_string()]).output().ok()?;
+    let command = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !command.is_empty()).then_some(command)
+}
+
+#[cfg(not(unix))]
+fn command(_pid: u32) -> Option<String> {
+    None
+}
+
+#[cfg(unix)]
+fn is_alive(pid: u32) -> bool {
+    use nix::errno::Errno;
+    use nix::sys::signal::kill;
+    use nix::unistd::Pid;
+
+    // Signal 0 only checks; EPERM means it exists but belongs to someone else
+    matches!(kill(Pid::from_raw(pid as i32), None), Ok(()) | Err(Errno::EPERM))
+}
+
+/// Without a way to check, assume the server is gone rather than act on a pid that may be reused
+#[cfg(not(unix))]
+fn is_alive(_pid: u32) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn terminate(pid: u32) -> Result<()> {
+    use nix::sys::signal::{kill, Signal};
+    use nix::unistd::Pid;
+
+    kill(Pid::from_raw(pid as i32), Signal::SIGTERM).with_context(|| format!("Failed to signal server (pid {})", pid))
+}
+
+#[cfg(not(unix))]
+fn terminate(_pid: u32) -> Result<()> {
+    anyhow::bail!("`cc-atlas stop` is only supported on Unix; close the server's window instead")
+}
@@ -1,58 +1,342 @@
 use axum::{
-    http::StatusCode,
+    body::to_bytes,
+    extract::Request,
+    http::{header, StatusCode},
+    middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
-use serde_json::json;
+use serde_json::{json, Value};
 use std::fmt;
+use std::io;
 
+use super::request_id;
+use crate::models::{ErrorCode, ErrorResponse, FileConflict};
+use crate::services::chat_exporter::ChatError;
+use crate::services::history::HistoryError;
+use crate::services::mover::MoveError;
+use crate::services::trash::TrashError;
+use crate::utils::adr::AdrError;
+use crate::utils::batch::BatchError;
+use crate::utils::plans::PlanError;
+use crate::utils::sandbox::SandboxError;
+
+/// A failed request: a stable code, a message for people and optional structured details
 #[derive(Debug)]
-pub enum ServerError {
-    NotFound(String),
-    Internal(String),
-    BadRequest(String),
-    Conflict(String),
+pub struct ServerError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub details: Option<Value>,
+    pub conflict: Option<Box<FileConflict>>,
+}
+
+impl ServerError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self { code, message: message.into(), details: None, conflict: None }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// The file changed on disk since the version an edit was based on
+    pub fn conflict(conflict: FileConflict) -> Self {
+        Self {
+            message: format!("File was modified externally: {}", conflict.path),
+            conflict: Some(Box::new(conflict)),
+            ..Self::new(ErrorCode::HashMismatch, "")
+        }
+    }
+
+    pub fn file_not_found(path: &str) -> Self {
+        Self::new(ErrorCode::FileNotFound, format!("File not found: {}", path)).with_details(json!({ "path": path }))
+    }
+
+    pub fn file_exists(path: &str) -> Self {
+        Self::new(ErrorCode::FileExists, format!("File already exists: {}", path)).with_details(json!({ "path": path }))
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    pub fn status(&self) -> StatusCode {
+        status_for(self.code)
+    }
+}
+
+fn status_for(code: ErrorCode) -> StatusCode {
+    match code {
+        ErrorCode::InvalidRequest
+        | ErrorCode::InvalidPath
+        | ErrorCode::PathOutsideProject
+        | ErrorCode::PathOutsideDocs
+        | ErrorCode::NotAMemoryFile
+        | ErrorCode::MissingContent
+        | ErrorCode::InvalidMetadata
+        | ErrorCode::InvalidMove
+        | ErrorCode::UnresolvedConflicts
+        | ErrorCode::EmptyQuery
+        | ErrorCode::NotATask => StatusCode::BAD_REQUEST,
+        ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+        ErrorCode::PermissionDenied => StatusCode::FORBIDDEN,
+        ErrorCode::NotFound
+        | ErrorCode::FileNotFound
+        | ErrorCode::DirectoryNotFound
+        | ErrorCode::ChatNotFound
+        | ErrorCode::RevisionNotFound
+        | ErrorCode::TrashEntryNotFound
+        | ErrorCode::AdrNotFound
+        | ErrorCode::PlanNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+        ErrorCode::FileExists | ErrorCode::HashMismatch | ErrorCode::TaskChanged | ErrorCode::InvalidTransition => {
+            StatusCode::CONFLICT
+        }
+        ErrorCode::BaseHashRequired => StatusCode::PRECONDITION_REQUIRED,
+        ErrorCode::IoError | ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+    }
 }
 
 impl fmt::Display for ServerError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            ServerError::NotFound(msg) => write!(f, "Not found: {}", msg),
-            ServerError::Internal(msg) => write!(f, "Internal error: {}", msg),
-            ServerError::BadRequest(msg) => write!(f, "Bad request: {}", msg),
-            ServerError::Conflict(msg) => write!(f, "Conflict: {}", msg),
-        }
+        write!(f, "{}", self.message)
     }
 }
 
 impl IntoResponse for ServerError {
     fn into_response(self) -> Response {
-        let (status, message) = match self {
-            ServerError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
-            ServerError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
-            ServerError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
-            ServerError::Conflict(msg) => (StatusCode::CONFLICT, msg),
+        let status = self.status();
+        if status.is_server_error() {
+            tracing::error!(code = ?self.code, "{}", self.message);
+        }
+        let body = ErrorResponse {
+            error: self.message,
+            code: self.code,
+            request_id: request_id::current(),
+            details: self.details,
+            conflict: self.conflict.map(|c| *c),
         };
 
-        let body = Json(json!({
-            "error": message,
-        }));
-
-        (status, body).into_response()
+        (status, Json(body)).into_response()
     }
 }
 
-impl From<std::io::Error> for ServerError {
-    fn from(err: std::io::Error) -> Self {
-        match err.kind() {
-            std::io::ErrorKind::NotFound => ServerError::NotFound(err.to_string()),
-            _ => ServerError::Internal(format!("IO error: {}", err)),
+/// Longest plain-text rejection body we read to use as the message
+const MAX_REJECTION_BYTES: usize = 4096;
+
+/// Rejections from extractors and the router are plain text; give them the same JSON body as handler errors
+pub async fn json_errors(request: Request, next: Next) -> Response {
+    let response = next.run(request).await;
+    let status = response.status();
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if is_json || !(status.is_client_error() || status.is_server_error()) {
+        return response;
+    }
+
+    let code = match status {
+        StatusCode::NOT_FOUND => ErrorCode::NotFound,
+        StatusCode::METHOD_NOT_ALLOWED => ErrorCode::MethodNotAllowed,
+        StatusCode::UNAUTHORIZED => ErrorCode::Unauthorized,
+        s if s.is_server_error() => ErrorCode::Internal,
+        _ => ErrorCode::InvalidRequest,
+    };
+
+    let (parts, body) = response.into_parts();
+    let text = to_bytes(body, MAX_REJECTION_BYTES).await.unwrap_or_default();
+    let text = String::from_utf8_lossy(&text).trim().to_string();
+    let message = if text.is_empty() { status.canonical_reason().unwrap_or("Error").to_string() } else { text };
+
+    let mut json = ServerError::new(code, message).into_response();
+    *json.status_mut() = status; // Keep e.g. 415 and 422 rather than the code's usual status
+    for (name, value) in &parts.headers {
+        if name != header::CONTENT_TYPE && name != header::CONTENT_LENGTH {
+            json.headers_mut().insert(name, value.clone());
         }
     }
+    json
 }
 
+impl From<io::Error> for ServerError {
+    fn from(err: io::Error) -> Self {
+        ServerError::new(io_code(&err), err.to_string())
+    }
+}
+
+fn io_code(err: &io::Error) -> ErrorCode {
+    match err.kind() {
+        io::ErrorKind::NotFound => ErrorCode::FileNotFound,
+        io::ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+        io::ErrorKind::AlreadyExists => ErrorCode::FileExists,
+        _ => ErrorCode::IoError,
+    }
+}
+
+/// Services return `anyhow` errors; find the typed cause anywhere in the chain and keep every message
 impl From<anyhow::Error> for ServerError {
     fn from(err: anyhow::Error) -> Self {
-        ServerError::Internal(err.to_string())
+        let typed = err.chain().find_map(typed_cause);
+
+        let message = format!("{:#}", err);
+        match typed {
+            Some(typed) => ServerError { message, ..typed },
+            None => ServerError::internal(message),
+        }
+    }
+}
+
+/// The one place a service error type is mapped from a cause; a cause has a single concrete type, so order is irrelevant
+fn typed_cause(cause: &(dyn std::error::Error + 'static)) -> Option<ServerError> {
+    fn typed<E>(cause: &(dyn std::error::Error + 'static)) -> Option<ServerError>
+    where
+        E: std::error::Error + Clone + 'static,
+        ServerError: From<E>,
+    {
+        cause.downcast_ref::<E>().cloned().map(ServerError::from)
+    }
+
+    typed::<SandboxError>(cause)
+        .or_else(|| typed::<AdrError>(cause))
+        .or_else(|| typed::<PlanError>(cause))
+        .or_else(|| typed::<ChatError>(cause))
+        .or_else(|| typed::<HistoryError>(cause))
+        .or_else(|| typed::<TrashError>(cause))
+        .or_else(|| typed::<MoveError>(cause))
+        .or_else(|| typed::<BatchError>(cause))
+        .or_else(|| cause.downcast_ref::<io::Error>().map(|e| ServerError::new(io_code(e), "")))
+}
+
+impl From<SandboxError> for ServerError {
+    fn from(err: SandboxError) -> Self {
+        let code = match &err {
+            SandboxError::Empty | SandboxError::Absolute(_) | SandboxError::Traversal(_) => ErrorCode::InvalidPath,
+            SandboxError::Escape(_) => ErrorCode::PathOutsideProject,
+            SandboxError::NotMemoryFile(_) => ErrorCode::NotAMemoryFile,
+            SandboxError::OutsideAllowed(_) => ErrorCode::PathOutsideDocs,
+            SandboxError::RootUnavailable(_) => ErrorCode::Internal,
+        };
+        ServerError::new(code, err.to_string())
+    }
+}
+
+impl From<AdrError> for ServerError {
+    fn from(err: AdrError) -> Self {
+        let message = err.to_string();
+        match err {
+            AdrError::NotFound(number) => {
+                ServerError::new(ErrorCode::AdrNotFound, message).with_details(json!({ "number": number }))
+            }
+            AdrError::InvalidTransition { from, to } => {
+                ServerError::new(ErrorCode::InvalidTransition, message).with_details(json!({ "from": from, "to": to }))
+            }
+            AdrError::InvalidFrontMatter(_) => ServerError::new(ErrorCode::InvalidMetadata, message),
+            AdrError::EmptyTitle | AdrError::MissingSupersededBy | AdrError::SelfSupersede(_) => {
+                ServerError::new(ErrorCode::InvalidRequest, message)
+            }
+        }
+    }
+}
+
+impl From<PlanError> for ServerError {
+    fn from(err: PlanError) -> Self {
+        let message = err.to_string();
+        match err {
+            PlanError::NotAPlan(path) => {
+                ServerError::new(ErrorCode::PlanNotFound, message).with_details(json!({ "path": path }))
+            }
+            PlanError::NotATask(line) => {
+                ServerError::new(ErrorCode::NotATask, message).with_details(json!({ "line": line }))
+            }
+            PlanError::TextChanged { line, found } => {
+                ServerError::new(ErrorCode::TaskChanged, message).with_details(json!({ "line": line, "found": found }))
+            }
+            PlanError::Modified(path) => {
+                ServerError::new(ErrorCode::HashMismatch, message).with_details(json!({ "path": path }))
+            }
+        }
+    }
+}
+
+impl From<ChatError> for ServerError {
+    fn from(err: ChatError) -> Self {
+        let message = err.to_string();
+        match err {
+            ChatError::NotFound(id) => {
+                ServerError::new(ErrorCode::ChatNotFound, message).with_details(json!({ "session_id": id }))
+            }
+        }
+    }
+}
+
+impl From<HistoryError> for ServerError {
+    fn from(err: HistoryError) -> Self {
+        let message = err.to_string();
+        match err {
+            HistoryError::RevisionNotFound(id) => {
+                ServerError::new(ErrorCode::RevisionNotFound, message).with_details(json!({ "id": id }))
+            }
+            HistoryError::DifferentFiles(a, b) => {
+                ServerError::new(ErrorCode::InvalidRequest, message).with_details(json!({ "paths": [a, b] }))
+            }
+        }
+    }
+}
+
+impl From<TrashError> for ServerError {
+    fn from(err: TrashError) -> Self {
+        let message = err.to_string();
+        match err {
+            TrashError::EntryNotFound(id) => {
+                ServerError::new(ErrorCode::TrashEntryNotFound, message).with_details(json!({ "id": id }))
+            }
+            TrashError::PathOccupied(path) => {
+                ServerError::new(ErrorCode::FileExists, message).with_details(json!({ "path": path }))
+            }
+        }
+    }
+}
+
+impl From<MoveError> for ServerError {
+    fn from(err: MoveError) -> Self {
+        let message = err.to_string();
+        match err {
+            MoveError::SourceNotFound(path) => {
+                ServerError::new(ErrorCode::FileNotFound, message).with_details(json!({ "path": path }))
+            }
+            MoveError::DestinationExists(path) => {
+                ServerError::new(ErrorCode::FileExists, message).with_details(json!({ "path": path }))
+            }
+            MoveError::IntoItself(path) => {
+                ServerError::new(ErrorCode::InvalidMove, message).with_details(json!({ "path": path }))
+            }
+            MoveError::OutsideProject(path) => {
+                ServerError::new(ErrorCode::PathOutsideProject, message).with_details(json!({ "path": path }))
+            }
+        }
+    }
+}
+
+impl From<BatchError> for ServerError {
+    fn from(err: BatchError) -> Self {
+        let message = err.to_string();
+        match err {
+            BatchError::Empty => ServerError::new(ErrorCode::InvalidRequest, message),
+            BatchError::NotFound { operation, path } => ServerError::new(ErrorCode::FileNotFound, message)
+                .with_details(json!({ "operation": operation, "path": path })),
+            BatchError::Exists { operation, path } => ServerError::new(ErrorCode::FileExists, message)
+                .with_details(json!({ "operation": operation, "path": path })),
+            BatchError::Modified { operation, path, base_hash, current_hash } => {
+                ServerError::new(ErrorCode::HashMismatch, message).with_details(json!({
+                    "operation": operation,
+                    "path": path,
+                    "base_hash": base_hash,
+                    "current_hash": current_hash,
+                }))
+            }
+        }
     }
 }
@@ -0,0 +1,146 @@
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, StatusCode, Uri},
+    response::{Html, IntoResponse, Response},
+};
+use rust_embed::RustEmbed;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use super::{auth, error::ServerError, AppState};
+use crate::models::ErrorCode;
+use crate::utils::{hash, markdown, sandbox};
+
+const TOKEN_META_NAME: &str = "cc-atlas-token";
+
+/// Vite puts content-hashed bundles here, so they never change under the same name
+const HASHED_ASSETS_PREFIX: &str = "assets/";
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+const REVALIDATE: &str = "no-cache";
+
+/// The built frontend, compiled into the binary. Build it (`npm run build` in `frontend/`) before `cargo build`.
+#[derive(RustEmbed)]
+#[folder = "frontend/dist"]
+#[allow_missing = true]
+struct Assets;
+
+/// Where the dashboard's files come from
+#[derive(Debug, Clone, Default)]
+pub enum Frontend {
+    /// Compiled into the binary
+    #[default]
+    Embedded,
+    /// Read from disk on every request (`--frontend-dir`), for working on the frontend
+    Dir(PathBuf),
+}
+
+struct Asset {
+    bytes: Cow<'static, [u8]>,
+    hash: String,
+}
+
+impl Frontend {
+    fn load(&self, path: &str) -> Option<Asset> {
+        match self {
+            Frontend::Embedded => {
+                Assets::get(path).map(|file| Asset { hash: hex(&file.metadata.sha256_hash()), bytes: file.data })
+            }
+            Frontend::Dir(dir) => {
+                let file_path = sandbox::resolve_path(dir, path).ok()?;
+                let bytes = std::fs::read(file_path).ok()?;
+                Some(Asset { hash: hex(&Sha256::digest(&bytes)), bytes: Cow::Owned(bytes) })
+            }
+        }
+    }
+}
+
+/// Serve `index.html` with the access token injected so the dashboard can call `/api`.
+///
+/// The page needs no token to fetch, so it is only served under a known `Host`: a DNS-rebinding
+/// page or a LAN client using another name for this machine must not be handed the token.
+pub async fn serve_index(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ServerError> {
+    let host = headers.get(header::HOST).and_then(|v| v.to_str().ok());
+    if !auth::is_allowed_host(host, &state.allowed_hosts) {
+        return Err(ServerError::new(
+            ErrorCode::PermissionDenied,
+            format!(
+                "Unknown host {:?}; open the dashboard at the address the server printed",
+                host.unwrap_or_default()
+            ),
+        ));
+    }
+
+    let index = state
+        .frontend
+        .load("index.html")
+        .ok_or_else(|| ServerError::new(ErrorCode::NotFound, "Frontend not built: run `npm run build` in frontend/"))?;
+    let index = String::from_utf8_lossy(&index.bytes);
+
+    // The page carries the token, so never let it be stored
+    Ok(([(header::CACHE_CONTROL, "no-store")], Html(inject_token(&index, &state.access_token))))
+}
+
+/// Serve the stylesheet for the classes in server-highlighted code blocks
+pub async fn serve_highlight_css() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/css"), (header::CACHE_CONTROL, REVALIDATE)], markdown::highlight_css())
+}
+
+/// Serve a frontend file, falling back to `index.html` for client-side routes
+pub async fn serve_asset(
+    State(state): State<Arc<AppState>>,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Result<Response, ServerError> {
+    let path = uri.path().trim_start_matches('/');
+    if path == "api" || path.starts_with("api/") {
+        return Err(ServerError::new(ErrorCode::NotFound, format!("No such endpoint: {}", uri.path())));
+    }
+
+    let Some(asset) = state.frontend.load(path).filter(|_| !path.is_empty() && path != "index.html") else {
+        // Bundles and anything that looks like a file are real 404s; other paths belong to the app
+        if path.starts_with(HASHED_ASSETS_PREFIX) || path.rsplit('/').next().is_some_and(|name| name.contains('.')) {
+            return Err(ServerError::file_not_found(path));
+        }
+        return Ok(serve_index(State(state), headers).await?.into_response());
+    };
+
+    let etag = hash::to_etag(&asset.hash);
+    let cache = if path.starts_with(HASHED_ASSETS_PREFIX) { IMMUTABLE } else { REVALIDATE };
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|tag| hash::from_etag(tag) == asset.hash));
+
+    let mime = mime_guess::from_path(path).first_or_octet_stream();
+    let mut response = if not_modified {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        ([(header::CONTENT_TYPE, mime.as_ref())], asset.bytes).into_response()
+    };
+
+    let response_headers = response.headers_mut();
+    response_headers.insert(header::CACHE_CONTROL, HeaderValue::from_static(cache));
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        response_headers.insert(header::ETAG, value);
+    }
+    Ok(response)
+}
+
+fn inject_token(index: &str, token: &str) -> String {
+    let meta = format!("<meta name=\"{}\" content=\"{}\" />", TOKEN_META_NAME, token);
+
+    if index.contains("</head>") {
+        index.replacen("</head>", &format!("{}\n  </head>", meta), 1)
+    } else {
+        format!("{}\n{}", meta, index)
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
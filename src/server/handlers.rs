@@ -1,141 +1,290 @@
+use anyhow::Context;
 use axum::{
-    extract::{Path as AxumPath, State},
-    response::Json,
+    extract::{Path as AxumPath, Query, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Json},
 };
-use serde_json::Value;
-use std::path::Path;
+use serde_json::json;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use utoipa::IntoParams;
 
-use crate::services::{analyzer, chat_exporter, docs};
-use crate::utils::{markdown, paths};
-use crate::models::{ChatMetadata, DirectoryInfo, DocFileResponse, DocsNode, ExportOptions, ExportResult, MemoryFileResponse};
+use crate::models::{
+    AdrIndexResponse, AdrStatusRequest, AdrSummary, BacklinksResponse, BatchRequest, BatchResult, BrokenLinkReport,
+    ChatMetadata, CheckAllRequest, CreateAdrRequest, CreatedFile, DeletedFile, DirectoryInfo, DocFileResponse,
+    DocSortField, DocsNode, ErrorCode, ErrorResponse, ExportChatRequest, ExportOptions, ExportResult, FileConflict,
+    FileHashStatus, FileKind, MemoryFileDetail, MemoryFileResponse, MemoryScope, MergeRequest, MergeResult,
+    MoveRequest, MoveResult, PlanSummary, ResolveRequest, ResolveStrategy, Revision, RevisionContent, RevisionDiff,
+    SaveFileRequest, SavedFile, SearchResponse, ServerInfo, SortOrder, ToggleTaskRequest, TrashEntry,
+};
+use crate::services::link_graph::LinkGraph;
+use crate::services::{adr, analyzer, batch, chat_exporter, docs, history, mover, plans, trash};
+use crate::utils::front_matter::DocFilter;
+use crate::utils::search::SearchFilters;
+use crate::utils::{front_matter, hash, markdown, merge, paths, sandbox, writer};
+
+use super::{error::ServerError, AppState};
 
-use super::{AppState, error::ServerError};
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+const MAX_SEARCH_LIMIT: usize = 200;
 
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MemoryListQuery {
+    pub include: Option<String>, // `content` to also return content and HTML
+}
+
+#[utoipa::path(
+    get, path = "/memory-files", tag = "memory",
+    params(MemoryListQuery),
+    responses((status = 200, body = Vec<MemoryFileResponse>))
+)]
 pub async fn get_memory_files(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<MemoryListQuery>,
 ) -> Result<Json<Vec<MemoryFileResponse>>, ServerError> {
     let root = Path::new(&state.project_root);
+    let include_content =
+        query.include.as_deref().is_some_and(|include| include.split(',').any(|part| part.trim() == "content"));
 
-    let files = analyzer::find_memory_files(root)
-        .map_err(|e| ServerError::Internal(format!("Failed to find memory files: {}", e)))?;
+    let files = analyzer::find_memory_file_paths(root).context("Failed to find memory files")?;
+
+    let mut responses = Vec::with_capacity(files.len());
+    for file in files {
+        let response = paths::memory_file_response(&file, &state.project_root, include_content)?;
+        if let Some(content) = &response.content {
+            state.remember_version(&response.content_hash, content);
+        }
+        responses.push(response);
+    }
 
-    let responses = paths::convert_to_responses(files, &state.project_root);
     Ok(Json(responses))
 }
 
+#[utoipa::path(
+    get, path = "/memory-files/{path}", tag = "memory",
+    params(("path" = String, Path, description = "Project-relative; may contain slashes")),
+    responses(
+        (status = 200, body = MemoryFileDetail, headers(("ETag" = String))),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+pub async fn get_memory_file(
+    State(state): State<Arc<AppState>>,
+    AxumPath(path): AxumPath<String>,
+) -> Result<impl IntoResponse, ServerError> {
+    let root = Path::new(&state.project_root).canonicalize()?;
+    let file_path = sandbox::resolve_memory_path(&root, &path)?;
+    if !file_path.is_file() {
+        return Err(ServerError::file_not_found(&path));
+    }
+
+    // The sandbox hands back a path under the canonical root, so strip that one
+    let file = paths::memory_file_response(&file_path, &root.to_string_lossy(), true)?;
+    let dir = file_path.parent().unwrap_or(&root);
+    let detail = MemoryFileDetail {
+        stats: analyzer::calculate_stats(dir)?,
+        staleness: analyzer::check_staleness(&file_path)?,
+        file,
+    };
+
+    if let Some(content) = &detail.file.content {
+        state.remember_version(&detail.file.content_hash, content);
+    }
+
+    Ok(([(header::ETAG, hash::to_etag(&detail.file.content_hash))], Json(detail)))
+}
+
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TreeQuery {
+    pub path: Option<String>, // Subtree to return, relative to the project root
+    pub depth: Option<usize>, // Levels of children to load; unlimited if omitted
+    #[serde(default)]
+    pub include_files: bool,
+}
+
+#[utoipa::path(
+    get, path = "/tree", tag = "memory",
+    params(TreeQuery),
+    responses((status = 200, body = DirectoryInfo), (status = 404, body = ErrorResponse))
+)]
 pub async fn get_directory_tree(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<TreeQuery>,
 ) -> Result<Json<DirectoryInfo>, ServerError> {
-    let root = Path::new(&state.project_root);
+    let root = Path::new(&state.project_root).canonicalize()?;
+
+    let dir = match query.path.as_deref().map(|p| p.trim_start_matches("./")) {
+        None | Some("") | Some(".") => root.clone(),
+        Some(path) => sandbox::resolve_path(&root, path)?,
+    };
+    if !dir.is_dir() {
+        let requested = query.path.unwrap_or_default();
+        return Err(ServerError::new(ErrorCode::DirectoryNotFound, format!("Directory not found: {}", requested))
+            .with_details(json!({ "path": requested })));
+    }
 
-    let tree = analyzer::build_directory_tree(root)
-        .map_err(|e| ServerError::Internal(format!("Failed to build directory tree: {}", e)))?;
+    let options = analyzer::TreeOptions { depth: query.depth, include_files: query.include_files };
+    let tree = analyzer::build_tree(&dir, &options).context("Failed to build directory tree")?;
 
     // Convert paths to relative paths for frontend
-    let converted_tree = paths::convert_tree_paths(tree, &state.project_root);
+    let converted_tree = paths::convert_tree_paths(tree, &root.to_string_lossy());
     Ok(Json(converted_tree))
 }
 
+#[utoipa::path(
+    put, path = "/memory-files/{path}", tag = "memory",
+    params(("path" = String, Path, description = "Project-relative; may contain slashes"), ("If-Match" = Option<String>, Header, description = "Hash the edit is based on")),
+    request_body = SaveFileRequest,
+    responses(
+        (status = 200, body = SavedFile, headers(("ETag" = String))),
+        (status = 409, description = "Changed on disk since `base_hash`", body = ErrorResponse),
+        (status = 428, description = "No base hash given", body = ErrorResponse),
+    )
+)]
 pub async fn update_memory_file(
     State(state): State<Arc<AppState>>,
     AxumPath(path): AxumPath<String>,
-    Json(body): Json<Value>,
-) -> Result<Json<Value>, ServerError> {
-    // Check if we're receiving HTML or markdown
-    let content_html = body.get("content_html").and_then(|v| v.as_str());
-    let content_md = body.get("content").and_then(|v| v.as_str());
+    headers: HeaderMap,
+    Json(body): Json<SaveFileRequest>,
+) -> Result<impl IntoResponse, ServerError> {
+    let base_hash = require_base_hash(&headers, body.base_hash.as_deref())?;
+    let final_content = memory_content(&body)?;
 
-    let final_content = if let Some(html) = content_html {
-        // Convert HTML to markdown for saving
-        markdown::html_to_markdown(html)
-            .map_err(|e| ServerError::Internal(format!("Failed to convert HTML to markdown: {}", e)))?
-    } else if let Some(md) = content_md {
-        // Use markdown directly (for source mode saves)
-        md.to_string()
-    } else {
-        return Err(ServerError::BadRequest("Missing content or content_html".to_string()));
-    };
+    let file_path = sandbox::resolve_memory_path(Path::new(&state.project_root), &path)?;
 
-    let file_path = Path::new(&state.project_root).join(&path);
+    let _write = state.writes.lock().await;
+    check_base_hash(&state, &file_path, &path, &base_hash, &final_content)?;
+    snapshot_before(&state, &file_path, "update")?;
 
-    std::fs::write(&file_path, &final_content)
-        .map_err(|e| ServerError::Internal(format!("Failed to write file: {}", e)))?;
+    let written = writer::write_preserving(&file_path, &final_content).context("Failed to write file")?;
+    state.reindex(&file_path);
 
-    Ok(Json(serde_json::json!({
-        "content": final_content
-    })))
+    Ok(saved_response(&state, written))
 }
 
+#[utoipa::path(
+    post, path = "/memory-files/{path}", tag = "memory",
+    params(("path" = String, Path, description = "Project-relative; may contain slashes")),
+    request_body = SaveFileRequest,
+    responses((status = 200, body = CreatedFile), (status = 409, body = ErrorResponse))
+)]
 pub async fn create_memory_file(
     State(state): State<Arc<AppState>>,
     AxumPath(path): AxumPath<String>,
-    Json(body): Json<Value>,
-) -> Result<Json<Value>, ServerError> {
-    // Check if we're receiving HTML or markdown
-    let content_html = body.get("content_html").and_then(|v| v.as_str());
-    let content_md = body.get("content").and_then(|v| v.as_str());
+    Json(body): Json<SaveFileRequest>,
+) -> Result<Json<CreatedFile>, ServerError> {
+    let final_content = memory_content(&body)?;
 
-    let final_content = if let Some(html) = content_html {
-        // Convert HTML to markdown for saving
-        markdown::html_to_markdown(html)
-            .map_err(|e| ServerError::Internal(format!("Failed to convert HTML to markdown: {}", e)))?
-    } else if let Some(md) = content_md {
-        // Use markdown directly
-        md.to_string()
-    } else {
-        return Err(ServerError::BadRequest("Missing content or content_html".to_string()));
-    };
-
-    let file_path = Path::new(&state.project_root).join(&path);
+    let file_path = sandbox::resolve_memory_path(Path::new(&state.project_root), &path)?;
 
+    let _write = state.writes.lock().await;
     // Create parent directories if they don't exist
     if let Some(parent) = file_path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| ServerError::Internal(format!("Failed to create parent directories: {}", e)))?;
+        std::fs::create_dir_all(parent).context("Failed to create parent directories")?;
     }
 
     // Check if file already exists
     if file_path.exists() {
-        return Err(ServerError::Conflict(format!("File already exists: {}", path)));
+        return Err(ServerError::file_exists(&path));
     }
 
-    std::fs::write(&file_path, &final_content)
-        .map_err(|e| ServerError::Internal(format!("Failed to create file: {}", e)))?;
+    let written = writer::write_preserving(&file_path, &final_content).context("Failed to create file")?;
+    state.reindex(&file_path);
+
+    let content_hash = hash::calculate_hash(&written);
+    state.remember_version(&content_hash, &written);
 
-    Ok(Json(serde_json::json!({
-        "path": path,
-        "content": final_content,
-        "created": true
-    })))
+    Ok(Json(CreatedFile { path, content: written, content_hash, created: true }))
 }
 
+#[utoipa::path(
+    delete, path = "/memory-files/{path}", tag = "memory",
+    params(("path" = String, Path, description = "Project-relative; may contain slashes")),
+    responses((status = 200, body = DeletedFile), (status = 404, body = ErrorResponse))
+)]
 pub async fn delete_memory_file(
     State(state): State<Arc<AppState>>,
     AxumPath(path): AxumPath<String>,
-) -> Result<Json<Value>, ServerError> {
-    let file_path = Path::new(&state.project_root).join(&path);
+) -> Result<Json<DeletedFile>, ServerError> {
+    let file_path = sandbox::resolve_memory_path(Path::new(&state.project_root), &path)?;
 
+    let _write = state.writes.lock().await;
     if !file_path.exists() {
-        return Err(ServerError::NotFound(format!("File not found: {}", path)));
+        return Err(ServerError::file_not_found(&path));
     }
 
-    std::fs::remove_file(&file_path)
-        .map_err(|e| ServerError::Internal(format!("Failed to delete file: {}", e)))?;
+    snapshot_before(&state, &file_path, "delete")?;
 
-    Ok(Json(serde_json::json!({
-        "path": path,
-        "deleted": true
-    })))
+    let entry =
+        trash::move_to_trash(Path::new(&state.project_root), &file_path, "api").context("Failed to delete file")?;
+    state.reindex(&file_path);
+
+    Ok(Json(DeletedFile { path, deleted: true, trash_id: entry.id }))
 }
 
-pub async fn get_recommendations(
+#[utoipa::path(
+    post, path = "/memory-files/batch", tag = "memory",
+    request_body = BatchRequest,
+    responses(
+        (status = 200, body = BatchResult),
+        (status = 404, body = ErrorResponse),
+        (status = 409, description = "A target exists or a file changed since its `base_hash`", body = ErrorResponse),
+    )
+)]
+pub async fn batch_memory_files(
     State(state): State<Arc<AppState>>,
-) -> Result<Json<Vec<String>>, ServerError> {
+    Json(request): Json<BatchRequest>,
+) -> Result<Json<BatchResult>, ServerError> {
+    let root = Path::new(&state.project_root);
+
+    let _write = state.writes.lock().await;
+    let result = batch::apply(root, &request.operations, request.dry_run, "api").context("Failed to apply batch")?;
+
+    if !result.dry_run {
+        for file in &result.files {
+            let file_path = root.join(&file.path);
+            state.reindex(&file_path);
+            if let Some(from) = &file.from {
+                state.reindex(&root.join(from));
+            }
+            if let Some(content_hash) = &file.content_hash {
+                if let Ok(content) = std::fs::read_to_string(&file_path) {
+                    state.remember_version(content_hash, &content);
+                }
+            }
+        }
+    }
+
+    Ok(Json(result))
+}
+
+/// Markdown to save from a memory file create or update body
+fn memory_content(body: &SaveFileRequest) -> Result<String, ServerError> {
+    if let Some(html) = &body.content_html {
+        // Convert HTML to markdown for saving
+        Ok(markdown::html_to_markdown(html).context("Failed to convert HTML to markdown")?)
+    } else if let Some(md) = &body.content {
+        // Use markdown directly (for source mode saves)
+        Ok(md.clone())
+    } else {
+        Err(missing_content())
+    }
+}
+
+fn missing_content() -> ServerError {
+    ServerError::new(ErrorCode::MissingContent, "Missing content or content_html")
+}
+
+#[utoipa::path(
+    get, path = "/recommendations", tag = "memory",
+    responses((status = 200, description = "Directories that would benefit from a CLAUDE.md", body = Vec<String>))
+)]
+pub async fn get_recommendations(State(state): State<Arc<AppState>>) -> Result<Json<Vec<String>>, ServerError> {
     let root = Path::new(&state.project_root);
 
-    let tree = analyzer::build_directory_tree(root)
-        .map_err(|e| ServerError::Internal(format!("Failed to build directory tree: {}", e)))?;
+    let tree = analyzer::build_directory_tree(root).context("Failed to build directory tree")?;
 
     // Get recommendations from analyzer
     let recommendations = analyzer::get_recommendations(&tree);
@@ -146,170 +295,856 @@ pub async fn get_recommendations(
     Ok(Json(relative_paths))
 }
 
-pub async fn get_chats(
-    State(state): State<Arc<AppState>>,
-) -> Result<Json<Vec<ChatMetadata>>, ServerError> {
+#[utoipa::path(
+    get, path = "/chats", tag = "chats",
+    responses((status = 200, body = Vec<ChatMetadata>))
+)]
+pub async fn get_chats(State(state): State<Arc<AppState>>) -> Result<Json<Vec<ChatMetadata>>, ServerError> {
     let root = Path::new(&state.project_root);
 
-    let chats = chat_exporter::find_project_chats(root)
-        .map_err(|e| ServerError::Internal(format!("Failed to find chats: {}", e)))?;
+    let chats = chat_exporter::find_project_chats(root).context("Failed to find chats")?;
 
     Ok(Json(chats))
 }
 
+#[utoipa::path(
+    post, path = "/chats/{session_id}/export", tag = "chats",
+    params(("session_id" = String, Path)),
+    request_body(content = Option<ExportChatRequest>),
+    responses((status = 200, body = ExportResult), (status = 500, body = ErrorResponse))
+)]
 pub async fn export_chat(
     State(state): State<Arc<AppState>>,
     AxumPath(session_id): AxumPath<String>,
-    Json(body): Json<Value>,
+    body: Option<Json<ExportChatRequest>>,
 ) -> Result<Json<ExportResult>, ServerError> {
     let root = Path::new(&state.project_root);
 
-    // Parse options from request body (use defaults if not provided)
-    let options = if let Some(opts) = body.as_object() {
-        ExportOptions {
-            include_tools: opts.get("include_tools")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(true),
-            include_timestamps: opts.get("include_timestamps")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(true),
-            include_thinking: opts.get("include_thinking")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(false),
-            max_tool_files: opts.get("max_tool_files")
-                .and_then(|v| v.as_u64())
-                .map(|v| v as usize)
-                .unwrap_or(5),
-        }
-    } else {
-        ExportOptions::default()
+    // Without a body, every option keeps its default
+    let options = match body {
+        Some(Json(request)) => request.options(),
+        None => ExportOptions::default(),
     };
 
-    let result = chat_exporter::export_chat(&session_id, root, &options, None)
-        .map_err(|e| ServerError::Internal(format!("Failed to export chat: {}", e)))?;
+    let _write = state.writes.lock().await;
+    let result = chat_exporter::export_chat(&session_id, root, &options, None).context("Failed to export chat")?;
 
     Ok(Json(result))
 }
 
 // ===== Docs Handlers =====
 
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DocsTreeQuery {
+    pub status: Option<String>, // Only docs whose front matter matches
+    pub owner: Option<String>,
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub sort: DocSortField, // Order of files within each folder
+    #[serde(default)]
+    pub order: SortOrder,
+}
+
+#[utoipa::path(
+    get, path = "/docs/tree", tag = "docs",
+    params(DocsTreeQuery),
+    responses((status = 200, description = "Null when the project has no doc roots", body = Option<DocsNode>))
+)]
 pub async fn get_docs_tree(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<DocsTreeQuery>,
 ) -> Result<Json<Option<DocsNode>>, ServerError> {
     let root = Path::new(&state.project_root);
 
-    // Find docs directory
-    let docs_dir = match docs::find_docs_dir(root) {
-        Some(dir) => dir,
-        None => return Ok(Json(None)), // No docs directory found
-    };
+    let roots = docs::doc_roots(root)?;
+    if roots.is_empty() {
+        return Ok(Json(None)); // No documentation folders found
+    }
 
     // Build docs tree
-    let tree = docs::build_docs_tree(&docs_dir)
-        .map_err(|e| ServerError::Internal(format!("Failed to build docs tree: {}", e)))?;
+    let tree = docs::build_docs_tree(root, &roots).context("Failed to build docs tree")?;
+
+    let filter = DocFilter { status: query.status, owner: query.owner, tag: query.tag };
+    let mut tree = front_matter::filter_tree(tree, &filter)
+        .ok_or_else(|| ServerError::internal("Docs tree root was filtered out"))?;
+    if query.sort != DocSortField::Name || query.order != SortOrder::Asc {
+        front_matter::sort_tree(&mut tree, query.sort, query.order);
+    }
 
     Ok(Json(Some(tree)))
 }
 
+#[utoipa::path(
+    get, path = "/docs/files/{path}", tag = "docs",
+    params(("path" = String, Path, description = "Project-relative; may contain slashes")),
+    responses(
+        (status = 200, body = DocFileResponse, headers(("ETag" = String))),
+        (status = 400, description = "Outside every doc root", body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
 pub async fn get_doc_file(
     State(state): State<Arc<AppState>>,
     AxumPath(path): AxumPath<String>,
-) -> Result<Json<DocFileResponse>, ServerError> {
-    let root = Path::new(&state.project_root);
-
-    // Find docs directory
-    let docs_dir = docs::find_docs_dir(root)
-        .ok_or_else(|| ServerError::NotFound("Docs directory not found".to_string()))?;
+) -> Result<impl IntoResponse, ServerError> {
+    // Reject paths outside the doc roots before treating read failures as missing files
+    let file_path = resolve_doc(&state, &path)?;
 
     // Read file content
-    let content = docs::read_doc_file(&docs_dir, &path)
-        .map_err(|e| ServerError::NotFound(format!("Failed to read doc file: {}", e)))?;
+    let content = docs::read_doc_file(&file_path).context("Failed to read doc file")?;
 
-    // Convert markdown to HTML
-    let content_html = markdown::markdown_to_html(&content);
+    // Front matter becomes metadata; only the body is rendered
+    let (metadata, body) = front_matter::parse(&content);
+    let rendered = markdown::render(body);
+    let content_hash = hash::calculate_hash(&content);
+    state.remember_version(&content_hash, &content);
 
-    Ok(Json(DocFileResponse {
-        path,
-        content,
-        content_html,
-        exists: true,
-    }))
+    Ok((
+        [(header::ETAG, hash::to_etag(&content_hash))],
+        Json(DocFileResponse {
+            path,
+            content,
+            content_html: rendered.html,
+            toc: rendered.toc,
+            content_hash,
+            exists: true,
+            metadata,
+        }),
+    ))
 }
 
+#[utoipa::path(
+    post, path = "/docs/files/{path}", tag = "docs",
+    params(("path" = String, Path, description = "Project-relative; may contain slashes")),
+    request_body = SaveFileRequest,
+    responses((status = 200, body = CreatedFile), (status = 409, body = ErrorResponse))
+)]
 pub async fn create_doc_file(
     State(state): State<Arc<AppState>>,
     AxumPath(path): AxumPath<String>,
-    Json(body): Json<Value>,
-) -> Result<Json<Value>, ServerError> {
-    let root = Path::new(&state.project_root);
-
-    // Find docs directory
-    let docs_dir = docs::find_docs_dir(root)
-        .ok_or_else(|| ServerError::NotFound("Docs directory not found".to_string()))?;
-
+    Json(body): Json<SaveFileRequest>,
+) -> Result<Json<CreatedFile>, ServerError> {
     // Check if file already exists
-    let file_path = docs_dir.join(&path);
+    let file_path = resolve_doc(&state, &path)?;
+    let _write = state.writes.lock().await;
     if file_path.exists() {
-        return Err(ServerError::Conflict(format!("File already exists: {}", path)));
+        return Err(ServerError::file_exists(&path));
     }
 
-    // Check if we're receiving HTML or markdown
-    let content_html = body.get("content_html").and_then(|v| v.as_str());
-    let content_md = body.get("content").and_then(|v| v.as_str());
-
-    let final_content = if let Some(html) = content_html {
-        // Convert HTML to markdown for saving
-        markdown::html_to_markdown(html)
-            .map_err(|e| ServerError::Internal(format!("Failed to convert HTML to markdown: {}", e)))?
-    } else if let Some(md) = content_md {
-        // Use markdown directly
-        md.to_string()
-    } else {
-        return Err(ServerError::BadRequest("Missing content or content_html".to_string()));
-    };
+    let final_content = doc_content(&body, None)?;
 
     // Write file
-    docs::write_doc_file(&docs_dir, &path, &final_content)
-        .map_err(|e| ServerError::Internal(format!("Failed to create doc file: {}", e)))?;
+    let written = docs::write_doc_file(&file_path, &final_content).context("Failed to create doc file")?;
+    state.reindex(&file_path);
+
+    let content_hash = hash::calculate_hash(&written);
+    state.remember_version(&content_hash, &written);
 
-    Ok(Json(serde_json::json!({
-        "path": path,
-        "content": final_content,
-        "created": true
-    })))
+    Ok(Json(CreatedFile { path, content: written, content_hash, created: true }))
 }
 
+#[utoipa::path(
+    put, path = "/docs/files/{path}", tag = "docs",
+    params(("path" = String, Path, description = "Project-relative; may contain slashes"), ("If-Match" = Option<String>, Header, description = "Hash the edit is based on")),
+    request_body = SaveFileRequest,
+    responses(
+        (status = 200, body = SavedFile, headers(("ETag" = String))),
+        (status = 409, description = "Changed on disk since `base_hash`", body = ErrorResponse),
+        (status = 428, description = "No base hash given", body = ErrorResponse),
+    )
+)]
 pub async fn update_doc_file(
     State(state): State<Arc<AppState>>,
     AxumPath(path): AxumPath<String>,
-    Json(body): Json<Value>,
-) -> Result<Json<Value>, ServerError> {
+    headers: HeaderMap,
+    Json(body): Json<SaveFileRequest>,
+) -> Result<impl IntoResponse, ServerError> {
+    let base_hash = require_base_hash(&headers, body.base_hash.as_deref())?;
+
+    let file_path = resolve_doc(&state, &path)?;
+    let _write = state.writes.lock().await;
+    let existing = std::fs::read_to_string(&file_path).ok();
+    let final_content = doc_content(&body, existing.as_deref())?;
+    check_base_hash(&state, &file_path, &path, &base_hash, &final_content)?;
+    snapshot_before(&state, &file_path, "update")?;
+
+    // Write file
+    let written = docs::write_doc_file(&file_path, &final_content).context("Failed to write doc file")?;
+    state.reindex(&file_path);
+
+    Ok(saved_response(&state, written))
+}
+
+#[utoipa::path(
+    delete, path = "/docs/files/{path}", tag = "docs",
+    params(("path" = String, Path, description = "Project-relative; may contain slashes")),
+    responses((status = 200, body = DeletedFile), (status = 404, body = ErrorResponse))
+)]
+pub async fn delete_doc_file(
+    State(state): State<Arc<AppState>>,
+    AxumPath(path): AxumPath<String>,
+) -> Result<Json<DeletedFile>, ServerError> {
     let root = Path::new(&state.project_root);
 
-    // Find docs directory
-    let docs_dir = docs::find_docs_dir(root)
-        .ok_or_else(|| ServerError::NotFound("Docs directory not found".to_string()))?;
+    let file_path = resolve_doc(&state, &path)?;
+    let _write = state.writes.lock().await;
+    if !file_path.exists() {
+        return Err(ServerError::file_not_found(&path));
+    }
 
-    // Check if we're receiving HTML or markdown
-    let content_html = body.get("content_html").and_then(|v| v.as_str());
-    let content_md = body.get("content").and_then(|v| v.as_str());
+    snapshot_before(&state, &file_path, "delete")?;
 
-    let final_content = if let Some(html) = content_html {
+    // Folders go to the trash whole, so they can be restored in one piece
+    let entry = trash::move_to_trash(root, &file_path, "api").context("Failed to delete doc")?;
+    state.reindex(&file_path);
+
+    Ok(Json(DeletedFile { path, deleted: true, trash_id: entry.id }))
+}
+
+/// Markdown to save from a create or update body.
+///
+/// HTML from the rich editor carries no front matter, so the file's current block is kept.
+/// A `metadata` object replaces the front matter either way.
+fn doc_content(body: &SaveFileRequest, existing: Option<&str>) -> Result<String, ServerError> {
+    let content = if let Some(html) = &body.content_html {
         // Convert HTML to markdown for saving
-        markdown::html_to_markdown(html)
-            .map_err(|e| ServerError::Internal(format!("Failed to convert HTML to markdown: {}", e)))?
-    } else if let Some(md) = content_md {
+        let markdown = markdown::html_to_markdown(html).context("Failed to convert HTML to markdown")?;
+        let block = existing.map(|c| front_matter::split(c).0).unwrap_or_default();
+        front_matter::with_block(block, &markdown)
+    } else if let Some(md) = &body.content {
         // Use markdown directly
-        md.to_string()
+        md.clone()
     } else {
-        return Err(ServerError::BadRequest("Missing content or content_html".to_string()));
+        return Err(missing_content());
     };
 
-    // Write file
-    docs::write_doc_file(&docs_dir, &path, &final_content)
-        .map_err(|e| ServerError::Internal(format!("Failed to write doc file: {}", e)))?;
+    match &body.metadata {
+        Some(metadata) => {
+            let block = front_matter::render(metadata).context("Failed to write front matter")?;
+            Ok(front_matter::with_block(&block, &content))
+        }
+        None => Ok(content),
+    }
+}
+
+/// Resolve a project-relative doc path against the project's doc roots
+fn resolve_doc(state: &AppState, path: &str) -> Result<PathBuf, ServerError> {
+    let root = Path::new(&state.project_root);
+    let roots = docs::doc_roots(root)?;
+    Ok(docs::resolve_doc_path(root, &roots, path)?)
+}
+
+// ===== Move Handlers =====
+
+#[utoipa::path(
+    post, path = "/move", tag = "files",
+    request_body = MoveRequest,
+    responses(
+        (status = 200, body = MoveResult),
+        (status = 404, body = ErrorResponse),
+        (status = 409, description = "Target exists", body = ErrorResponse),
+    )
+)]
+pub async fn move_file(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<MoveRequest>,
+) -> Result<Json<MoveResult>, ServerError> {
+    let root = Path::new(&state.project_root).canonicalize()?;
+
+    let (from_path, to_path) = match request.kind {
+        FileKind::Memory => {
+            (sandbox::resolve_memory_path(&root, &request.from)?, sandbox::resolve_memory_path(&root, &request.to)?)
+        }
+        FileKind::Docs => (resolve_doc(&state, &request.from)?, resolve_doc(&state, &request.to)?),
+    };
+
+    let _write = state.writes.lock().await;
+    if !from_path.exists() {
+        return Err(ServerError::file_not_found(&request.from));
+    }
+    if request.kind == FileKind::Memory && !from_path.is_file() {
+        return Err(ServerError::new(ErrorCode::InvalidMove, format!("Not a file: {}", request.from))
+            .with_details(json!({ "path": request.from })));
+    }
+    if to_path.starts_with(&from_path) {
+        return Err(ServerError::new(ErrorCode::InvalidMove, format!("Cannot move {} into itself", request.from))
+            .with_details(json!({ "path": request.from })));
+    }
+    if to_path.exists() {
+        return Err(ServerError::file_exists(&request.to));
+    }
+
+    let relative = |path: &Path| path.strip_prefix(&root).map(|p| p.to_string_lossy().to_string());
+    let (Ok(from), Ok(to)) = (relative(&from_path), relative(&to_path)) else {
+        return Err(ServerError::new(ErrorCode::PathOutsideProject, "Path resolves outside the project"));
+    };
+
+    let result = mover::move_path(&root, &from, &to, request.dry_run, "api")
+        .with_context(|| format!("Failed to move {}", request.from))?;
+
+    if !result.dry_run {
+        state.reindex(&from_path);
+        state.reindex(&to_path);
+        for rewrite in &result.rewrites {
+            state.reindex(&root.join(&rewrite.path));
+        }
+    }
+
+    Ok(Json(result))
+}
+
+// ===== Change Detection =====
+
+#[utoipa::path(
+    post, path = "/check-all", tag = "files",
+    request_body = CheckAllRequest,
+    responses((status = 200, body = Vec<FileHashStatus>))
+)]
+pub async fn check_all(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<CheckAllRequest>,
+) -> Result<Json<Vec<FileHashStatus>>, ServerError> {
+    let root = Path::new(&state.project_root);
+    let mut statuses = Vec::new();
+
+    for (path, known_hash) in &request.memory {
+        let file_path = sandbox::resolve_memory_path(root, path)?;
+        statuses.push(hash_status(&file_path, path, FileKind::Memory, known_hash));
+    }
+
+    if !request.docs.is_empty() {
+        let roots = docs::doc_roots(root)?;
+
+        for (path, known_hash) in &request.docs {
+            let file_path = docs::resolve_doc_path(root, &roots, path)?;
+            statuses.push(hash_status(&file_path, path, FileKind::Docs, known_hash));
+        }
+    }
+
+    statuses.sort_by(|a, b| (a.kind as u8, &a.path).cmp(&(b.kind as u8, &b.path)));
+    Ok(Json(statuses))
+}
+
+fn hash_status(file_path: &Path, path: &str, kind: FileKind, known_hash: &str) -> FileHashStatus {
+    let current_hash = hash::file_hash(file_path).ok();
+    let changed = current_hash.as_deref() != Some(hash::from_etag(known_hash).as_str());
+
+    FileHashStatus { path: path.to_string(), kind, current_hash, changed }
+}
+
+// ===== Merge Handlers =====
+
+#[utoipa::path(
+    post, path = "/merge", tag = "files",
+    request_body = MergeRequest,
+    responses((status = 200, body = MergeResult))
+)]
+pub async fn merge_versions(Json(request): Json<MergeRequest>) -> Result<Json<MergeResult>, ServerError> {
+    Ok(Json(merge::merge3(&request.base, &request.mine, &request.theirs)))
+}
+
+#[utoipa::path(
+    post, path = "/resolve", tag = "files",
+    request_body = ResolveRequest,
+    responses(
+        (status = 200, body = SavedFile, headers(("ETag" = String))),
+        (status = 400, description = "Conflict hunks left unresolved", body = ErrorResponse),
+        (status = 409, body = ErrorResponse),
+        (status = 428, description = "Neither `disk_hash` nor If-Match given", body = ErrorResponse),
+    )
+)]
+pub async fn resolve_conflict(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<ResolveRequest>,
+) -> Result<impl IntoResponse, ServerError> {
+    let root = Path::new(&state.project_root);
+    let disk_hash = match &request.disk_hash {
+        Some(disk_hash) => hash::from_etag(disk_hash),
+        None => require_base_hash(&headers, None)?,
+    };
+
+    let file_path = match request.kind {
+        FileKind::Memory => sandbox::resolve_memory_path(root, &request.path)?,
+        FileKind::Docs => resolve_doc(&state, &request.path)?,
+    };
+
+    let _write = state.writes.lock().await;
+    // "Theirs" is whatever is on disk now; it must still be the version the user resolved against
+    let theirs = std::fs::read_to_string(&file_path).unwrap_or_default();
+    let theirs_hash = hash::calculate_hash(&theirs);
+    check_base_hash(&state, &file_path, &request.path, &disk_hash, &request.mine)?;
+
+    let resolved = match request.strategy {
+        ResolveStrategy::KeepMine => request.mine,
+        ResolveStrategy::TakeTheirs => theirs,
+        ResolveStrategy::Merge => merge::apply_resolutions(&request.base, &request.mine, &theirs, &request.resolutions)
+            .map_err(|unresolved| {
+                ServerError::new(ErrorCode::UnresolvedConflicts, format!("Unresolved conflict hunks: {:?}", unresolved))
+                    .with_details(json!({ "hunks": unresolved }))
+            })?,
+    };
+
+    if hash::calculate_hash(&resolved) == theirs_hash {
+        return Ok(saved_response(&state, resolved));
+    }
+
+    snapshot_before(&state, &file_path, "resolve")?;
+    let written = writer::write_preserving(&file_path, &resolved).context("Failed to write file")?;
+    state.reindex(&file_path);
+
+    Ok(saved_response(&state, written))
+}
+
+// ===== History Handlers =====
+
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HistoryQuery {
+    pub path: Option<String>,
+}
+
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DiffQuery {
+    pub to: Option<String>,
+}
+
+#[utoipa::path(
+    get, path = "/history", tag = "history",
+    params(HistoryQuery),
+    responses((status = 200, body = Vec<Revision>))
+)]
+pub async fn list_history(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<Vec<Revision>>, ServerError> {
+    let root = Path::new(&state.project_root);
+
+    let revisions = history::list_revisions(root, query.path.as_deref()).context("Failed to read history")?;
+
+    Ok(Json(revisions))
+}
+
+#[utoipa::path(
+    get, path = "/history/{id}", tag = "history",
+    params(("id" = String, Path)),
+    responses((status = 200, body = RevisionContent), (status = 404, body = ErrorResponse))
+)]
+pub async fn get_revision(
+    State(state): State<Arc<AppState>>,
+    AxumPath(id): AxumPath<String>,
+) -> Result<Json<RevisionContent>, ServerError> {
+    let root = Path::new(&state.project_root);
+
+    let revision = history::read_revision(root, &id)?;
+
+    Ok(Json(revision))
+}
+
+#[utoipa::path(
+    get, path = "/history/{id}/diff", tag = "history",
+    params(("id" = String, Path), DiffQuery),
+    responses((status = 200, body = RevisionDiff), (status = 400, body = ErrorResponse))
+)]
+pub async fn diff_revision(
+    State(state): State<Arc<AppState>>,
+    AxumPath(id): AxumPath<String>,
+    Query(query): Query<DiffQuery>,
+) -> Result<Json<RevisionDiff>, ServerError> {
+    let root = Path::new(&state.project_root);
+    let to = query.to.unwrap_or_else(|| "current".to_string());
+
+    let diff = history::diff_revisions(root, &id, &to).context("Failed to diff revisions")?;
+
+    Ok(Json(diff))
+}
+
+#[utoipa::path(
+    post, path = "/history/{id}/restore", tag = "history",
+    params(("id" = String, Path)),
+    responses((status = 200, body = SavedFile, headers(("ETag" = String))), (status = 500, body = ErrorResponse))
+)]
+pub async fn restore_revision(
+    State(state): State<Arc<AppState>>,
+    AxumPath(id): AxumPath<String>,
+) -> Result<impl IntoResponse, ServerError> {
+    let root = Path::new(&state.project_root);
+
+    let _write = state.writes.lock().await;
+    let restored = history::restore_revision(root, &id, "api").context("Failed to restore revision")?;
+    state.reindex(&sandbox::resolve_path(root, &restored.revision.path)?);
+
+    Ok(saved_response(&state, restored.content))
+}
+
+// ===== Trash Handlers =====
+
+#[utoipa::path(
+    get, path = "/trash", tag = "trash",
+    responses((status = 200, body = Vec<TrashEntry>))
+)]
+pub async fn list_trash(State(state): State<Arc<AppState>>) -> Result<Json<Vec<TrashEntry>>, ServerError> {
+    let root = Path::new(&state.project_root);
+
+    let entries = trash::list_trash(root).context("Failed to read trash")?;
+
+    Ok(Json(entries))
+}
+
+#[utoipa::path(
+    post, path = "/trash/{id}/restore", tag = "trash",
+    params(("id" = String, Path)),
+    responses(
+        (status = 200, body = TrashEntry),
+        (status = 404, body = ErrorResponse),
+        (status = 409, description = "Something is already at the original path", body = ErrorResponse),
+    )
+)]
+pub async fn restore_trash_entry(
+    State(state): State<Arc<AppState>>,
+    AxumPath(id): AxumPath<String>,
+) -> Result<Json<TrashEntry>, ServerError> {
+    let root = Path::new(&state.project_root);
+
+    let _write = state.writes.lock().await;
+    let entry = trash::find_entry(root, &id)?;
+    if sandbox::resolve_path(root, &entry.path)?.exists() {
+        return Err(ServerError::file_exists(&entry.path));
+    }
+
+    let restored = trash::restore(root, &id).context("Failed to restore from trash")?;
+    state.reindex(&sandbox::resolve_path(root, &restored.path)?);
+
+    Ok(Json(restored))
+}
+
+#[utoipa::path(
+    delete, path = "/trash/{id}", tag = "trash",
+    params(("id" = String, Path)),
+    responses((status = 200, description = "The purged entries", body = Vec<TrashEntry>), (status = 404, body = ErrorResponse))
+)]
+pub async fn purge_trash_entry(
+    State(state): State<Arc<AppState>>,
+    AxumPath(id): AxumPath<String>,
+) -> Result<Json<Vec<TrashEntry>>, ServerError> {
+    let root = Path::new(&state.project_root);
+
+    let _write = state.writes.lock().await;
+    trash::find_entry(root, &id)?;
+
+    let purged = trash::purge(root, Some(&id)).context("Failed to purge trash entry")?;
+
+    Ok(Json(purged))
+}
+
+#[utoipa::path(
+    delete, path = "/trash", tag = "trash",
+    responses((status = 200, description = "The purged entries", body = Vec<TrashEntry>))
+)]
+pub async fn empty_trash(State(state): State<Arc<AppState>>) -> Result<Json<Vec<TrashEntry>>, ServerError> {
+    let root = Path::new(&state.project_root);
+
+    let _write = state.writes.lock().await;
+    let purged = trash::purge(root, None).context("Failed to empty trash")?;
+
+    Ok(Json(purged))
+}
+
+/// Keep a copy of the file's current contents in the history store before changing it
+fn snapshot_before(state: &AppState, file_path: &Path, operation: &str) -> Result<(), ServerError> {
+    history::snapshot(Path::new(&state.project_root), file_path, operation, "api")
+        .context("Failed to snapshot file history")?;
+    Ok(())
+}
+
+/// Read the hash the client based its edit on, from `If-Match` or a `base_hash` field
+fn require_base_hash(headers: &HeaderMap, base_hash: Option<&str>) -> Result<String, ServerError> {
+    let from_header = headers.get(header::IF_MATCH).and_then(|v| v.to_str().ok()).map(hash::from_etag);
+    let from_body = base_hash.map(hash::from_etag);
+
+    from_header
+        .or(from_body)
+        .ok_or_else(|| ServerError::new(ErrorCode::BaseHashRequired, "Missing If-Match header or base_hash field"))
+}
+
+/// Reject the write if the file on disk no longer matches the version the client edited
+fn check_base_hash(
+    state: &AppState,
+    file_path: &Path,
+    path: &str,
+    base_hash: &str,
+    submitted: &str,
+) -> Result<(), ServerError> {
+    let disk_content = std::fs::read_to_string(file_path).ok();
+    let disk_hash = disk_content.as_deref().map(hash::calculate_hash);
+
+    if disk_hash.as_deref() == Some(base_hash) {
+        return Ok(());
+    }
+
+    let base_content = state.known_version(base_hash);
+    let merge = match (&base_content, &disk_content) {
+        (Some(base), Some(disk)) => Some(merge::merge3(base, submitted, disk)),
+        _ => None,
+    };
+
+    Err(ServerError::conflict(FileConflict {
+        path: path.to_string(),
+        base_hash: base_hash.to_string(),
+        base_content,
+        disk_hash,
+        disk_content,
+        submitted_content: submitted.to_string(),
+        merge,
+    }))
+}
+
+/// Build the response for a successful save, carrying the new version as an ETag
+fn saved_response(state: &AppState, content: String) -> impl IntoResponse {
+    let content_hash = hash::calculate_hash(&content);
+    state.remember_version(&content_hash, &content);
+
+    ([(header::ETAG, hash::to_etag(&content_hash))], Json(SavedFile { content, content_hash }))
+}
+
+// ===== Search Handlers =====
+
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchQuery {
+    pub q: String,
+    pub kind: Option<FileKind>,
+    pub scope: Option<MemoryScope>,
+    pub dir: Option<String>, // Project-relative directory to search within
+    pub limit: Option<usize>,
+}
+
+#[utoipa::path(
+    get, path = "/search", tag = "search",
+    params(SearchQuery),
+    responses((status = 200, body = SearchResponse), (status = 400, body = ErrorResponse))
+)]
+pub async fn search(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<SearchResponse>, ServerError> {
+    if query.q.trim().is_empty() {
+        return Err(ServerError::new(ErrorCode::EmptyQuery, "Query must not be empty"));
+    }
+
+    let filters = SearchFilters { kind: query.kind, scope: query.scope, dir: query.dir };
+    let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).min(MAX_SEARCH_LIMIT);
+
+    let index = state.search_index().await.context("Failed to build search index")?;
+    let (total, results) = index.search(&query.q, &filters, limit);
+
+    Ok(Json(SearchResponse { query: query.q, total, results }))
+}
+
+// ===== ADR Handlers =====
+
+#[utoipa::path(
+    get, path = "/adr", tag = "adr",
+    responses((status = 200, body = Vec<AdrSummary>))
+)]
+pub async fn list_adrs(State(state): State<Arc<AppState>>) -> Result<Json<Vec<AdrSummary>>, ServerError> {
+    let adrs = adr::list(Path::new(&state.project_root))?;
+    Ok(Json(adrs))
+}
+
+#[utoipa::path(
+    post, path = "/adr", tag = "adr",
+    request_body = CreateAdrRequest,
+    responses((status = 200, body = AdrSummary), (status = 400, body = ErrorResponse), (status = 409, body = ErrorResponse))
+)]
+pub async fn create_adr(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<CreateAdrRequest>,
+) -> Result<Json<AdrSummary>, ServerError> {
+    let root = Path::new(&state.project_root);
+    let _write = state.writes.lock().await;
+    let created = adr::create(root, &request.title, &request.supersedes, "api")?;
+    reindex_adrs(&state);
+
+    Ok(Json(created))
+}
+
+#[utoipa::path(
+    put, path = "/adr/{number}/status", tag = "adr",
+    params(("number" = u32, Path)),
+    request_body = AdrStatusRequest,
+    responses(
+        (status = 200, body = AdrSummary),
+        (status = 404, body = ErrorResponse),
+        (status = 409, description = "Not allowed from the current status", body = ErrorResponse),
+    )
+)]
+pub async fn set_adr_status(
+    State(state): State<Arc<AppState>>,
+    AxumPath(number): AxumPath<u32>,
+    Json(request): Json<AdrStatusRequest>,
+) -> Result<Json<AdrSummary>, ServerError> {
+    let root = Path::new(&state.project_root);
+    let _write = state.writes.lock().await;
+    let updated = adr::set_status(root, number, request.status, request.superseded_by, "api")?;
+    reindex_adrs(&state);
+
+    Ok(Json(updated))
+}
+
+#[utoipa::path(
+    post, path = "/adr/index", tag = "adr",
+    responses((status = 200, body = AdrIndexResponse))
+)]
+pub async fn write_adr_index(State(state): State<Arc<AppState>>) -> Result<Json<AdrIndexResponse>, ServerError> {
+    let root = Path::new(&state.project_root).canonicalize()?;
+    let _write = state.writes.lock().await;
+    let index = adr::write_index(&root)?;
+    state.reindex(&index);
+
+    Ok(Json(AdrIndexResponse { path: paths::project_relative(&root, &index).unwrap_or_default() }))
+}
+
+/// ADR operations touch several files; refresh the search index over the whole folder
+fn reindex_adrs(state: &AppState) {
+    if let Ok(dir) = adr::adr_dir(Path::new(&state.project_root)) {
+        state.reindex(&dir);
+    }
+}
+
+// ===== Plan Handlers =====
+
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PlanListQuery {
+    pub include: Option<String>, // `tasks` to also return every task
+}
+
+#[utoipa::path(
+    get, path = "/plans", tag = "plans",
+    params(PlanListQuery),
+    responses((status = 200, body = Vec<PlanSummary>))
+)]
+pub async fn list_plans(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PlanListQuery>,
+) -> Result<Json<Vec<PlanSummary>>, ServerError> {
+    let include_tasks = query.include.as_deref() == Some("tasks");
+    let list = plans::list(Path::new(&state.project_root), include_tasks)?;
+    Ok(Json(list))
+}
+
+#[utoipa::path(
+    get, path = "/plans/{path}", tag = "plans",
+    params(("path" = String, Path, description = "Project-relative; may contain slashes")),
+    responses((status = 200, body = PlanSummary), (status = 404, body = ErrorResponse))
+)]
+pub async fn get_plan(
+    State(state): State<Arc<AppState>>,
+    AxumPath(path): AxumPath<String>,
+) -> Result<Json<PlanSummary>, ServerError> {
+    let plan = plans::get(Path::new(&state.project_root), &path)?;
+    Ok(Json(plan))
+}
+
+#[utoipa::path(
+    post, path = "/plans/toggle", tag = "plans",
+    params(("If-Match" = Option<String>, Header, description = "Content hash the toggle is based on")),
+    request_body = ToggleTaskRequest,
+    responses(
+        (status = 200, body = PlanSummary),
+        (status = 400, description = "Line is not a task", body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+        (status = 409, description = "Plan or task changed", body = ErrorResponse),
+    )
+)]
+pub async fn toggle_plan_task(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<ToggleTaskRequest>,
+) -> Result<Json<PlanSummary>, ServerError> {
+    let base_hash = headers
+        .get(header::IF_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(hash::from_etag)
+        .or(request.base_hash.as_deref().map(hash::from_etag));
+
+    let _write = state.writes.lock().await;
+    let root = Path::new(&state.project_root);
+    let plan = plans::toggle(
+        root,
+        &request.path,
+        request.line,
+        request.checked,
+        request.text.as_deref(),
+        base_hash.as_deref(),
+        "api",
+    )?;
+    state.reindex(&root.join(&plan.path));
+
+    Ok(Json(plan))
+}
+
+// ===== Link Graph Handlers =====
+
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BacklinksQuery {
+    pub path: String, // Project-relative file, memory or doc
+}
+
+#[utoipa::path(
+    get, path = "/links/backlinks", tag = "links",
+    params(BacklinksQuery),
+    responses((status = 200, body = BacklinksResponse))
+)]
+pub async fn get_backlinks(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<BacklinksQuery>,
+) -> Result<Json<BacklinksResponse>, ServerError> {
+    let root = Path::new(&state.project_root).canonicalize()?;
+
+    // The file may be gone; its backlinks are then exactly the ones that are broken
+    let file_path = sandbox::resolve_path(&root, &query.path)?;
+    let relative = paths::project_relative(&root, &file_path).unwrap_or(query.path);
+
+    let graph = LinkGraph::build(&root).context("Failed to build link graph")?;
+
+    Ok(Json(graph.backlinks(&relative)))
+}
+
+#[utoipa::path(
+    get, path = "/links/broken", tag = "links",
+    responses((status = 200, body = BrokenLinkReport))
+)]
+pub async fn get_broken_links(State(state): State<Arc<AppState>>) -> Result<Json<BrokenLinkReport>, ServerError> {
+    let graph = LinkGraph::build(Path::new(&state.project_root)).context("Failed to build link graph")?;
+
+    Ok(Json(graph.broken_report()))
+}
+
+// ===== Server Handlers =====
+
+#[utoipa::path(
+    get, path = "/server", tag = "server",
+    responses((status = 200, body = ServerInfo))
+)]
+pub async fn get_server_info(State(state): State<Arc<AppState>>) -> Result<Json<ServerInfo>, ServerError> {
+    let info = state.server_info.clone().ok_or_else(|| ServerError::internal("Server is not registered"))?;
+
+    Ok(Json(info))
+}
+
+/// Stops the server the same way SIGTERM does; the response goes out before connections close
+#[utoipa::path(
+    post, path = "/server/shutdown", tag = "server",
+    responses((status = 200, body = ServerInfo))
+)]
+pub async fn shutdown_server(State(state): State<Arc<AppState>>) -> Result<Json<ServerInfo>, ServerError> {
+    let info = state.server_info.clone().ok_or_else(|| ServerError::internal("Server is not registered"))?;
+    state.shutdown.notify_one();
 
-    Ok(Json(serde_json::json!({
-        "content": final_content
-    })))
+    Ok(Json(info))
 }
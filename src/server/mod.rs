@@ -1,27 +1,257 @@
 use anyhow::Result;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::io::{ErrorKind, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::sync::{watch, Mutex as AsyncMutex, Notify};
 
-pub mod routes;
-pub mod handlers;
+use crate::models::ServerInfo;
+use crate::services::search::{self, SearchCache};
+use crate::utils::metrics;
+use crate::utils::search::SearchIndex;
+use frontend::Frontend;
+
+pub mod auth;
+pub mod daemon;
 pub mod error;
+pub mod frontend;
+pub mod handlers;
+pub mod openapi;
+pub mod request_id;
+pub mod routes;
+#[cfg(unix)]
+pub mod socket;
+pub mod telemetry;
+
+/// Upper bound on remembered file versions before the cache is reset
+const MAX_KNOWN_VERSIONS: usize = 512;
+
+/// Ports tried after the requested one is taken, before letting the OS pick any free port
+const PORT_ATTEMPTS: u16 = 20;
+
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+    pub project: String,
+    /// Fixed access token; a random one is generated per launch when unset
+    pub token: Option<String>,
+    /// Extra origins allowed to call the API cross-origin
+    pub allowed_origins: Vec<String>,
+    /// Serve the dashboard from this directory instead of the copy built into the binary
+    pub frontend_dir: Option<PathBuf>,
+    /// Also listen on this Unix domain socket
+    pub socket: Option<PathBuf>,
+    /// File mode of the socket, e.g. `0o660` to let the group connect
+    pub socket_mode: u32,
+    /// Listen on `host:port`; off when only the socket should be reachable
+    pub tcp: bool,
+}
 
 pub struct AppState {
     pub project_root: String,
+    pub access_token: String,
+    /// Content of recently served versions, keyed by hash, so conflicts can report the base
+    pub known_versions: Mutex<HashMap<String, String>>,
+    /// Full-text index over memory files and docs, built on first search
+    pub search: Mutex<SearchCache>,
+    /// Held while the search index is refreshed, so concurrent searches wait for one refresh
+    search_refresh: AsyncMutex<()>,
+    /// Held by every handler that changes the tree, from its base-hash or existence checks
+    /// until the change is made, so no two of them act on the same state
+    pub writes: AsyncMutex<()>,
+    pub frontend: Frontend,
+    /// Host names the dashboard page, which carries the token, may be served under
+    pub allowed_hosts: Vec<String>,
+    /// Where this server listens, as reported by `GET /server`
+    pub server_info: Option<ServerInfo>,
+    /// Notified by `POST /server/shutdown`
+    pub shutdown: Notify,
 }
 
-pub async fn run(port: u16, project: String) -> Result<()> {
-    let state = Arc::new(AppState {
-        project_root: project.clone(),
-    });
+impl AppState {
+    pub fn new(project_root: String, access_token: String, frontend: Frontend) -> Self {
+        Self {
+            project_root,
+            access_token,
+            frontend,
+            known_versions: Mutex::new(HashMap::new()),
+            search: Mutex::new(SearchCache::default()),
+            search_refresh: AsyncMutex::new(()),
+            writes: AsyncMutex::new(()),
+            allowed_hosts: auth::LOOPBACK_HOSTS.iter().map(|h| h.to_string()).collect(),
+            server_info: None,
+            shutdown: Notify::new(),
+        }
+    }
+
+    /// Remember a version of a file that was handed to a client
+    pub fn remember_version(&self, hash: &str, content: &str) {
+        let mut versions = self.known_versions.lock().unwrap();
+        if versions.len() >= MAX_KNOWN_VERSIONS && !versions.contains_key(hash) {
+            versions.clear();
+        }
+        versions.insert(hash.to_string(), content.to_string());
+    }
 
-    let app = routes::create_router(state);
-    let addr = format!("0.0.0.0:{}", port);
+    /// Update the search index after a file or folder was written, deleted or restored
+    pub fn reindex(&self, path: &Path) {
+        // Read the disk first, so the lock every search needs is only held to apply the result
+        let built = self.search.lock().unwrap().is_built();
+        let change = built.then(|| search::Change::read(Path::new(&self.project_root), path));
+        self.search.lock().unwrap().apply(change);
+    }
 
-    println!("Server running at http://{}", addr);
-    println!("Analyzing project: {}", project);
+    /// The search index, refreshed from disk on a blocking thread if it is due
+    pub async fn search_index(&self) -> Result<Arc<SearchIndex>> {
+        if let Some(index) = self.search.lock().unwrap().current() {
+            return Ok(index);
+        }
 
-    let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+        let _refreshing = self.search_refresh.lock().await;
+        // Another search may have refreshed it while this one waited
+        if let Some(index) = self.search.lock().unwrap().current() {
+            return Ok(index);
+        }
+
+        let refresh = self.search.lock().unwrap().begin_refresh();
+        let root = PathBuf::from(&self.project_root);
+        let refresh = tokio::task::spawn_blocking(move || refresh.run(&root)).await??;
+        Ok(self.search.lock().unwrap().finish_refresh(refresh))
+    }
+
+    /// Look up the content of a previously served version
+    pub fn known_version(&self, hash: &str) -> Option<String> {
+        let version = self.known_versions.lock().unwrap().get(hash).cloned();
+        metrics::cache_lookup("known_versions", version.is_some());
+        version
+    }
+}
 
+pub async fn run(mut config: ServerConfig) -> Result<()> {
+    if !config.tcp && config.socket.is_none() {
+        anyhow::bail!("--no-tcp needs --socket, or the server would not be reachable at all");
+    }
+    let token = match config.token.clone() {
+        Some(token) => token,
+        None => auth::generate_token()?,
+    };
+    let frontend = match &config.frontend_dir {
+        Some(dir) => Frontend::Dir(dir.clone()),
+        None => Frontend::Embedded,
+    };
+    let project_root = Path::new(&config.project).canonicalize()?;
+    daemon::ensure_not_running(&project_root)?;
+
+    let listener = if config.tcp { Some(bind(&config.host, config.port).await?) } else { None };
+    if let Some(listener) = &listener {
+        let port = listener.local_addr()?.port();
+        if port != config.port {
+            tracing::warn!("Port {} is in use; using {} instead", config.port, port);
+            config.port = port;
+        }
+    }
+    config.socket = config.socket.as_deref().map(std::path::absolute).transpose()?;
+    #[cfg(unix)]
+    let socket_listener = match &config.socket {
+        Some(path) => Some(socket::bind(path, config.socket_mode)?),
+        None => None,
+    };
+    #[cfg(not(unix))]
+    if config.socket.is_some() {
+        anyhow::bail!("--socket is only supported on Unix");
+    }
+
+    let info = daemon::describe(
+        &project_root,
+        listener.as_ref().map(|_| (config.host.as_str(), config.port)),
+        config.socket.as_deref(),
+        daemon::is_daemon(),
+    );
+    let mut state = AppState::new(config.project.clone(), token.clone(), frontend);
+    state.server_info = Some(info.clone());
+    if !state.allowed_hosts.contains(&config.host) {
+        state.allowed_hosts.push(config.host.clone());
+    }
+    let state = Arc::new(state);
+
+    // Built after binding, so the allowed origins name the port actually in use
+    let app = routes::create_router(state.clone(), &config);
+    daemon::register(&project_root, &info)?;
+
+    tracing::info!(project = %config.project, pid = info.pid, "Server running at {}", daemon::address(&info));
+    if let (Some(_), Some(socket)) = (&info.url, &info.socket) {
+        tracing::info!("Also listening on {}", socket.display());
+    }
+    if listener.is_some() {
+        // Never into the log, which a daemon writes to a file; `serve --daemon` shows it instead
+        if std::io::stdout().is_terminal() {
+            println!("🔑 API access token: {}", token);
+        } else if !daemon::is_daemon() && config.token.is_none() {
+            tracing::warn!("stdout is not a terminal, so the access token isn't shown; set --token to choose one");
+        }
+        if !is_loopback(&config.host) {
+            tracing::warn!("Listening on {} exposes the API beyond this machine", config.host);
+        }
+    }
+
+    // Stop accepting connections on SIGTERM, Ctrl+C or `POST /server/shutdown`,
+    // but let in-flight requests (and their writes) finish
+    let (stop, stopped) = watch::channel(false);
+    let signals = state.clone();
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = daemon::shutdown_signal() => {}
+            _ = signals.shutdown.notified() => {}
+        }
+        tracing::info!("Shutting down; finishing in-flight requests");
+        let _ = stop.send(true);
+    });
+
+    let tcp = async {
+        let Some(listener) = listener else {
+            return Ok(());
+        };
+        let mut stopped = stopped.clone();
+        axum::serve(listener, app.clone())
+            .with_graceful_shutdown(async move {
+                let _ = stopped.wait_for(|stop| *stop).await;
+            })
+            .await
+            .map_err(anyhow::Error::from)
+    };
+    let unix = async {
+        #[cfg(unix)]
+        if let Some(listener) = socket_listener {
+            return socket::serve(listener, app.clone(), stopped.clone()).await;
+        }
+        Ok(())
+    };
+    let served = tokio::try_join!(tcp, unix);
+
+    daemon::unregister(&project_root);
+    if let Some(path) = &config.socket {
+        let _ = std::fs::remove_file(path);
+    }
+    served?;
+    tracing::info!("Server stopped");
     Ok(())
 }
+
+/// Bind the requested port, or the next free one after it
+async fn bind(host: &str, port: u16) -> Result<TcpListener> {
+    let candidates = (0..PORT_ATTEMPTS).filter_map(|offset| port.checked_add(offset));
+    for candidate in candidates {
+        match TcpListener::bind((host, candidate)).await {
+            Ok(listener) => return Ok(listener),
+            Err(e) if e.kind() == ErrorKind::AddrInUse => continue,
+            Err(e) => return Err(anyhow::anyhow!("Failed to listen on {}:{}: {}", host, candidate, e)),
+        }
+    }
+
+    TcpListener::bind((host, 0)).await.map_err(|e| anyhow::anyhow!("Failed to find a free port on {}: {}", host, e))
+}
+
+fn is_loopback(host: &str) -> bool {
+    host == "localhost" || host.parse::<std::net::IpAddr>().map(|ip| ip.is_loopback()).unwrap_or(false)
+}
@@ -0,0 +1,77 @@
+use axum::response::Json;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use super::handlers;
+
+/// Where the current API version is mounted
+pub const API_V1: &str = "/api/v1";
+
+/// OpenAPI description of `/api/v1`, generated from the handlers and models
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "cc-atlas API", description = "Memory files, docs and their history for one project"),
+    servers((url = "/api/v1")),
+    security(("token" = [])),
+    modifiers(&TokenAuth),
+    paths(
+        handlers::get_memory_files,
+        handlers::get_memory_file,
+        handlers::update_memory_file,
+        handlers::create_memory_file,
+        handlers::delete_memory_file,
+        handlers::batch_memory_files,
+        handlers::get_directory_tree,
+        handlers::get_recommendations,
+        handlers::get_chats,
+        handlers::export_chat,
+        handlers::get_docs_tree,
+        handlers::get_doc_file,
+        handlers::create_doc_file,
+        handlers::update_doc_file,
+        handlers::delete_doc_file,
+        handlers::move_file,
+        handlers::check_all,
+        handlers::merge_versions,
+        handlers::resolve_conflict,
+        handlers::list_history,
+        handlers::get_revision,
+        handlers::diff_revision,
+        handlers::restore_revision,
+        handlers::list_trash,
+        handlers::empty_trash,
+        handlers::purge_trash_entry,
+        handlers::restore_trash_entry,
+        handlers::search,
+        handlers::list_adrs,
+        handlers::create_adr,
+        handlers::write_adr_index,
+        handlers::set_adr_status,
+        handlers::list_plans,
+        handlers::get_plan,
+        handlers::toggle_plan_task,
+        handlers::get_backlinks,
+        handlers::get_broken_links,
+        handlers::get_server_info,
+        handlers::shutdown_server,
+    )
+)]
+pub struct ApiDoc;
+
+/// Every endpoint needs the launch token as a bearer token
+struct TokenAuth;
+
+impl Modify for TokenAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "token",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+/// Served without the token, so clients can be generated before connecting
+pub async fn serve_openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
@@ -0,0 +1,57 @@
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+pub const HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest caller-supplied ID we pass through; anything else gets a fresh one
+const MAX_INCOMING_LEN: usize = 64;
+const ID_BYTES: usize = 8;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Tag every request with an ID, echoed in `X-Request-Id` and in error bodies
+pub async fn assign(request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(&HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| is_valid(v))
+        .map(str::to_string)
+        .unwrap_or_else(generate);
+
+    let mut response = REQUEST_ID.scope(id.clone(), next.run(request)).await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(HEADER, value);
+    }
+    response
+}
+
+/// The ID of the request being handled, if any
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+fn is_valid(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_INCOMING_LEN
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+fn generate() -> String {
+    let mut bytes = [0u8; ID_BYTES];
+    if getrandom::getrandom(&mut bytes).is_err() {
+        // Only used to correlate logs, so a clock-based ID will do
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        bytes = nanos.to_be_bytes();
+    }
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -1,19 +1,21 @@
 use axum::{
+    http::{header, HeaderValue, Method},
+    middleware,
     routing::{delete, get, post, put},
     Router,
 };
-use std::path::PathBuf;
 use std::sync::Arc;
-use tower_http::cors::CorsLayer;
-use tower_http::services::ServeDir;
+use tower_http::cors::{AllowOrigin, CorsLayer};
 
-use super::AppState;
-use super::handlers;
+use super::{auth, error, frontend, handlers, openapi, request_id, telemetry};
+use super::{AppState, ServerConfig};
 
-pub fn create_router(state: Arc<AppState>) -> Router {
+pub fn create_router(state: Arc<AppState>, config: &ServerConfig) -> Router {
     let api_routes = Router::new()
         .route("/memory-files", get(handlers::get_memory_files))
+        .route("/memory-files/batch", post(handlers::batch_memory_files))
         .route("/tree", get(handlers::get_directory_tree))
+        .route("/memory-files/*path", get(handlers::get_memory_file))
         .route("/memory-files/*path", put(handlers::update_memory_file))
         .route("/memory-files/*path", post(handlers::create_memory_file))
         .route("/memory-files/*path", delete(handlers::delete_memory_file))
@@ -24,14 +26,69 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/docs/files/*path", get(handlers::get_doc_file))
         .route("/docs/files/*path", post(handlers::create_doc_file))
         .route("/docs/files/*path", put(handlers::update_doc_file))
-        .with_state(state);
+        .route("/docs/files/*path", delete(handlers::delete_doc_file))
+        .route("/check-all", post(handlers::check_all))
+        .route("/merge", post(handlers::merge_versions))
+        .route("/resolve", post(handlers::resolve_conflict))
+        .route("/history", get(handlers::list_history))
+        .route("/history/:id", get(handlers::get_revision))
+        .route("/history/:id/diff", get(handlers::diff_revision))
+        .route("/history/:id/restore", post(handlers::restore_revision))
+        .route("/trash", get(handlers::list_trash))
+        .route("/trash", delete(handlers::empty_trash))
+        .route("/trash/:id", delete(handlers::purge_trash_entry))
+        .route("/trash/:id/restore", post(handlers::restore_trash_entry))
+        .route("/search", get(handlers::search))
+        .route("/move", post(handlers::move_file))
+        .route("/adr", get(handlers::list_adrs))
+        .route("/adr", post(handlers::create_adr))
+        .route("/adr/index", post(handlers::write_adr_index))
+        .route("/adr/:number/status", put(handlers::set_adr_status))
+        .route("/plans", get(handlers::list_plans))
+        .route("/plans/toggle", post(handlers::toggle_plan_task))
+        .route("/plans/*path", get(handlers::get_plan))
+        .route("/links/backlinks", get(handlers::get_backlinks))
+        .route("/links/broken", get(handlers::get_broken_links))
+        .route("/server", get(handlers::get_server_info))
+        .route("/server/shutdown", post(handlers::shutdown_server))
+        .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_token))
+        .route("/openapi.json", get(openapi::serve_openapi))
+        .layer(middleware::from_fn(error::json_errors))
+        .with_state(state.clone());
 
-    let frontend_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join("frontend")
-        .join("dist");
+    let frontend_routes = Router::new()
+        .route("/", get(frontend::serve_index))
+        .route("/index.html", get(frontend::serve_index))
+        .route("/highlight.css", get(frontend::serve_highlight_css))
+        .fallback(frontend::serve_asset)
+        .with_state(state);
 
     Router::new()
+        .nest(openapi::API_V1, api_routes.clone())
+        // Unversioned paths from before `/api/v1`, kept for existing clients
         .nest("/api", api_routes)
-        .fallback_service(ServeDir::new(frontend_dir).append_index_html_on_directories(true))
-        .layer(CorsLayer::permissive())
+        .route("/metrics", get(telemetry::serve_metrics))
+        .merge(frontend_routes)
+        .layer(cors_layer(config))
+        .layer(middleware::from_fn(telemetry::track_requests))
+        .layer(middleware::from_fn(request_id::assign))
+}
+
+/// Only the server's own origin, plus any explicitly configured ones, may call the API
+fn cors_layer(config: &ServerConfig) -> CorsLayer {
+    let mut origins = vec![format!("http://{}:{}", config.host, config.port)];
+    if config.host == "127.0.0.1" || config.host == "localhost" {
+        origins.push(format!("http://localhost:{}", config.port));
+        origins.push(format!("http://127.0.0.1:{}", config.port));
+    }
+    origins.extend(config.allowed_origins.iter().cloned());
+
+    let origins: Vec<HeaderValue> =
+        origins.iter().filter_map(|origin| HeaderValue::from_str(origin.trim_end_matches('/')).ok()).collect();
+
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(origins))
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE, header::IF_MATCH, request_id::HEADER])
+        .expose_headers([header::ETAG, request_id::HEADER])
 }
@@ -0,0 +1,126 @@
+use anyhow::{Context, Result};
+use axum::{
+    body::Bytes,
+    http::{header, Method},
+    Extension, Router,
+};
+use http_body_util::{BodyExt, Empty};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use hyper_util::server::graceful::GracefulShutdown;
+use hyper_util::service::TowerToHyperService;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+use std::os::unix::net::UnixStream as StdUnixStream;
+use std::path::Path;
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::watch;
+
+use super::auth::TrustedTransport;
+use super::openapi::API_V1;
+use crate::models::ErrorResponse;
+
+/// Listen on `path`, readable and writable only as `mode` allows
+///
+/// A socket left behind by a crashed server is replaced; one that still answers is an error.
+pub fn bind(path: &Path, mode: u32) -> Result<UnixListener> {
+    if let Ok(metadata) = fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            anyhow::bail!("{} exists and is not a socket", path.display());
+        }
+        if StdUnixStream::connect(path).is_ok() {
+            anyhow::bail!("Another server is already listening on {}", path.display());
+        }
+        fs::remove_file(path).with_context(|| format!("Failed to remove stale socket {}", path.display()))?;
+    }
+
+    // Bind inside a directory only we can enter, set the final permissions, then move the socket into
+    // place, so it is never open to others even briefly and the process-wide umask is left alone
+    let parent = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let staging = parent.join(format!(".cc-atlas-socket-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&staging);
+    fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&staging)
+        .with_context(|| format!("Failed to create {}", staging.display()))?;
+    let staged = staging.join("s");
+    let listener = UnixListener::bind(&staged)
+        .and_then(|listener| {
+            fs::set_permissions(&staged, fs::Permissions::from_mode(mode))?;
+            fs::rename(&staged, path)?;
+            Ok(listener)
+        })
+        .with_context(|| format!("Failed to listen on {}", path.display()));
+    let _ = fs::remove_dir_all(&staging);
+    listener
+}
+
+/// Serve `app` on the socket until `shutdown` flips, then let open connections finish
+pub async fn serve(listener: UnixListener, app: Router, mut shutdown: watch::Receiver<bool>) -> Result<()> {
+    let service = app.layer(Extension(TrustedTransport));
+    let graceful = GracefulShutdown::new();
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let stream = match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        tracing::warn!("Failed to accept socket connection: {}", e);
+                        continue;
+                    }
+                };
+                let connection = auto::Builder::new(TokioExecutor::new())
+                    .serve_connection_with_upgrades(TokioIo::new(stream), TowerToHyperService::new(service.clone()))
+                    .into_owned();
+                let connection = graceful.watch(connection);
+                tokio::spawn(async move {
+                    if let Err(e) = connection.await {
+                        tracing::debug!("Socket connection closed with an error: {}", e);
+                    }
+                });
+            }
+            _ = shutdown.wait_for(|stop| *stop) => break,
+        }
+    }
+
+    drop(listener);
+    graceful.shutdown().await;
+    Ok(())
+}
+
+/// `GET` an `/api/v1` path from the server behind `socket`
+pub async fn get<T: DeserializeOwned>(socket: &Path, path: &str) -> Result<T> {
+    send(socket, Method::GET, path).await
+}
+
+/// `POST` to an `/api/v1` path, without a body
+pub async fn post<T: DeserializeOwned>(socket: &Path, path: &str) -> Result<T> {
+    send(socket, Method::POST, path).await
+}
+
+async fn send<T: DeserializeOwned>(socket: &Path, method: Method, path: &str) -> Result<T> {
+    let stream = UnixStream::connect(socket)
+        .await
+        .with_context(|| format!("No cc-atlas server is listening on {}", socket.display()))?;
+    let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream)).await?;
+    tokio::spawn(connection);
+
+    let request = hyper::Request::builder()
+        .method(method)
+        .uri(format!("{}{}", API_V1, path))
+        .header(header::HOST, "localhost")
+        .body(Empty::<Bytes>::new())?;
+    let response = sender.send_request(request).await?;
+    let status = response.status();
+    let body = response.into_body().collect().await?.to_bytes();
+
+    if !status.is_success() {
+        let message = serde_json::from_slice::<ErrorResponse>(&body)
+            .map(|e| e.error)
+            .unwrap_or_else(|_| String::from_utf8_lossy(&body).to_string());
+        anyhow::bail!("{} ({})", message, status);
+    }
+    serde_json::from_slice(&body).context("Unexpected response from the server")
+}
@@ -0,0 +1,82 @@
+use anyhow::Result;
+use axum::{
+    extract::{MatchedPath, Request},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::io::IsTerminal;
+use std::time::Instant;
+use tracing::Instrument;
+use tracing_subscriber::EnvFilter;
+
+use super::request_id;
+use crate::utils::metrics;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable, one line per event
+    Pretty,
+    /// One JSON object per line, for log collectors
+    Json,
+}
+
+/// Install the global subscriber. `level` takes `RUST_LOG`-style directives, e.g. `info` or `cc_atlas=debug`.
+pub fn init_logging(level: &str, format: LogFormat) -> Result<()> {
+    let filter = EnvFilter::try_new(level).map_err(|e| anyhow::anyhow!("Invalid log level {:?}: {}", level, e))?;
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal()); // No colour codes in a daemon's log file
+
+    let installed = match format {
+        LogFormat::Pretty => builder.try_init(),
+        LogFormat::Json => builder.json().flatten_event(true).try_init(),
+    };
+    installed.map_err(|e| anyhow::anyhow!("Failed to set up logging: {}", e))
+}
+
+/// One span per request, logged with its status and latency and counted in `/metrics`
+pub async fn track_requests(request: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let method = request.method().clone();
+    // The route template, not the raw path, so metric labels stay bounded
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let span = tracing::info_span!(
+        "request",
+        method = %method,
+        path = %request.uri().path(),
+        request_id = request_id::current().as_deref().unwrap_or_default(),
+    );
+    let response = next.run(request).instrument(span.clone()).await;
+
+    let status = response.status();
+    let elapsed = started.elapsed();
+    let latency_ms = elapsed.as_secs_f64() * 1000.0;
+    span.in_scope(|| {
+        if status.is_server_error() {
+            tracing::error!(status = status.as_u16(), latency_ms, "request failed");
+        } else {
+            tracing::info!(status = status.as_u16(), latency_ms, "request finished");
+        }
+    });
+
+    let registry = metrics::global();
+    registry.increment(
+        metrics::HTTP_REQUESTS,
+        &[("method", method.as_str()), ("route", &route), ("status", status.as_str())],
+    );
+    registry.observe(metrics::HTTP_DURATION, &[("method", method.as_str()), ("route", &route)], elapsed.as_secs_f64());
+
+    response
+}
+
+/// Prometheus scrape endpoint; counts and timings only, so it needs no token
+pub async fn serve_metrics() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")], metrics::global().render())
+}
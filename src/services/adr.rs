@@ -0,0 +1,175 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{config, docs, history};
+use crate::models::{AdrStatus, AdrSummary};
+use crate::utils::adr::{self, AdrError};
+use crate::utils::{links, paths, sandbox, time, writer};
+
+const ADR_DIR_NAME: &str = "adr";
+const DEFAULT_ADR_DIR: &str = "docs/adr";
+
+/// Where ADRs live: the configured `adr_dir`, an `adr` doc root or an `adr` folder in one, else `docs/adr`
+pub fn adr_dir(project_root: &Path) -> Result<PathBuf> {
+    let root = project_root.canonicalize()?;
+
+    if let Some(dir) = config::load(&root)?.adr_dir {
+        return Ok(sandbox::resolve_path(&root, &dir)?);
+    }
+
+    match docs::find_doc_subdir(&root, ADR_DIR_NAME)? {
+        Some(dir) => Ok(dir),
+        None => Ok(sandbox::resolve_path(&root, DEFAULT_ADR_DIR)?),
+    }
+}
+
+/// Every numbered ADR, in number order
+pub fn list(project_root: &Path) -> Result<Vec<AdrSummary>> {
+    let root = project_root.canonicalize()?;
+    let dir = adr_dir(&root)?;
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut adrs = Vec::new();
+    for entry in fs::read_dir(&dir)?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(number) = adr::parse_number(&name) else {
+            continue;
+        };
+        let Ok(content) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let path = paths::project_relative(&root, &entry.path())?;
+        adrs.push(adr::summarize(number, &path, &content));
+    }
+
+    adrs.sort_by_key(|a| a.number);
+    Ok(adrs)
+}
+
+/// Write the next-numbered ADR from the template, marking any ADRs it supersedes
+pub fn create(project_root: &Path, title: &str, supersedes: &[u32], source: &str) -> Result<AdrSummary> {
+    let root = project_root.canonicalize()?;
+    let dir = adr_dir(&root)?;
+    let existing = list(&root)?;
+
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(AdrError::EmptyTitle.into());
+    }
+    for number in supersedes {
+        let old = find(&existing, *number)?;
+        check_transition(old.status, AdrStatus::Superseded)?;
+    }
+
+    let number = adr::next_number(existing.iter().map(|a| a.number));
+    let template =
+        fs::read_to_string(dir.join(adr::TEMPLATE_FILE)).unwrap_or_else(|_| adr::DEFAULT_TEMPLATE.to_string());
+    let today = time::format_date(time::now_unix());
+
+    let name = adr::file_name(number, title);
+
+    let mut content = adr::render_template(&template, number, title, &today);
+    for old in supersedes {
+        content = adr::update_content(&content, &name, None, None, Some(*old), &today)?;
+    }
+
+    let file_path = dir.join(&name);
+    // Create only: another writer may have taken the name since the listing
+    writer::write_new(&file_path, content.as_bytes()).with_context(|| format!("Failed to write ADR {:04}", number))?;
+
+    for old in supersedes {
+        mark_superseded(&root, &existing, *old, number, &today, source)?;
+    }
+
+    write_index(&root)?;
+    let path = paths::project_relative(&root, &file_path)?;
+    Ok(adr::summarize(number, &path, &fs::read_to_string(&file_path)?))
+}
+
+/// Move an ADR through its lifecycle; superseding also links the replacement back to it
+pub fn set_status(
+    project_root: &Path,
+    number: u32,
+    status: AdrStatus,
+    superseded_by: Option<u32>,
+    source: &str,
+) -> Result<AdrSummary> {
+    let root = project_root.canonicalize()?;
+    let existing = list(&root)?;
+    let current = find(&existing, number)?;
+    check_transition(current.status, status)?;
+    let today = time::format_date(time::now_unix());
+
+    if status == AdrStatus::Superseded {
+        let by = superseded_by.ok_or(AdrError::MissingSupersededBy)?;
+        if by == number {
+            return Err(AdrError::SelfSupersede(number).into());
+        }
+        let replacement = find(&existing, by)?;
+
+        mark_superseded(&root, &existing, number, by, &today, source)?;
+        update_file(&root, replacement, None, None, Some(number), &today, source)?;
+    } else {
+        update_file(&root, current, Some(status), None, None, &today, source)?;
+    }
+
+    write_index(&root)?;
+    let file_path = root.join(&current.path);
+    Ok(adr::summarize(number, &current.path, &fs::read_to_string(file_path)?))
+}
+
+/// Regenerate the index page from the ADRs on disk
+pub fn write_index(project_root: &Path) -> Result<PathBuf> {
+    let root = project_root.canonicalize()?;
+    let dir = adr_dir(&root)?;
+    let index_path = dir.join(adr::INDEX_FILE);
+    let index = paths::project_relative(&root, &index_path).unwrap_or_else(|_| adr::INDEX_FILE.to_string());
+
+    let adrs = list(&root)?;
+    let content = adr::render_index(&adrs, |a| links::relative_to(&index, &a.path));
+
+    if fs::read_to_string(&index_path).ok().as_deref() != Some(content.as_str()) {
+        docs::write_doc_file(&index_path, &content)?;
+    }
+    Ok(index_path)
+}
+
+fn find(adrs: &[AdrSummary], number: u32) -> Result<&AdrSummary, AdrError> {
+    adrs.iter().find(|a| a.number == number).ok_or(AdrError::NotFound(number))
+}
+
+fn check_transition(from: AdrStatus, to: AdrStatus) -> Result<(), AdrError> {
+    if adr::can_transition(from, to) {
+        Ok(())
+    } else {
+        Err(AdrError::InvalidTransition { from, to })
+    }
+}
+
+fn mark_superseded(root: &Path, existing: &[AdrSummary], old: u32, by: u32, today: &str, source: &str) -> Result<()> {
+    let adr = find(existing, old)?;
+    update_file(root, adr, Some(AdrStatus::Superseded), Some(by), None, today, source)
+}
+
+fn update_file(
+    root: &Path,
+    adr: &AdrSummary,
+    status: Option<AdrStatus>,
+    superseded_by: Option<u32>,
+    add_supersedes: Option<u32>,
+    today: &str,
+    source: &str,
+) -> Result<()> {
+    let file_path = root.join(&adr.path);
+    let content = fs::read_to_string(&file_path)?;
+    let updated = adr::update_content(&content, &adr.path, status, superseded_by, add_supersedes, today)?;
+
+    if updated != content {
+        history::snapshot(root, &file_path, "adr", source)?;
+        docs::write_doc_file(&file_path, &updated)?;
+    }
+    Ok(())
+}
@@ -1,25 +1,30 @@
+use crate::models::{DirectoryInfo, FileStats, MemoryFile, Staleness};
+use crate::utils::{hash, metrics, sandbox, time};
 use anyhow::Result;
+use std::collections::HashMap;
+use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
-use crate::models::{DirectoryInfo, FileStats, MemoryFile};
 
 const MEMORY_FILE_NAME: &str = "CLAUDE.md";
 const DEFAULT_MAX_DEPTH: usize = 3;
 const COMPLEXITY_FILE_THRESHOLD: usize = 10;
 const COMPLEXITY_LINE_THRESHOLD: usize = 500;
+const STALE_AFTER_DAYS: u64 = 30;
 
 pub fn analyze_project(path: &str) -> Result<()> {
     let project_path = Path::new(path);
-    let memory_files = find_memory_files(project_path)?;
-    
+    let scan = Scan::walk(project_path, None);
+    let memory_files = scan.memory_files()?;
+
     print_memory_files(&memory_files);
-    
-    let tree = build_directory_tree(project_path)?;
+
+    let tree = scan.node(project_path, &TreeOptions::default());
     let recommendations = get_recommendations(&tree);
-    
+
     print_recommendations(&recommendations);
-    
+
     Ok(())
 }
 
@@ -40,150 +45,272 @@ fn print_recommendations(recommendations: &[PathBuf]) {
 }
 
 pub fn find_memory_files(root: &Path) -> Result<Vec<MemoryFile>> {
-    let mut memory_files = Vec::new();
-    
+    Scan::walk(root, None).memory_files()
+}
+
+/// Locate memory files, shared and local, without reading them or computing directory stats
+pub fn find_memory_file_paths(root: &Path) -> Result<Vec<PathBuf>> {
+    find_files_named(root, sandbox::MEMORY_FILE_NAMES)
+}
+
+/// Locate files with any of the given names, skipping ignored directories
+pub fn find_files_named(root: &Path, names: &[&str]) -> Result<Vec<PathBuf>> {
+    metrics::time_phase("find_files", || walk_for_names(root, names))
+}
+
+fn walk_for_names(root: &Path, names: &[&str]) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+
     for entry in walk_directory(root) {
         let entry = entry?;
-        if is_memory_file(&entry) {
-            let memory_file = create_memory_file(&entry, root)?;
-            memory_files.push(memory_file);
+        if entry.file_type().is_file() && names.iter().any(|n| entry.file_name() == *n) {
+            paths.push(entry.into_path());
         }
     }
-    
-    Ok(memory_files)
+
+    Ok(paths)
 }
 
-fn walk_directory(root: &Path) -> impl Iterator<Item = walkdir::Result<walkdir::DirEntry>> {
-    WalkDir::new(root)
-        .follow_links(true)
-        .into_iter()
-        .filter_entry(|e| !is_ignored(e.path()))
-}
-
-fn is_memory_file(entry: &walkdir::DirEntry) -> bool {
-    entry.file_type().is_file() && entry.file_name() == MEMORY_FILE_NAME
-}
-
-fn create_memory_file(entry: &walkdir::DirEntry, root: &Path) -> Result<MemoryFile> {
-    let path = entry.path();
-    let content = fs::read_to_string(path)?;
-    let relative_path = get_relative_path(path, root);
-    let parent_dir = path.parent().unwrap_or(root);
-    let stats = calculate_stats(parent_dir)?;
-    
-    Ok(MemoryFile {
-        path: path.to_path_buf(),
-        content,
-        content_html: None,  // Will be converted when needed
-        relative_path,
-        stats,
+/// Every markdown file in the project, skipping ignored directories
+pub fn find_markdown_files(root: &Path) -> Result<Vec<PathBuf>> {
+    metrics::time_phase("find_markdown", || walk_for_markdown(root))
+}
+
+fn walk_for_markdown(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+
+    for entry in walk_directory(root) {
+        let entry = entry?;
+        let is_markdown = entry.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("md"));
+        if entry.file_type().is_file() && is_markdown {
+            paths.push(entry.into_path());
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Compare a memory file's modification time with the newest file in the directory it documents
+pub fn check_staleness(memory_path: &Path) -> Result<Staleness> {
+    metrics::time_phase("staleness", || staleness(memory_path))
+}
+
+fn staleness(memory_path: &Path) -> Result<Staleness> {
+    let memory_modified = time::to_unix(fs::metadata(memory_path)?.modified()?);
+    let dir = memory_path.parent().unwrap_or(Path::new("."));
+
+    let newest = walk_limited(dir, DEFAULT_MAX_DEPTH)
+        .flatten()
+        .filter(|e| e.file_type().is_file() && !is_memory_name(e.file_name()))
+        .filter_map(|e| {
+            let modified = e.metadata().ok()?.modified().ok()?;
+            Some((e.into_path(), time::to_unix(modified)))
+        })
+        .max_by_key(|(_, modified)| *modified);
+
+    let Some((path, modified)) = newest else {
+        return Ok(Staleness { stale: false, newest_change: None, newest_change_at: None, days_behind: 0 });
+    };
+
+    let days_behind = modified.saturating_sub(memory_modified) / 86_400;
+
+    Ok(Staleness {
+        stale: days_behind >= STALE_AFTER_DAYS,
+        newest_change: Some(get_relative_path(&path, dir)),
+        newest_change_at: Some(time::format_rfc3339(modified)),
+        days_behind,
     })
 }
 
+fn walk_directory(root: &Path) -> impl Iterator<Item = walkdir::Result<walkdir::DirEntry>> {
+    WalkDir::new(root).follow_links(true).into_iter().filter_entry(|e| !is_ignored(e.path()))
+}
+
+fn is_memory_name(name: &OsStr) -> bool {
+    sandbox::MEMORY_FILE_NAMES.iter().any(|n| name == *n)
+}
+
 fn get_relative_path(path: &Path, root: &Path) -> String {
-    path.strip_prefix(root)
-        .unwrap_or(path)
-        .to_string_lossy()
-        .to_string()
+    path.strip_prefix(root).unwrap_or(path).to_string_lossy().to_string()
+}
+
+/// How much of the tree to build
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TreeOptions {
+    pub depth: Option<usize>, // Levels of children to load; `None` for everything
+    pub include_files: bool,
 }
 
 pub fn build_directory_tree(root: &Path) -> Result<DirectoryInfo> {
-    // For the root, get the actual directory name from the absolute path
-    let name = if root == Path::new(".") || root == Path::new("./") {
-        root.canonicalize()
-            .ok()
-            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
-            .unwrap_or_else(|| "root".to_string())
-    } else {
-        get_directory_name(root)
-    };
-    
-    let has_memory = check_has_memory(root);
-    let stats = calculate_stats(root)?;
-    let children = build_children(root)?;
-    
-    Ok(DirectoryInfo {
-        path: root.to_path_buf(),
-        name,
-        has_memory,
-        children,
-        stats,
+    build_tree(root, &TreeOptions::default())
+}
+
+/// Build the tree below `path`, stopping after `options.depth` levels
+pub fn build_tree(root: &Path, options: &TreeOptions) -> Result<DirectoryInfo> {
+    metrics::time_phase("build_tree", || {
+        // Stats of the deepest nodes loaded still cover files further down
+        let scan = Scan::walk(root, options.depth.map(|depth| depth + DEFAULT_MAX_DEPTH));
+        Ok(scan.node(root, options))
     })
 }
 
-fn get_directory_name(path: &Path) -> String {
-    // For root directory, use the actual folder name
-    path.file_name()
-        .map(|name| name.to_string_lossy().to_string())
-        .unwrap_or_else(|| {
-            // If no file_name (e.g., "."), use the full path's last component
+/// Stats over a directory's files up to `DEFAULT_MAX_DEPTH` levels down
+pub fn calculate_stats(path: &Path) -> Result<FileStats> {
+    Ok(Scan::walk(path, Some(DEFAULT_MAX_DEPTH)).stats(path))
+}
+
+/// What one walk below a directory found; the tree, stats, memory files and
+/// recommendations are all derived from it instead of reading the disk again
+struct Scan {
+    root: PathBuf,
+    dirs: HashMap<PathBuf, ScannedDir>,
+}
+
+#[derive(Default)]
+struct ScannedDir {
+    subdirs: Vec<PathBuf>,
+    files: Vec<String>,
+    file_count: usize, // Files up to `DEFAULT_MAX_DEPTH` levels down
+    total_lines: usize,
+}
+
+impl Scan {
+    /// Walk `levels` deep below `root`, or all the way down with `None`
+    fn walk(root: &Path, levels: Option<usize>) -> Scan {
+        metrics::time_phase("scan", || {
+            let mut dirs: HashMap<PathBuf, ScannedDir> = HashMap::new();
+            dirs.insert(root.to_path_buf(), ScannedDir::default());
+
+            let mut walker = WalkDir::new(root).follow_links(true);
+            if let Some(levels) = levels {
+                walker = walker.max_depth(levels);
+            }
+            let entries = walker.into_iter().filter_entry(|e| !is_ignored(e.path())).flatten();
+
+            for entry in entries.filter(|e| e.depth() > 0) {
+                let path = entry.path();
+                let Some(parent) = path.parent() else {
+                    continue;
+                };
+
+                if entry.file_type().is_dir() {
+                    dirs.entry(parent.to_path_buf()).or_default().subdirs.push(path.to_path_buf());
+                    dirs.entry(path.to_path_buf()).or_default();
+                } else if entry.file_type().is_file() {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    dirs.entry(parent.to_path_buf()).or_default().files.push(name);
+
+                    // Counted towards each directory it sits at most `DEFAULT_MAX_DEPTH` levels below
+                    let lines = count_lines(path);
+                    for dir in path.ancestors().skip(1).take(entry.depth().min(DEFAULT_MAX_DEPTH)) {
+                        let scanned = dirs.entry(dir.to_path_buf()).or_default();
+                        scanned.file_count += 1;
+                        scanned.total_lines += lines;
+                    }
+                }
+            }
+
+            Scan { root: root.to_path_buf(), dirs }
+        })
+    }
+
+    fn stats(&self, path: &Path) -> FileStats {
+        let scanned = self.dirs.get(path);
+        FileStats {
+            file_count: scanned.map_or(0, |d| d.file_count),
+            total_lines: scanned.map_or(0, |d| d.total_lines),
+            depth: count_depth(path),
+        }
+    }
+
+    fn node(&self, path: &Path, options: &TreeOptions) -> DirectoryInfo {
+        // For the root, get the actual directory name from the absolute path
+        let name = if path == Path::new(".") || path == Path::new("./") {
             path.canonicalize()
                 .ok()
                 .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
                 .unwrap_or_else(|| "root".to_string())
-        })
-}
+        } else {
+            get_directory_name(path)
+        };
 
-fn check_has_memory(path: &Path) -> bool {
-    path.join(MEMORY_FILE_NAME).exists()
-}
+        let empty = ScannedDir::default();
+        let scanned = self.dirs.get(path).unwrap_or(&empty);
+        let has_memory = scanned.files.iter().any(|f| f == MEMORY_FILE_NAME);
+        let stats = self.stats(path);
 
-fn build_children(root: &Path) -> Result<Vec<DirectoryInfo>> {
-    let mut children = Vec::new();
-    
-    if !root.is_dir() {
-        return Ok(children);
-    }
-    
-    for entry in fs::read_dir(root)? {
-        let entry = entry?;
-        let path = entry.path();
-        
-        if should_process_directory(&path) {
-            if let Ok(child) = build_directory_tree(&path) {
-                children.push(child);
+        let (children, has_children) = match options.depth {
+            Some(0) => (Vec::new(), !scanned.subdirs.is_empty()),
+            depth => {
+                let child_options = TreeOptions { depth: depth.map(|d| d - 1), ..*options };
+                let mut children: Vec<DirectoryInfo> =
+                    scanned.subdirs.iter().map(|dir| self.node(dir, &child_options)).collect();
+                children.sort_by(|a, b| a.name.cmp(&b.name));
+                let has_children = !children.is_empty();
+                (children, has_children)
             }
+        };
+
+        let files = options.include_files.then(|| {
+            let mut files = scanned.files.clone();
+            files.sort();
+            files
+        });
+
+        DirectoryInfo {
+            path: path.to_path_buf(),
+            name,
+            has_memory,
+            has_children,
+            should_recommend: !has_memory && should_have_memory(&stats),
+            children,
+            files,
+            stats,
         }
     }
-    
-    Ok(children)
-}
 
-fn should_process_directory(path: &Path) -> bool {
-    path.is_dir() && !is_ignored(path)
-}
+    /// Every memory file found, read, with the stats of the directory it documents
+    fn memory_files(&self) -> Result<Vec<MemoryFile>> {
+        let mut memory_files = Vec::new();
+
+        for (dir, scanned) in &self.dirs {
+            for name in scanned.files.iter().filter(|name| is_memory_name(OsStr::new(name))) {
+                let path = dir.join(name);
+                let content = fs::read_to_string(&path)?;
 
-fn calculate_stats(path: &Path) -> Result<FileStats> {
-    let mut file_count = 0;
-    let mut total_lines = 0;
-    
-    for entry in walk_limited(path, DEFAULT_MAX_DEPTH) {
-        if let Ok(entry) = entry {
-            if entry.file_type().is_file() {
-                file_count += 1;
-                total_lines += count_lines(entry.path());
+                memory_files.push(MemoryFile {
+                    relative_path: get_relative_path(&path, &self.root),
+                    content_hash: hash::calculate_hash(&content),
+                    content,
+                    content_html: None, // Will be converted when needed
+                    stats: self.stats(dir),
+                    path,
+                });
             }
         }
+
+        memory_files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(memory_files)
     }
-    
-    Ok(FileStats {
-        file_count,
-        total_lines,
-        depth: count_depth(path),
+}
+
+fn get_directory_name(path: &Path) -> String {
+    // For root directory, use the actual folder name
+    path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_else(|| {
+        // If no file_name (e.g., "."), use the full path's last component
+        path.canonicalize()
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| "root".to_string())
     })
 }
 
 fn walk_limited(path: &Path, max_depth: usize) -> impl Iterator<Item = walkdir::Result<walkdir::DirEntry>> {
-    WalkDir::new(path)
-        .max_depth(max_depth)
-        .into_iter()
-        .filter_entry(|e| !is_ignored(e.path()))
+    WalkDir::new(path).max_depth(max_depth).into_iter().filter_entry(|e| !is_ignored(e.path()))
 }
 
 fn count_lines(path: &Path) -> usize {
-    fs::read_to_string(path)
-        .map(|content| content.lines().count())
-        .unwrap_or(0)
+    fs::read_to_string(path).map(|content| content.lines().count()).unwrap_or(0)
 }
 
 fn count_depth(path: &Path) -> usize {
@@ -197,32 +324,36 @@ pub fn get_recommendations(tree: &DirectoryInfo) -> Vec<PathBuf> {
 }
 
 fn collect_recommendations(dir: &DirectoryInfo, recommendations: &mut Vec<PathBuf>) {
-    if should_recommend_memory(dir) {
+    if dir.should_recommend {
         recommendations.push(dir.path.clone());
     }
-    
+
     for child in &dir.children {
         collect_recommendations(child, recommendations);
     }
 }
 
-fn should_recommend_memory(dir: &DirectoryInfo) -> bool {
-    !dir.has_memory && should_have_memory(&dir.stats)
-}
-
 fn should_have_memory(stats: &FileStats) -> bool {
-    stats.file_count > COMPLEXITY_FILE_THRESHOLD || 
-    stats.total_lines > COMPLEXITY_LINE_THRESHOLD
+    stats.file_count > COMPLEXITY_FILE_THRESHOLD || stats.total_lines > COMPLEXITY_LINE_THRESHOLD
 }
 
-fn is_ignored(path: &Path) -> bool {
+pub fn is_ignored(path: &Path) -> bool {
     const IGNORED_DIRS: &[&str] = &[
-        ".git", ".claude", "node_modules", "target", "dist", "build",
-        ".next", ".cache", "coverage", "__pycache__"
+        ".git",
+        ".claude",
+        ".cc-atlas",
+        "node_modules",
+        "target",
+        "dist",
+        "build",
+        ".next",
+        ".cache",
+        "coverage",
+        "__pycache__",
     ];
-    
+
     path.components().any(|component| {
         let name = component.as_os_str().to_string_lossy();
         IGNORED_DIRS.iter().any(|&ignored| name == ignored)
     })
-}
\ No newline at end of file
+}
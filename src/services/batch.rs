@@ -0,0 +1,143 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::{history, trash};
+use crate::models::{BatchOperation, BatchResult};
+use crate::utils::batch::{self, FilePlan, Removal};
+use crate::utils::{sandbox, writer};
+
+/// What to do to put a file back if a later step of the batch fails
+enum Undo {
+    Restore { path: String, content: String },
+    Remove { path: String },
+    Untrash { id: String, path: String },
+}
+
+/// Apply create, update, delete and move operations on memory files as one unit.
+///
+/// Every operation is checked before anything is written; if a write then fails, the
+/// files already changed are put back. With `dry_run` the result only previews the changes.
+pub fn apply(project_root: &Path, operations: &[BatchOperation], dry_run: bool, source: &str) -> Result<BatchResult> {
+    let root = project_root.canonicalize()?;
+    let operations = operations
+        .iter()
+        .enumerate()
+        .map(|(i, op)| normalize(&root, op).with_context(|| format!("Operation {}", i)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut unreadable: Option<(String, io::Error)> = None;
+    let files = batch::plan(&operations, |path| {
+        let file_path = root.join(path);
+        if !file_path.is_file() {
+            return None;
+        }
+        match fs::read_to_string(&file_path) {
+            Ok(content) => Some(content),
+            Err(e) => {
+                unreadable.get_or_insert((path.to_string(), e));
+                None
+            }
+        }
+    })?;
+    if let Some((path, e)) = unreadable {
+        return Err(anyhow::Error::new(e).context(format!("Failed to read {}", path)));
+    }
+
+    let mut result = batch::summarize(&files, dry_run);
+    if dry_run {
+        return Ok(result);
+    }
+
+    let changed: Vec<&FilePlan> = files.iter().filter(|f| f.is_changed()).collect();
+    for file in changed.iter().filter(|f| f.original.is_some()) {
+        history::snapshot(&root, &root.join(&file.path), "batch", source)?;
+    }
+
+    let mut undo = Vec::new();
+    if let Err(e) = write_all(&root, &changed, &mut undo, source) {
+        roll_back(&root, undo);
+        return Err(e.context("Batch was rolled back"));
+    }
+
+    for step in undo {
+        if let Undo::Untrash { id, path } = step {
+            if let Some(change) = result.files.iter_mut().find(|c| c.path == path) {
+                change.trash_id = Some(id);
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Write new contents first, then remove what was deleted or moved away, recording how to undo each step
+fn write_all(root: &Path, files: &[&FilePlan], undo: &mut Vec<Undo>, source: &str) -> Result<()> {
+    for file in files {
+        let Some(content) = &file.content else {
+            continue;
+        };
+        writer::write_atomic(&root.join(&file.path), content.as_bytes())?;
+        undo.push(match &file.original {
+            Some(original) => Undo::Restore { path: file.path.clone(), content: original.clone() },
+            None => Undo::Remove { path: file.path.clone() },
+        });
+    }
+
+    for file in files {
+        let (None, Some(original)) = (&file.content, &file.original) else {
+            continue;
+        };
+        let file_path = root.join(&file.path);
+        match file.removal {
+            Some(Removal::Moved) => {
+                fs::remove_file(&file_path).with_context(|| format!("Failed to remove {}", file.path))?;
+                undo.push(Undo::Restore { path: file.path.clone(), content: original.clone() });
+            }
+            _ => {
+                let entry = trash::move_to_trash(root, &file_path, source)?;
+                undo.push(Undo::Untrash { id: entry.id, path: file.path.clone() });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Best effort: each step is tried even if an earlier one fails, so as much as possible is restored
+fn roll_back(root: &Path, undo: Vec<Undo>) {
+    for step in undo.into_iter().rev() {
+        let restored = match &step {
+            Undo::Restore { path, content } => writer::write_atomic(&root.join(path), content.as_bytes()),
+            Undo::Remove { path } => fs::remove_file(root.join(path)).map_err(Into::into),
+            Undo::Untrash { id, .. } => trash::restore(root, id).map(|_| ()),
+        };
+        if let Err(e) = restored {
+            tracing::error!("Failed to roll back batch step: {:#}", e);
+        }
+    }
+}
+
+/// The operation with every path in one project-relative form, checked against the memory sandbox
+fn normalize(root: &Path, op: &BatchOperation) -> Result<BatchOperation> {
+    let relative = |path: &str| -> Result<String> {
+        let file_path = sandbox::resolve_memory_path(root, path)?;
+        let relative = file_path.strip_prefix(root).context("File is outside the project root")?;
+        Ok(relative.to_string_lossy().replace('\\', "/"))
+    };
+
+    Ok(match op {
+        BatchOperation::Create { path, content } => {
+            BatchOperation::Create { path: relative(path)?, content: content.clone() }
+        }
+        BatchOperation::Update { path, content, base_hash } => {
+            BatchOperation::Update { path: relative(path)?, content: content.clone(), base_hash: base_hash.clone() }
+        }
+        BatchOperation::Delete { path, base_hash } => {
+            BatchOperation::Delete { path: relative(path)?, base_hash: base_hash.clone() }
+        }
+        BatchOperation::Move { from, to, base_hash } => {
+            BatchOperation::Move { from: relative(from)?, to: relative(to)?, base_hash: base_hash.clone() }
+        }
+    })
+}
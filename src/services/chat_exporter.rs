@@ -1,13 +1,29 @@
-use anyhow::{Result, Context};
-use std::fs::{File, self};
-use std::io::{BufReader, BufRead};
-use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
 use serde_json::Value;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
 
 use crate::models::{
-    ChatMetadata, ExportOptions, ExportResult, ChatEntry, ChatMessage,
-    ToolCallSummary, SessionMetadata
+    ChatEntry, ChatMessage, ChatMetadata, ExportOptions, ExportResult, SessionMetadata, ToolCallSummary,
 };
+use crate::utils::writer;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChatError {
+    NotFound(String),
+}
+
+impl fmt::Display for ChatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChatError::NotFound(id) => write!(f, "Chat not found: {}", id),
+        }
+    }
+}
+
+impl std::error::Error for ChatError {}
 
 /// Find all chat files for the current project
 pub fn find_project_chats(project_root: &Path) -> Result<Vec<ChatMetadata>> {
@@ -15,8 +31,7 @@ pub fn find_project_chats(project_root: &Path) -> Result<Vec<ChatMetadata>> {
     let claude_projects = PathBuf::from(home).join(".claude/projects");
 
     // Canonicalize the project path to get absolute path
-    let absolute_project = project_root.canonicalize()
-        .context("Failed to resolve project path")?;
+    let absolute_project = project_root.canonicalize().context("Failed to resolve project path")?;
 
     // Convert path to string, strip leading /, then replace remaining / with -
     let path_str = absolute_project.display().to_string();
@@ -82,23 +97,13 @@ pub fn get_chat_metadata(path: &Path) -> Result<ChatMetadata> {
     }
 
     let metadata = fs::metadata(path)?;
-    let session_id = session_id.unwrap_or_else(|| {
-        path.file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("unknown")
-            .to_string()
-    });
-
-    let project_name = cwd
-        .as_ref()
-        .and_then(|p| Path::new(p).file_name())
-        .and_then(|s| s.to_str())
-        .unwrap_or("unknown");
-
-    let date = last_timestamp
-        .as_ref()
-        .and_then(|ts| ts.split('T').next())
-        .unwrap_or("unknown");
+    let session_id =
+        session_id.unwrap_or_else(|| path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string());
+
+    let project_name =
+        cwd.as_ref().and_then(|p| Path::new(p).file_name()).and_then(|s| s.to_str()).unwrap_or("unknown");
+
+    let date = last_timestamp.as_ref().and_then(|ts| ts.split('T').next()).unwrap_or("unknown");
 
     let title = format!("{} · {} messages · {}", date, line_count, project_name);
 
@@ -117,13 +122,12 @@ pub fn export_chat(
     session_id: &str,
     project_root: &Path,
     options: &ExportOptions,
-    custom_name: Option<&str>
+    custom_name: Option<&str>,
 ) -> Result<ExportResult> {
     // Find the chat file
     let chats = find_project_chats(project_root)?;
-    let chat = chats.iter()
-        .find(|c| c.session_id == session_id)
-        .context("Chat not found")?;
+    let chat =
+        chats.iter().find(|c| c.session_id == session_id).ok_or_else(|| ChatError::NotFound(session_id.to_string()))?;
 
     // Stream and parse messages
     let (messages, metadata) = stream_and_parse(&chat.file_path)?;
@@ -140,7 +144,7 @@ pub fn export_chat(
     }
 
     // Write file
-    fs::write(&export_path, &markdown)?;
+    writer::write_atomic(&export_path, markdown.as_bytes())?;
 
     let file_size = fs::metadata(&export_path)?.len();
 
@@ -211,11 +215,8 @@ fn stream_and_parse(path: &Path) -> Result<(Vec<ChatMessage>, SessionMetadata)>
             let content_str = extract_content_text(&msg_content.content);
 
             // Extract tools from assistant messages
-            let tools_used = if entry.entry_type == "assistant" {
-                extract_tool_summary(&msg_content.content)
-            } else {
-                Vec::new()
-            };
+            let tools_used =
+                if entry.entry_type == "assistant" { extract_tool_summary(&msg_content.content) } else { Vec::new() };
 
             // Track models used
             if let Some(model) = &msg_content.model {
@@ -246,10 +247,7 @@ fn stream_and_parse(path: &Path) -> Result<(Vec<ChatMessage>, SessionMetadata)>
         git_branch,
         message_count: messages.len(),
         models_used,
-        date_range: (
-            first_ts.unwrap_or_default(),
-            last_ts.unwrap_or_default()
-        ),
+        date_range: (first_ts.unwrap_or_default(), last_ts.unwrap_or_default()),
     };
 
     Ok((messages, metadata))
@@ -315,10 +313,7 @@ fn extract_tool_summary(content: &Value) -> Vec<ToolCallSummary> {
             if let Some(obj) = item.as_object() {
                 // Only process tool_use, not tool_result (which contains bloat)
                 if obj.get("type").and_then(|v| v.as_str()) == Some("tool_use") {
-                    let tool_name = obj.get("name")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("unknown")
-                        .to_string();
+                    let tool_name = obj.get("name").and_then(|v| v.as_str()).unwrap_or("unknown").to_string();
 
                     let mut files = Vec::new();
 
@@ -342,11 +337,7 @@ fn extract_tool_summary(content: &Value) -> Vec<ToolCallSummary> {
 }
 
 /// Format messages as markdown
-fn format_as_markdown(
-    messages: &[ChatMessage],
-    metadata: &SessionMetadata,
-    options: &ExportOptions
-) -> String {
+fn format_as_markdown(messages: &[ChatMessage], metadata: &SessionMetadata, options: &ExportOptions) -> String {
     let mut md = String::new();
 
     // Header
@@ -407,10 +398,7 @@ fn format_message_block(msg: &ChatMessage, options: &ExportOptions) -> String {
 
     // Timestamp
     if options.include_timestamps {
-        let ts = msg.timestamp.split('T')
-            .nth(1)
-            .and_then(|t| t.split('.').next())
-            .unwrap_or(&msg.timestamp);
+        let ts = msg.timestamp.split('T').nth(1).and_then(|t| t.split('.').next()).unwrap_or(&msg.timestamp);
         block.push_str(&format!("*{}*\n", ts));
     }
 
@@ -420,7 +408,7 @@ fn format_message_block(msg: &ChatMessage, options: &ExportOptions) -> String {
         block.push_str(&format!("*Tools: {}*\n", tools_str));
     }
 
-    block.push_str("\n");
+    block.push('\n');
     block.push_str(&msg.content);
 
     block
@@ -434,14 +422,13 @@ fn format_tools(tools: &[ToolCallSummary], max_files: usize) -> String {
         if tool.files.is_empty() {
             result.push(tool.tool_name.clone());
         } else {
-            let files: Vec<_> = tool.files.iter()
+            let files: Vec<_> = tool
+                .files
+                .iter()
                 .take(max_files)
                 .map(|f| {
                     // Extract just filename from path
-                    Path::new(f)
-                        .file_name()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or(f)
+                    Path::new(f).file_name().and_then(|s| s.to_str()).unwrap_or(f)
                 })
                 .collect();
 
@@ -462,11 +449,7 @@ fn generate_export_path(session_id: &str, custom_name: Option<&str>) -> PathBuf
     let base_name = custom_name.unwrap_or(session_id);
 
     // Ensure .md extension
-    let filename = if base_name.ends_with(".md") {
-        base_name.to_string()
-    } else {
-        format!("{}.md", base_name)
-    };
+    let filename = if base_name.ends_with(".md") { base_name.to_string() } else { format!("{}.md", base_name) };
 
     let mut path = base_dir.join(&filename);
 
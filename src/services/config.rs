@@ -0,0 +1,19 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use crate::models::ProjectConfig;
+
+const CONFIG_FILE: &str = ".cc-atlas/config.json";
+
+/// Load the project's settings, falling back to defaults when there is no config file
+pub fn load(project_root: &Path) -> Result<ProjectConfig> {
+    let path = project_root.join(CONFIG_FILE);
+
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).with_context(|| format!("Invalid config in {}", CONFIG_FILE)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(ProjectConfig::default()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", CONFIG_FILE)),
+    }
+}
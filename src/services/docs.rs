@@ -1,48 +1,148 @@
+use super::{analyzer, config};
+use crate::models::{DocRootConfig, DocsNode};
+use crate::utils::sandbox::{self, SandboxError};
+use crate::utils::{front_matter, metrics, paths, writer};
 use anyhow::Result;
 use std::fs;
 use std::path::{Path, PathBuf};
-use crate::models::DocsNode;
+use walkdir::WalkDir;
+
+/// Folder names picked up as documentation roots when the config doesn't list any
+const DOCS_DIR_NAMES: &[&str] = &["docs", "documentation", "adr"];
+/// Roots inside otherwise ignored directories, checked explicitly
+const EXTRA_DOCS_DIRS: &[&str] = &[".claude/docs"];
+const DETECT_MAX_DEPTH: usize = 3;
+
+/// A directory whose markdown files are served as documentation
+#[derive(Debug, Clone)]
+pub struct DocRoot {
+    pub name: String, // Display name
+    pub path: String, // Relative to the project root; doc paths start with it
+    pub dir: PathBuf, // Absolute
+}
+
+/// The configured doc roots, or auto-detected ones when the config lists none
+pub fn doc_roots(project_root: &Path) -> Result<Vec<DocRoot>> {
+    let root = project_root.canonicalize()?;
+    let config = config::load(&root)?;
+
+    let mut roots = match config.docs_roots {
+        Some(entries) => entries.iter().filter_map(|entry| configured_root(&root, entry)).collect(),
+        None => detect_roots(&root),
+    };
+
+    roots.sort_by(|a, b| a.path.cmp(&b.path));
+    roots.dedup_by(|a, b| a.path == b.path);
+    Ok(roots)
+}
+
+/// A doc root called `name`, or the first `name` folder directly inside a doc root
+pub fn find_doc_subdir(project_root: &Path, name: &str) -> Result<Option<PathBuf>> {
+    let roots = doc_roots(project_root)?;
+
+    Ok(roots
+        .iter()
+        .find(|r| r.dir.file_name().is_some_and(|n| n == name))
+        .map(|r| r.dir.clone())
+        .or_else(|| roots.iter().map(|r| r.dir.join(name)).find(|dir| dir.is_dir())))
+}
+
+/// Resolve a project-relative doc path, which must lie inside one of `roots`
+pub fn resolve_doc_path(project_root: &Path, roots: &[DocRoot], path: &str) -> Result<PathBuf, SandboxError> {
+    let allowed: Vec<&str> = roots.iter().map(|r| r.path.as_str()).collect();
+    sandbox::resolve_within(project_root, &allowed, path)
+}
 
-const DOCS_DIR_NAME: &str = "docs";
+/// The root a project-relative doc path belongs to; the innermost one if roots nest
+pub fn root_for<'a>(roots: &'a [DocRoot], path: &str) -> Option<&'a DocRoot> {
+    roots.iter().filter(|r| path == r.path || path.starts_with(&format!("{}/", r.path))).max_by_key(|r| r.path.len())
+}
+
+fn configured_root(root: &Path, entry: &DocRootConfig) -> Option<DocRoot> {
+    let dir = sandbox::resolve_path(root, &entry.path).ok()?;
+    if !dir.is_dir() {
+        return None; // Listed but missing; nothing to show
+    }
+
+    let path = paths::project_relative(root, &dir).ok()?;
+    let name = entry.name.clone().unwrap_or_else(|| path.clone());
+    Some(DocRoot { name, path, dir })
+}
+
+/// Find doc folders near the top of the project, skipping ones nested inside another root
+fn detect_roots(root: &Path) -> Vec<DocRoot> {
+    let mut roots: Vec<DocRoot> = Vec::new();
+
+    let walker = WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(DETECT_MAX_DEPTH)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| !analyzer::is_ignored(e.path().strip_prefix(root).unwrap_or(e.path())));
+
+    for entry in walker.flatten() {
+        let name = entry.file_name().to_string_lossy();
+        if !entry.file_type().is_dir() || !DOCS_DIR_NAMES.contains(&name.as_ref()) {
+            continue;
+        }
+        let Ok(path) = paths::project_relative(root, entry.path()) else {
+            continue;
+        };
+        if root_for(&roots, &path).is_none() {
+            roots.push(DocRoot { name: path.clone(), path, dir: entry.into_path() });
+        }
+    }
 
-/// Find the docs directory in the project root
-pub fn find_docs_dir(root: &Path) -> Option<PathBuf> {
-    let docs_path = root.join(DOCS_DIR_NAME);
-    if docs_path.exists() && docs_path.is_dir() {
-        Some(docs_path)
-    } else {
-        None
+    for extra in EXTRA_DOCS_DIRS {
+        let dir = root.join(extra);
+        if dir.is_dir() {
+            roots.push(DocRoot { name: extra.to_string(), path: extra.to_string(), dir });
+        }
     }
+
+    roots
 }
 
-/// Build a tree of documentation files and directories
-pub fn build_docs_tree(docs_path: &Path) -> Result<DocsNode> {
-    let name = docs_path
-        .file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_else(|| "docs".to_string());
+/// Build the documentation tree: one top-level node per root
+pub fn build_docs_tree(project_root: &Path, roots: &[DocRoot]) -> Result<DocsNode> {
+    metrics::time_phase("docs_tree", || docs_tree(project_root, roots))
+}
 
-    let children = build_children(docs_path, docs_path)?;
+fn docs_tree(project_root: &Path, roots: &[DocRoot]) -> Result<DocsNode> {
+    let root = project_root.canonicalize()?;
+    let children = roots
+        .iter()
+        .map(|doc_root| {
+            Ok(DocsNode {
+                path: doc_root.path.clone(),
+                name: doc_root.name.clone(),
+                is_file: false,
+                is_root: true,
+                metadata: None,
+                children: build_children(&doc_root.dir, &root)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
 
     Ok(DocsNode {
-        path: ".".to_string(), // Root of docs tree
-        name,
+        path: ".".to_string(), // Virtual node holding the roots
+        name: "docs".to_string(),
         is_file: false,
+        is_root: false,
+        metadata: None,
         children,
     })
 }
 
 /// Build children nodes recursively
-fn build_children(path: &Path, docs_root: &Path) -> Result<Vec<DocsNode>> {
+fn build_children(path: &Path, project_root: &Path) -> Result<Vec<DocsNode>> {
     let mut children = Vec::new();
 
     if !path.is_dir() {
         return Ok(children);
     }
 
-    let mut entries: Vec<_> = fs::read_dir(path)?
-        .filter_map(|e| e.ok())
-        .collect();
+    let mut entries: Vec<_> = fs::read_dir(path)?.filter_map(|e| e.ok()).collect();
 
     // Sort: directories first, then files, both alphabetically
     entries.sort_by_key(|e| {
@@ -56,11 +156,11 @@ fn build_children(path: &Path, docs_root: &Path) -> Result<Vec<DocsNode>> {
 
         if entry_path.is_dir() {
             // Recursively build directory node
-            let node = build_directory_node(&entry_path, docs_root)?;
+            let node = build_directory_node(&entry_path, project_root)?;
             children.push(node);
         } else if is_markdown_file(&entry_path) {
             // Build file node for markdown files
-            let node = build_file_node(&entry_path, docs_root)?;
+            let node = build_file_node(&entry_path, project_root)?;
             children.push(node);
         }
     }
@@ -69,92 +169,65 @@ fn build_children(path: &Path, docs_root: &Path) -> Result<Vec<DocsNode>> {
 }
 
 /// Build a directory node
-fn build_directory_node(path: &Path, docs_root: &Path) -> Result<DocsNode> {
-    let name = path
-        .file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_else(|| "unknown".to_string());
+fn build_directory_node(path: &Path, project_root: &Path) -> Result<DocsNode> {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "unknown".to_string());
 
-    let relative_path = path
-        .strip_prefix(docs_root)
-        .unwrap_or(path)
-        .to_string_lossy()
-        .to_string();
+    let relative_path = path.strip_prefix(project_root).unwrap_or(path).to_string_lossy().replace('\\', "/");
 
-    let children = build_children(path, docs_root)?;
+    let children = build_children(path, project_root)?;
 
-    Ok(DocsNode {
-        path: relative_path,
-        name,
-        is_file: false,
-        children,
-    })
+    Ok(DocsNode { path: relative_path, name, is_file: false, is_root: false, metadata: None, children })
 }
 
 /// Build a file node
-fn build_file_node(path: &Path, docs_root: &Path) -> Result<DocsNode> {
-    let file_name = path
-        .file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_else(|| "unknown".to_string());
+fn build_file_node(path: &Path, project_root: &Path) -> Result<DocsNode> {
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "unknown".to_string());
 
     // Remove .md extension for display
-    let display_name = file_name
-        .strip_suffix(".md")
-        .unwrap_or(&file_name)
-        .to_string();
+    let display_name = file_name.strip_suffix(".md").unwrap_or(&file_name).to_string();
 
-    let relative_path = path
-        .strip_prefix(docs_root)
-        .unwrap_or(path)
-        .to_string_lossy()
-        .to_string();
+    let relative_path = path.strip_prefix(project_root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+
+    // Unreadable files still show up, just without metadata
+    let metadata = fs::read_to_string(path).ok().and_then(|content| front_matter::parse(&content).0);
 
     Ok(DocsNode {
         path: relative_path,
         name: display_name,
         is_file: true,
+        is_root: false,
+        metadata,
         children: Vec::new(),
     })
 }
 
-/// Check if a file is a markdown file
-fn is_markdown_file(path: &Path) -> bool {
-    path.extension()
-        .and_then(|ext| ext.to_str())
-        .map(|ext| ext.eq_ignore_ascii_case("md"))
-        .unwrap_or(false)
+/// All markdown files below a doc root
+pub fn list_markdown_files(docs_root: &Path) -> Vec<PathBuf> {
+    WalkDir::new(docs_root)
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file() && is_markdown_file(e.path()))
+        .map(|e| e.into_path())
+        .collect()
 }
 
-/// Read a documentation file
-pub fn read_doc_file(docs_root: &Path, relative_path: &str) -> Result<String> {
-    let full_path = docs_root.join(relative_path);
-
-    if !full_path.starts_with(docs_root) {
-        anyhow::bail!("Path traversal attempt detected");
-    }
-
-    if !full_path.exists() {
-        anyhow::bail!("File not found: {}", relative_path);
-    }
+/// Check if a file is a markdown file
+pub fn is_markdown_file(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.eq_ignore_ascii_case("md")).unwrap_or(false)
+}
 
-    let content = fs::read_to_string(&full_path)?;
+/// Read a documentation file at a path already resolved with `resolve_doc_path`
+pub fn read_doc_file(file_path: &Path) -> Result<String> {
+    let content = fs::read_to_string(file_path)?;
     Ok(content)
 }
 
-/// Write a documentation file
-pub fn write_doc_file(docs_root: &Path, relative_path: &str, content: &str) -> Result<()> {
-    let full_path = docs_root.join(relative_path);
-
-    if !full_path.starts_with(docs_root) {
-        anyhow::bail!("Path traversal attempt detected");
-    }
-
+/// Write a documentation file, returning the content as saved on disk
+pub fn write_doc_file(file_path: &Path, content: &str) -> Result<String> {
     // Create parent directories if needed
-    if let Some(parent) = full_path.parent() {
+    if let Some(parent) = file_path.parent() {
         fs::create_dir_all(parent)?;
     }
 
-    fs::write(&full_path, content)?;
-    Ok(())
+    writer::write_preserving(file_path, content)
 }
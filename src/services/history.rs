@@ -0,0 +1,241 @@
+use anyhow::{Context, Result};
+use similar::TextDiff;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::models::{RetentionPolicy, Revision, RevisionContent, RevisionDiff};
+use crate::utils::{hash, jsonl, paths, sandbox, time, writer};
+
+const STORE_DIR: &str = ".cc-atlas/history";
+const OBJECTS_DIR: &str = "objects";
+const INDEX_FILE: &str = "index.jsonl";
+const CURRENT: &str = "current";
+
+/// Held while the index is read and then appended to or rewritten, so concurrent
+/// snapshots and prunes in this process don't drop each other's entries
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryError {
+    RevisionNotFound(String),
+    DifferentFiles(String, String),
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HistoryError::RevisionNotFound(id) => write!(f, "Revision not found: {}", id),
+            HistoryError::DifferentFiles(a, b) => write!(f, "Revisions belong to different files: {} and {}", a, b),
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {}
+
+/// Snapshot a file's current contents before it is overwritten or deleted.
+///
+/// Does nothing if the file doesn't exist yet (e.g. on create).
+pub fn snapshot(project_root: &Path, file_path: &Path, operation: &str, source: &str) -> Result<Option<Revision>> {
+    if !file_path.is_file() {
+        return Ok(None);
+    }
+
+    let root = project_root.canonicalize()?;
+    let content = fs::read_to_string(file_path)?;
+    let content_hash = hash::calculate_hash(&content);
+    let relative = paths::project_relative(&root, file_path)?;
+
+    // Objects are content-addressed, so identical snapshots share storage. The object is
+    // written under the index lock, or a concurrent prune could collect it as unreferenced
+    // before the revision pointing at it is appended.
+    let _index = lock_index();
+    let object = objects_dir(&root).join(&content_hash);
+    if !object.exists() {
+        writer::write_atomic(&object, content.as_bytes())?;
+    }
+
+    let timestamp = time::now_unix();
+    let revision = Revision {
+        id: unique_id(&root, timestamp, &content_hash)?,
+        path: relative.clone(),
+        hash: content_hash,
+        timestamp,
+        created_at: time::format_rfc3339(timestamp),
+        operation: operation.to_string(),
+        source: source.to_string(),
+        size: content.len() as u64,
+    };
+
+    append_index(&root, &revision)?;
+    prune_index(&root, &RetentionPolicy::default(), Some(&relative))?;
+
+    Ok(Some(revision))
+}
+
+/// List revisions, newest first, optionally for a single project-relative path
+pub fn list_revisions(project_root: &Path, path: Option<&str>) -> Result<Vec<Revision>> {
+    let root = project_root.canonicalize()?;
+    let mut revisions: Vec<Revision> =
+        read_index(&root)?.into_iter().filter(|r| path.is_none_or(|p| r.path == normalize(p))).collect();
+
+    revisions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then_with(|| b.id.cmp(&a.id)));
+    Ok(revisions)
+}
+
+/// Load a revision together with its stored content
+pub fn read_revision(project_root: &Path, id: &str) -> Result<RevisionContent> {
+    let root = project_root.canonicalize()?;
+    let revision = find_revision(&root, id)?;
+    let content = fs::read_to_string(objects_dir(&root).join(&revision.hash))
+        .with_context(|| format!("Snapshot content missing for revision {}", id))?;
+
+    Ok(RevisionContent { revision, content })
+}
+
+/// Unified diff from one revision to another revision of the same file, or to the file on disk
+pub fn diff_revisions(project_root: &Path, from: &str, to: &str) -> Result<RevisionDiff> {
+    let old = read_revision(project_root, from)?;
+
+    let new_content = if to == CURRENT {
+        let file_path = sandbox::resolve_path(project_root, &old.revision.path)?;
+        fs::read_to_string(file_path).unwrap_or_default()
+    } else {
+        let new = read_revision(project_root, to)?;
+        if new.revision.path != old.revision.path {
+            return Err(HistoryError::DifferentFiles(old.revision.path, new.revision.path).into());
+        }
+        new.content
+    };
+
+    let unified = TextDiff::from_lines(&old.content, &new_content)
+        .unified_diff()
+        .header(&format!("{}@{}", old.revision.path, from), &format!("{}@{}", old.revision.path, to))
+        .to_string();
+
+    Ok(RevisionDiff { path: old.revision.path, from: from.to_string(), to: to.to_string(), unified })
+}
+
+/// Write a revision's content back to its file, snapshotting what it replaces first
+pub fn restore_revision(project_root: &Path, id: &str, source: &str) -> Result<RevisionContent> {
+    let stored = read_revision(project_root, id)?;
+    let file_path = sandbox::resolve_path(project_root, &stored.revision.path)?;
+
+    snapshot(project_root, &file_path, "restore", source)?;
+
+    // Restore the exact bytes that were captured, not re-formatted ones
+    writer::write_atomic(&file_path, stored.content.as_bytes())?;
+
+    Ok(stored)
+}
+
+/// Apply the retention policy, returning how many revisions were dropped.
+///
+/// When `only_path` is given just that file's revisions are considered.
+pub fn prune(project_root: &Path, policy: &RetentionPolicy, only_path: Option<&str>) -> Result<usize> {
+    let root = project_root.canonicalize()?;
+    let _index = lock_index();
+    prune_index(&root, policy, only_path)
+}
+
+fn prune_index(root: &Path, policy: &RetentionPolicy, only_path: Option<&str>) -> Result<usize> {
+    let mut revisions = read_index(root)?;
+    revisions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then_with(|| b.id.cmp(&a.id)));
+
+    let cutoff = time::now_unix().saturating_sub(policy.max_age_days * 86_400);
+    let mut kept_per_path: HashMap<String, usize> = HashMap::new();
+    let before = revisions.len();
+
+    revisions.retain(|r| {
+        if only_path.is_some_and(|p| p != r.path) {
+            return true;
+        }
+        let kept = kept_per_path.entry(r.path.clone()).or_default();
+        if *kept >= policy.max_per_file || r.timestamp < cutoff {
+            return false;
+        }
+        *kept += 1;
+        true
+    });
+
+    let removed = before - revisions.len();
+    if removed > 0 {
+        revisions.reverse();
+        write_index(root, &revisions)?;
+        collect_garbage(root, &revisions)?;
+    }
+
+    Ok(removed)
+}
+
+fn lock_index() -> MutexGuard<'static, ()> {
+    jsonl::lock(&INDEX_LOCK)
+}
+
+/// Time and content id, suffixed if the same content was snapshotted earlier in the same second
+fn unique_id(root: &Path, timestamp: u64, content_hash: &str) -> Result<String> {
+    let taken: HashSet<String> = read_index(root)?.into_iter().map(|r| r.id).collect();
+    let base = format!("{}-{}", timestamp, &content_hash[..12]);
+    let mut id = base.clone();
+    let mut counter = 1;
+    while taken.contains(&id) {
+        id = format!("{}-{}", base, counter);
+        counter += 1;
+    }
+    Ok(id)
+}
+
+fn find_revision(root: &Path, id: &str) -> Result<Revision> {
+    read_index(root)?
+        .into_iter()
+        .find(|r| r.id == id)
+        .ok_or_else(|| HistoryError::RevisionNotFound(id.to_string()).into())
+}
+
+fn store_dir(root: &Path) -> PathBuf {
+    root.join(STORE_DIR)
+}
+
+fn objects_dir(root: &Path) -> PathBuf {
+    store_dir(root).join(OBJECTS_DIR)
+}
+
+fn index_path(root: &Path) -> PathBuf {
+    store_dir(root).join(INDEX_FILE)
+}
+
+fn read_index(root: &Path) -> Result<Vec<Revision>> {
+    jsonl::read(&index_path(root))
+}
+
+fn append_index(root: &Path, revision: &Revision) -> Result<()> {
+    jsonl::append(&index_path(root), revision)
+}
+
+fn write_index(root: &Path, revisions: &[Revision]) -> Result<()> {
+    jsonl::write(&index_path(root), revisions)
+}
+
+/// Remove objects no longer referenced by any revision
+fn collect_garbage(root: &Path, revisions: &[Revision]) -> Result<()> {
+    let referenced: HashSet<&str> = revisions.iter().map(|r| r.hash.as_str()).collect();
+    let objects = objects_dir(root);
+    if !objects.exists() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(objects)?.filter_map(|e| e.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !referenced.contains(name.as_str()) {
+            fs::remove_file(entry.path())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn normalize(path: &str) -> String {
+    path.trim_start_matches("./").to_string()
+}
@@ -0,0 +1,89 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use super::{analyzer, docs};
+use crate::models::{BacklinksResponse, BrokenLinkReport, LinkInfo, LinkStatus};
+use crate::utils::{links, metrics, paths, sandbox};
+
+/// Every link in the project's memory files and docs, resolved against the filesystem
+pub struct LinkGraph {
+    sources: Vec<String>,
+    links: Vec<LinkInfo>,
+}
+
+impl LinkGraph {
+    /// Scan memory files and every doc root
+    pub fn build(project_root: &Path) -> Result<Self> {
+        metrics::time_phase("link_graph", || Self::scan(project_root))
+    }
+
+    fn scan(project_root: &Path) -> Result<Self> {
+        let root = project_root.canonicalize()?;
+
+        let mut files = analyzer::find_files_named(&root, sandbox::MEMORY_FILE_NAMES)?;
+        for doc_root in docs::doc_roots(&root)? {
+            files.extend(docs::list_markdown_files(&doc_root.dir));
+        }
+
+        let mut sources: Vec<(String, String)> = files
+            .iter()
+            .filter_map(|file| {
+                let relative = paths::project_relative(&root, file).ok()?;
+                let content = fs::read_to_string(file).ok()?;
+                Some((relative, content))
+            })
+            .collect();
+        sources.sort_by(|a, b| a.0.cmp(&b.0));
+        sources.dedup_by(|a, b| a.0 == b.0);
+
+        // Anchors of scanned files come from memory; other markdown targets are read once on demand
+        let mut anchors: HashMap<String, Option<HashSet<String>>> =
+            sources.iter().map(|(path, content)| (path.clone(), Some(links::heading_anchors(content)))).collect();
+
+        let mut all_links = Vec::new();
+        for (path, content) in &sources {
+            let checked = links::check_links(
+                path,
+                content,
+                |target| root.join(target).exists(),
+                |target| {
+                    anchors
+                        .entry(target.to_string())
+                        .or_insert_with(|| {
+                            fs::read_to_string(root.join(target)).ok().map(|c| links::heading_anchors(&c))
+                        })
+                        .clone()
+                },
+            );
+            all_links.extend(checked);
+        }
+
+        Ok(Self { sources: sources.into_iter().map(|(path, _)| path).collect(), links: all_links })
+    }
+
+    /// Links into `path` from other files, and the links `path` itself contains
+    pub fn backlinks(&self, path: &str) -> BacklinksResponse {
+        let path = path.trim_start_matches("./").trim_end_matches('/');
+
+        BacklinksResponse {
+            path: path.to_string(),
+            backlinks: self
+                .links
+                .iter()
+                .filter(|l| l.source != path && l.resolved.as_deref() == Some(path))
+                .cloned()
+                .collect(),
+            outgoing: self.links.iter().filter(|l| l.source == path).cloned().collect(),
+        }
+    }
+
+    pub fn broken_report(&self) -> BrokenLinkReport {
+        BrokenLinkReport {
+            files_scanned: self.sources.len(),
+            links_checked: self.links.len(),
+            broken: self.links.iter().filter(|l| l.status != LinkStatus::Ok).cloned().collect(),
+        }
+    }
+}
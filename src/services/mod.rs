@@ -1,3 +1,12 @@
+pub mod adr;
 pub mod analyzer;
+pub mod batch;
 pub mod chat_exporter;
+pub mod config;
 pub mod docs;
+pub mod history;
+pub mod link_graph;
+pub mod mover;
+pub mod plans;
+pub mod search;
+pub mod trash;
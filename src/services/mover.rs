@@ -0,0 +1,177 @@
+use anyhow::Result;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::{analyzer, history};
+use crate::models::{FileRewrite, MoveResult, MovedPath};
+use crate::utils::links::{self, MoveMap};
+use crate::utils::{paths, sandbox, writer};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveError {
+    SourceNotFound(String),
+    DestinationExists(String),
+    IntoItself(String),
+    OutsideProject(String),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveError::SourceNotFound(path) => write!(f, "Not found: {}", path),
+            MoveError::DestinationExists(path) => write!(f, "Destination already exists: {}", path),
+            MoveError::IntoItself(path) => write!(f, "Cannot move {} into itself", path),
+            MoveError::OutsideProject(path) => write!(f, "Destination is outside the project root: {}", path),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// What to do to put the tree back if a later step of the move fails
+enum Undo {
+    Rename { from: PathBuf, to: PathBuf }, // Move `to` back to `from`
+    Restore { path: String, content: String },
+}
+
+/// A file whose links the move changes
+struct PlannedRewrite {
+    original: String,
+    content: String,
+}
+
+/// Move or rename a file or folder and fix up links and `@imports` that pointed into it.
+///
+/// `from` and `to` are project-relative. With `dry_run` nothing is written and the
+/// result previews the move and every link rewrite it would make.
+pub fn move_path(project_root: &Path, from: &str, to: &str, dry_run: bool, source: &str) -> Result<MoveResult> {
+    let root = project_root.canonicalize()?;
+    let from_path = sandbox::resolve_path(&root, from)?;
+    let to_path = sandbox::resolve_path(&root, to)?;
+    let from = paths::project_relative(&root, &from_path)?;
+    let to = relative_destination(&root, &to_path)?;
+
+    if !from_path.exists() {
+        return Err(MoveError::SourceNotFound(from).into());
+    }
+    if to_path.exists() {
+        return Err(MoveError::DestinationExists(to).into());
+    }
+    if to == from || to.starts_with(&format!("{}/", from)) {
+        return Err(MoveError::IntoItself(from).into());
+    }
+
+    let mut moves = MoveMap::new();
+    moves.add(&from, &to);
+
+    let moved = moved_files(&root, &from_path, &moves)?;
+    let (rewrites, planned) = plan_rewrites(&root, &moves)?;
+
+    if !dry_run {
+        // Snapshot before anything changes so every rewritten file can be rolled back
+        for rewrite in &rewrites {
+            let old = moved_from(&rewrite.path, &moved);
+            history::snapshot(&root, &root.join(old), "move", source)?;
+        }
+
+        let mut undo = Vec::new();
+        if let Err(e) = apply(&root, (&from_path, &to_path), &rewrites, &planned, &mut undo) {
+            roll_back(&root, undo);
+            return Err(e.context(format!("Failed to move {} to {}; the move was rolled back", from, to)));
+        }
+    }
+
+    Ok(MoveResult { dry_run, moved, rewrites })
+}
+
+/// Move the path, then rewrite links, recording how to undo each step
+fn apply(
+    root: &Path,
+    (from_path, to_path): (&Path, &Path),
+    rewrites: &[FileRewrite],
+    planned: &[PlannedRewrite],
+    undo: &mut Vec<Undo>,
+) -> Result<()> {
+    if let Some(parent) = to_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(from_path, to_path)?;
+    undo.push(Undo::Rename { from: from_path.to_path_buf(), to: to_path.to_path_buf() });
+
+    for (rewrite, plan) in rewrites.iter().zip(planned) {
+        writer::write_atomic(&root.join(&rewrite.path), plan.content.as_bytes())?;
+        undo.push(Undo::Restore { path: rewrite.path.clone(), content: plan.original.clone() });
+    }
+
+    Ok(())
+}
+
+/// Best effort: each step is tried even if an earlier one fails, so as much as possible is restored
+fn roll_back(root: &Path, undo: Vec<Undo>) {
+    for step in undo.into_iter().rev() {
+        let restored = match &step {
+            Undo::Rename { from, to } => fs::rename(to, from).map_err(Into::into),
+            Undo::Restore { path, content } => writer::write_atomic(&root.join(path), content.as_bytes()),
+        };
+        if let Err(e) = restored {
+            tracing::error!("Failed to roll back move step: {:#}", e);
+        }
+    }
+}
+
+/// Project-relative form of a destination whose parent directories may not exist yet
+fn relative_destination(root: &Path, to_path: &Path) -> Result<String> {
+    let relative = to_path.strip_prefix(root).map_err(|_| MoveError::OutsideProject(to_path.display().to_string()))?;
+    Ok(relative.to_string_lossy().replace('\\', "/"))
+}
+
+fn moved_files(root: &Path, from_path: &Path, moves: &MoveMap) -> Result<Vec<MovedPath>> {
+    let mut moved = Vec::new();
+
+    for entry in WalkDir::new(from_path).sort_by_file_name().into_iter().flatten() {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let from = paths::project_relative(root, entry.path())?;
+        let to = moves.map(&from).unwrap_or_else(|| from.clone());
+        moved.push(MovedPath { from, to });
+    }
+
+    Ok(moved)
+}
+
+/// Work out every link rewrite across the project's markdown, with each file's old and new content
+fn plan_rewrites(root: &Path, moves: &MoveMap) -> Result<(Vec<FileRewrite>, Vec<PlannedRewrite>)> {
+    let mut rewrites = Vec::new();
+    let mut planned = Vec::new();
+
+    let mut files = analyzer::find_markdown_files(root)?;
+    files.sort();
+
+    for file in files {
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue; // Not UTF-8; nothing we can rewrite
+        };
+        let Ok(old_path) = paths::project_relative(root, &file) else {
+            continue; // Symlinked in from outside the project
+        };
+        let new_path = moves.map(&old_path).unwrap_or_else(|| old_path.clone());
+
+        let (rewritten, changes) =
+            links::rewrite_links(&content, &old_path, &new_path, moves, |target| root.join(target).exists());
+
+        if !changes.is_empty() {
+            rewrites.push(FileRewrite { path: new_path, changes });
+            planned.push(PlannedRewrite { original: content, content: rewritten });
+        }
+    }
+
+    Ok((rewrites, planned))
+}
+
+/// Where a rewritten file lived before the move
+fn moved_from<'a>(new_path: &'a str, moved: &'a [MovedPath]) -> &'a str {
+    moved.iter().find(|m| m.to == new_path).map(|m| m.from.as_str()).unwrap_or(new_path)
+}
@@ -0,0 +1,107 @@
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{config, docs, history};
+use crate::models::PlanSummary;
+use crate::utils::plans::{self, PlanError};
+use crate::utils::sandbox::SandboxError;
+use crate::utils::{hash, paths, sandbox, time, writer};
+
+const PLANS_DIR_NAME: &str = "plans";
+const DEFAULT_PLANS_DIR: &str = "docs/plans";
+
+/// Where plans live: the configured `plans_dir`, a `plans` doc root or a `plans` folder in one, else `docs/plans`
+pub fn plans_dir(project_root: &Path) -> Result<PathBuf> {
+    let root = project_root.canonicalize()?;
+
+    if let Some(dir) = config::load(&root)?.plans_dir {
+        return Ok(sandbox::resolve_path(&root, &dir)?);
+    }
+
+    match docs::find_doc_subdir(&root, PLANS_DIR_NAME)? {
+        Some(dir) => Ok(dir),
+        None => Ok(sandbox::resolve_path(&root, DEFAULT_PLANS_DIR)?),
+    }
+}
+
+/// Every plan under the plans folder, sorted by path
+pub fn list(project_root: &Path, include_tasks: bool) -> Result<Vec<PlanSummary>> {
+    let root = project_root.canonicalize()?;
+    let dir = plans_dir(&root)?;
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut plans = Vec::new();
+    for file_path in docs::list_markdown_files(&dir) {
+        let Ok(content) = fs::read_to_string(&file_path) else {
+            continue;
+        };
+        plans.push(summarize(&root, &file_path, &content, include_tasks)?);
+    }
+
+    plans.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(plans)
+}
+
+/// One plan with its tasks
+pub fn get(project_root: &Path, path: &str) -> Result<PlanSummary> {
+    let root = project_root.canonicalize()?;
+    let file_path = resolve(&root, path)?;
+    let content = fs::read_to_string(&file_path)?;
+    summarize(&root, &file_path, &content, true)
+}
+
+/// Set one checkbox, writing back only that character.
+///
+/// `base_hash` is the content hash the caller last saw; the toggle is refused if the file has moved on.
+pub fn toggle(
+    project_root: &Path,
+    path: &str,
+    line: usize,
+    checked: Option<bool>,
+    expected_text: Option<&str>,
+    base_hash: Option<&str>,
+    source: &str,
+) -> Result<PlanSummary> {
+    let root = project_root.canonicalize()?;
+    let file_path = resolve(&root, path)?;
+    let content = fs::read_to_string(&file_path)?;
+
+    if base_hash.is_some_and(|h| h != hash::calculate_hash(&content)) {
+        return Err(PlanError::Modified(path.to_string()).into());
+    }
+
+    let (updated, _) = plans::toggle_task(&content, line, checked, expected_text)?;
+    if updated != content {
+        history::snapshot(&root, &file_path, "plan", source)?;
+        writer::write_atomic(&file_path, updated.as_bytes())?;
+    }
+
+    summarize(&root, &file_path, &updated, true)
+}
+
+fn resolve(root: &Path, path: &str) -> Result<PathBuf> {
+    let dir = plans_dir(root)?;
+    let relative = paths::project_relative(root, &dir)?;
+    let file_path = sandbox::resolve_within(root, &[relative.as_str()], path).map_err(|err| match err {
+        SandboxError::OutsideAllowed(_) => anyhow::Error::from(PlanError::NotAPlan(path.to_string())),
+        other => other.into(),
+    })?;
+
+    if !docs::is_markdown_file(&file_path) || !file_path.is_file() {
+        return Err(PlanError::NotAPlan(path.to_string()).into());
+    }
+    Ok(file_path)
+}
+
+fn summarize(root: &Path, file_path: &Path, content: &str, include_tasks: bool) -> Result<PlanSummary> {
+    let path = paths::project_relative(root, file_path)?;
+    let updated_at = fs::metadata(file_path)
+        .and_then(|m| m.modified())
+        .map(|t| time::format_rfc3339(time::to_unix(t)))
+        .unwrap_or_default();
+
+    Ok(plans::summarize(&path, content, updated_at, include_tasks))
+}
@@ -0,0 +1,196 @@
+use anyhow::Result;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::docs::DocRoot;
+use super::{analyzer, docs};
+use crate::models::FileKind;
+use crate::utils::search::{DocumentInfo, SearchIndex};
+use crate::utils::{metrics, paths, sandbox, time};
+
+/// How often searches re-check the disk for edits made outside cc-atlas
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Index every memory file and doc in the project
+pub fn build_index(project_root: &Path) -> Result<SearchIndex> {
+    let mut index = SearchIndex::new();
+    refresh(&mut index, project_root)?;
+    Ok(index)
+}
+
+/// Bring the index in line with the disk, re-reading only files whose mtime changed
+pub fn refresh(index: &mut SearchIndex, project_root: &Path) -> Result<()> {
+    let root = project_root.canonicalize()?;
+    let mut seen = HashSet::new();
+
+    for (info, path) in collect_sources(&root)? {
+        if !seen.insert(info.project_path.clone()) {
+            continue;
+        }
+        if index.document(&info.project_path).is_some_and(|d| d.modified == info.modified) {
+            continue;
+        }
+        if let Ok(content) = fs::read_to_string(&path) {
+            index.upsert(info, &content);
+        }
+    }
+
+    for path in index.paths() {
+        if !seen.contains(&path) {
+            index.remove(&path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Server-side index, built on first search and kept current by write handlers.
+///
+/// Searches share the index through an `Arc`, so the lock around the cache is only held to
+/// swap it; refreshing reads the disk on a copy, outside the lock (see [`Refresh`]).
+#[derive(Default)]
+pub struct SearchCache {
+    index: Option<Arc<SearchIndex>>,
+    refreshed_at: Option<Instant>,
+    changes: u64, // Writes reflected so far, to spot ones that raced with a refresh
+}
+
+/// A copy of the index being brought up to date with the disk
+pub struct Refresh {
+    index: SearchIndex,
+    changes: u64,
+}
+
+impl Refresh {
+    /// Re-read whatever changed on disk; blocking, so run it off the async runtime
+    pub fn run(mut self, project_root: &Path) -> Result<Self> {
+        metrics::time_phase("search_refresh", || refresh(&mut self.index, project_root))?;
+        Ok(self)
+    }
+}
+
+impl SearchCache {
+    /// The index, unless the last check against the disk is older than `REFRESH_INTERVAL`
+    pub fn current(&self) -> Option<Arc<SearchIndex>> {
+        let fresh = self.refreshed_at.is_some_and(|t| t.elapsed() < REFRESH_INTERVAL);
+        metrics::cache_lookup("search_index", fresh);
+        self.index.clone().filter(|_| fresh)
+    }
+
+    pub fn begin_refresh(&self) -> Refresh {
+        Refresh { index: self.index.as_deref().cloned().unwrap_or_default(), changes: self.changes }
+    }
+
+    /// Swap in a refreshed index for later searches
+    pub fn finish_refresh(&mut self, refresh: Refresh) -> Arc<SearchIndex> {
+        let index = Arc::new(refresh.index);
+        self.index = Some(index.clone());
+        // A file written during the refresh may have been read before the write; check again next time
+        self.refreshed_at = (self.changes == refresh.changes).then(Instant::now);
+        index
+    }
+
+    /// Whether a search has built the index yet; until then writes have nothing to update
+    pub fn is_built(&self) -> bool {
+        self.index.is_some()
+    }
+
+    /// Reflect a write, delete or restore without waiting for the next refresh.
+    ///
+    /// `change` is read from disk before the lock around the cache is taken; `None` means it
+    /// wasn't read because the index didn't exist yet.
+    pub fn apply(&mut self, change: Option<Change>) {
+        self.changes += 1;
+        let Some(index) = self.index.as_mut() else {
+            return; // Nothing to update until the first search builds it
+        };
+        let Some(change) = change else {
+            self.refreshed_at = None; // Built since the change was skipped; check the disk again
+            return;
+        };
+        // make_mut copies the index only if a search is still reading the current one
+        match change {
+            Change::Rescan => self.refreshed_at = None,
+            Change::Upsert(info, content) => Arc::make_mut(index).upsert(info, &content),
+            Change::Remove(path) => {
+                Arc::make_mut(index).remove(&path);
+            }
+            Change::RemoveDir(path) => {
+                Arc::make_mut(index).remove_dir(&path);
+            }
+        }
+    }
+}
+
+/// What a write did to one path, as far as the index is concerned
+pub enum Change {
+    Upsert(DocumentInfo, String),
+    Remove(String),
+    RemoveDir(String), // Possibly a deleted docs folder
+    Rescan,            // A restored folder can bring back any number of files
+}
+
+impl Change {
+    /// Read what is now at `path`; blocking, and done without holding the cache
+    pub fn read(project_root: &Path, path: &Path) -> Change {
+        if path.is_dir() {
+            return Change::Rescan;
+        }
+        let Ok(root) = project_root.canonicalize() else {
+            return Change::Rescan;
+        };
+
+        let doc_roots = docs::doc_roots(&root).unwrap_or_default();
+        match describe(&root, &doc_roots, path) {
+            Some(info) => match fs::read_to_string(path) {
+                Ok(content) => Change::Upsert(info, content),
+                Err(_) => Change::Remove(info.project_path),
+            },
+            None => match paths::project_relative(&root, path) {
+                Ok(relative) => Change::RemoveDir(relative),
+                Err(_) => Change::Rescan,
+            },
+        }
+    }
+}
+
+fn collect_sources(root: &Path) -> Result<Vec<(DocumentInfo, PathBuf)>> {
+    let roots = docs::doc_roots(root)?;
+    let mut files = analyzer::find_files_named(root, sandbox::MEMORY_FILE_NAMES)?;
+    for doc_root in &roots {
+        files.extend(docs::list_markdown_files(&doc_root.dir));
+    }
+
+    Ok(files.into_iter().filter_map(|path| describe(root, &roots, &path).map(|info| (info, path))).collect())
+}
+
+/// Classify a file under the canonical `root` as a memory file or doc; `None` if neither
+fn describe(root: &Path, doc_roots: &[DocRoot], path: &Path) -> Option<DocumentInfo> {
+    let project_path = paths::project_relative(root, path).ok()?;
+    let file_name = path.file_name()?.to_string_lossy().to_string();
+    let modified = fs::metadata(path).and_then(|m| m.modified()).map(time::to_unix).unwrap_or(0);
+
+    if sandbox::MEMORY_FILE_NAMES.contains(&file_name.as_str()) {
+        if analyzer::is_ignored(Path::new(&project_path)) {
+            return None;
+        }
+        return Some(DocumentInfo {
+            kind: FileKind::Memory,
+            path: project_path.clone(),
+            scope: Some(paths::memory_scope(&project_path)),
+            project_path,
+            modified,
+        });
+    }
+
+    if !docs::is_markdown_file(path) {
+        return None;
+    }
+
+    docs::root_for(doc_roots, &project_path)?;
+
+    Some(DocumentInfo { kind: FileKind::Docs, path: project_path.clone(), scope: None, project_path, modified })
+}
@@ -0,0 +1,187 @@
+use anyhow::{Context, Result};
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::models::TrashEntry;
+use crate::utils::{jsonl, paths, sandbox, time};
+
+const TRASH_DIR: &str = ".cc-atlas/trash";
+const ITEMS_DIR: &str = "items";
+const INDEX_FILE: &str = "index.jsonl";
+/// Name of the trashed file or folder inside its id's slot directory
+const SLOT_ITEM: &str = ".trashed";
+
+/// Held while the index is appended to or rewritten
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrashError {
+    EntryNotFound(String),
+    PathOccupied(String),
+}
+
+impl fmt::Display for TrashError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TrashError::EntryNotFound(id) => write!(f, "Trash entry not found: {}", id),
+            TrashError::PathOccupied(path) => write!(f, "File already exists: {}", path),
+        }
+    }
+}
+
+impl std::error::Error for TrashError {}
+
+/// Move a file or directory into the project trash instead of deleting it
+pub fn move_to_trash(project_root: &Path, target: &Path, source: &str) -> Result<TrashEntry> {
+    let root = project_root.canonicalize()?;
+    let relative = paths::project_relative(&root, target)?;
+    let is_dir = target.is_dir();
+
+    let timestamp = time::now_unix();
+    let id = reserve_slot(&root, timestamp)?;
+    let slot = items_dir(&root).join(&id);
+
+    if let Err(e) = fs::rename(target, slot.join(SLOT_ITEM)) {
+        let _ = fs::remove_dir(&slot);
+        return Err(anyhow::Error::new(e).context(format!("Failed to move {} to trash", relative)));
+    }
+
+    let entry = TrashEntry {
+        id,
+        path: relative,
+        is_dir,
+        timestamp,
+        deleted_at: time::format_rfc3339(timestamp),
+        source: source.to_string(),
+    };
+
+    let _index = lock_index();
+    jsonl::append(&index_path(&root), &entry)?;
+
+    Ok(entry)
+}
+
+/// List trashed items, most recently deleted first
+pub fn list_trash(project_root: &Path) -> Result<Vec<TrashEntry>> {
+    let root = project_root.canonicalize()?;
+    let mut entries = read_index(&root)?;
+    entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then_with(|| b.id.cmp(&a.id)));
+    Ok(entries)
+}
+
+/// Find a trashed item by id
+pub fn find_entry(project_root: &Path, id: &str) -> Result<TrashEntry> {
+    let root = project_root.canonicalize()?;
+    read_index(&root)?.into_iter().find(|e| e.id == id).ok_or_else(|| TrashError::EntryNotFound(id.to_string()).into())
+}
+
+/// Put a trashed item back at its original path; fails if something now lives there
+pub fn restore(project_root: &Path, id: &str) -> Result<TrashEntry> {
+    let root = project_root.canonicalize()?;
+    let entry = find_entry(&root, id)?;
+    let destination = sandbox::resolve_path(&root, &entry.path)?;
+
+    if destination.exists() {
+        return Err(TrashError::PathOccupied(entry.path).into());
+    }
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let slot = items_dir(&root).join(&entry.id);
+    fs::rename(item_path(&root, &entry.id), &destination)
+        .with_context(|| format!("Failed to restore {}", entry.path))?;
+    if slot.is_dir() {
+        let _ = fs::remove_dir(&slot);
+    }
+    remove_from_index(&root, std::slice::from_ref(&entry.id))?;
+
+    Ok(entry)
+}
+
+/// Permanently delete one trashed item, or everything when `id` is `None`
+pub fn purge(project_root: &Path, id: Option<&str>) -> Result<Vec<TrashEntry>> {
+    let root = project_root.canonicalize()?;
+
+    let purged: Vec<TrashEntry> = match id {
+        Some(id) => vec![find_entry(&root, id)?],
+        None => read_index(&root)?,
+    };
+
+    for entry in &purged {
+        let item = items_dir(&root).join(&entry.id);
+        if item.is_dir() {
+            fs::remove_dir_all(&item)?;
+        } else if item.exists() {
+            fs::remove_file(&item)?;
+        }
+    }
+
+    let ids: Vec<String> = purged.iter().map(|e| e.id.clone()).collect();
+    remove_from_index(&root, &ids)?;
+
+    Ok(purged)
+}
+
+fn trash_dir(root: &Path) -> PathBuf {
+    root.join(TRASH_DIR)
+}
+
+fn items_dir(root: &Path) -> PathBuf {
+    trash_dir(root).join(ITEMS_DIR)
+}
+
+/// Claim a timestamp-based id by creating its slot directory, suffixing the id if several
+/// deletions land in the same second. Creating the directory either succeeds or fails for
+/// exactly one caller, so two deletions can never be moved onto the same slot.
+fn reserve_slot(root: &Path, timestamp: u64) -> Result<String> {
+    fs::create_dir_all(items_dir(root))?;
+    let mut id = timestamp.to_string();
+    let mut counter = 1;
+    loop {
+        match fs::create_dir(items_dir(root).join(&id)) {
+            Ok(()) => return Ok(id),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                id = format!("{}-{}", timestamp, counter);
+                counter += 1;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Where a trashed item is kept: inside its slot, or the slot itself for items trashed before slots
+fn item_path(root: &Path, id: &str) -> PathBuf {
+    let slot = items_dir(root).join(id);
+    let item = slot.join(SLOT_ITEM);
+    if item.symlink_metadata().is_ok() {
+        item
+    } else {
+        slot
+    }
+}
+
+fn lock_index() -> MutexGuard<'static, ()> {
+    jsonl::lock(&INDEX_LOCK)
+}
+
+fn index_path(root: &Path) -> PathBuf {
+    trash_dir(root).join(INDEX_FILE)
+}
+
+fn read_index(root: &Path) -> Result<Vec<TrashEntry>> {
+    jsonl::read(&index_path(root))
+}
+
+fn remove_from_index(root: &Path, ids: &[String]) -> Result<()> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let _index = lock_index();
+    let remaining: Vec<TrashEntry> = read_index(root)?.into_iter().filter(|e| !ids.contains(&e.id)).collect();
+    jsonl::write(&index_path(root), &remaining)
+}
@@ -0,0 +1,95 @@
+use crate::models::AdrStatus;
+use crate::utils::adr::*;
+use crate::utils::front_matter;
+
+const LEGACY: &str =
+    "# 3. Use Postgres for events\n\n## Status\n\nAccepted\n\n## Context\n\nWe need durable storage.\n";
+
+#[test]
+fn test_numbers_and_file_names() {
+    assert_eq!(parse_number("0012-use-postgres.md"), Some(12));
+    assert_eq!(parse_number("7.md"), Some(7));
+    assert_eq!(parse_number("README.md"), None);
+    assert_eq!(parse_number("2024plan.md"), None);
+    assert_eq!(parse_number("0001-x.txt"), None);
+
+    assert_eq!(next_number([]), 1);
+    assert_eq!(next_number([1, 4, 2]), 5);
+    assert_eq!(file_name(7, "Use Postgres: for events!"), "0007-use-postgres-for-events.md");
+    assert_eq!(file_name(8, "???"), "0008.md");
+}
+
+#[test]
+fn test_status_lifecycle() {
+    use AdrStatus::*;
+
+    assert!(can_transition(Proposed, Accepted));
+    assert!(can_transition(Accepted, Superseded));
+    assert!(can_transition(Proposed, Deprecated));
+    assert!(!can_transition(Accepted, Proposed));
+    assert!(!can_transition(Superseded, Accepted));
+    assert!(!can_transition(Deprecated, Deprecated));
+
+    assert_eq!(parse_status("Superseded by ADR 7"), Some(Superseded));
+    assert_eq!(parse_status("**Accepted**"), Some(Accepted));
+    assert_eq!(parse_status("Rejected"), None);
+}
+
+#[test]
+fn test_template_quotes_title_in_front_matter() {
+    let content = render_template(DEFAULT_TEMPLATE, 4, "Cache: per tenant", "2025-02-01");
+    let (metadata, body) = front_matter::parse(&content);
+    let metadata = metadata.unwrap();
+
+    assert_eq!(metadata.title.as_deref(), Some("Cache: per tenant"));
+    assert_eq!(metadata.status.as_deref(), Some("proposed"));
+    assert_eq!(metadata.updated.as_deref(), Some("2025-02-01"));
+    assert!(body.starts_with("# 0004. Cache: per tenant\n"));
+}
+
+#[test]
+fn test_summarizes_hand_written_adrs() {
+    let adr = summarize(3, "docs/adr/0003-use-postgres.md", LEGACY);
+
+    assert_eq!(adr.title, "Use Postgres for events");
+    assert_eq!(adr.status, AdrStatus::Accepted);
+    assert_eq!(adr.date, None);
+    assert!(adr.supersedes.is_empty());
+}
+
+#[test]
+fn test_update_keeps_front_matter_and_status_section_in_step() {
+    let updated = update_content(LEGACY, "0003.md", Some(AdrStatus::Superseded), Some(9), None, "2025-03-01").unwrap();
+    let adr = summarize(3, "0003.md", &updated);
+
+    assert_eq!(adr.status, AdrStatus::Superseded);
+    assert_eq!(adr.superseded_by, Some(9));
+    assert_eq!(adr.date.as_deref(), Some("2025-03-01"));
+    assert!(updated.contains("## Status\n\nSuperseded by ADR 0009\n"));
+    assert!(updated.ends_with("We need durable storage.\n"));
+
+    // Adding the reverse link is idempotent
+    let replacement = update_content("# 9. New\n", "0009.md", None, None, Some(3), "2025-03-01").unwrap();
+    let replacement = update_content(&replacement, "0009.md", None, None, Some(3), "2025-03-01").unwrap();
+    assert_eq!(summarize(9, "0009.md", &replacement).supersedes, vec![3]);
+
+    let broken = "---\ntitle: [\n---\n# 1. X\n";
+    assert_eq!(
+        update_content(broken, "0001.md", Some(AdrStatus::Accepted), None, None, "2025-03-01"),
+        Err(AdrError::InvalidFrontMatter("0001.md".to_string()))
+    );
+}
+
+#[test]
+fn test_index_links_supersede_chain() {
+    let mut old = summarize(1, "docs/adr/0001-a.md", "# 1. A | B\n");
+    old.status = AdrStatus::Superseded;
+    old.superseded_by = Some(2);
+    let mut new = summarize(2, "docs/adr/0002-b.md", "# 2. C\n");
+    new.supersedes = vec![1];
+
+    let index = render_index(&[old, new], |a| a.path.trim_start_matches("docs/adr/").to_string());
+    assert!(index.contains("| [0001](0001-a.md) | A \\| B | Superseded by [0002](0002-b.md) |  |\n"));
+    assert!(index.contains("| [0002](0002-b.md) | C | Proposed, supersedes [0001](0001-a.md) |  |\n"));
+    assert!(render_index(&[], |a| a.path.clone()).contains("No decisions recorded yet."));
+}
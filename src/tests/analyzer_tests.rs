@@ -0,0 +1,65 @@
+use std::fs;
+use std::path::Path;
+
+use crate::services::analyzer::*;
+use crate::utils::metrics::{self, SCAN_DURATION};
+
+fn write_lines(path: &Path, lines: usize) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, "line\n".repeat(lines)).unwrap();
+}
+
+fn scans() -> u64 {
+    metrics::global().count(SCAN_DURATION, &[("phase", "scan")])
+}
+
+#[test]
+fn test_tree_stats_and_recommendations_from_one_walk() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write_lines(&root.join("CLAUDE.md"), 2);
+    for i in 0..12 {
+        write_lines(&root.join(format!("api/handlers/h{}.rs", i)), 10);
+    }
+    write_lines(&root.join("api/a/b/c/deep.rs"), 1000); // Four levels below api/
+    write_lines(&root.join("web/CLAUDE.md"), 1);
+    write_lines(&root.join("web/app.ts"), 600);
+    write_lines(&root.join("node_modules/pkg/index.js"), 5000);
+
+    let before = scans();
+    let tree = build_directory_tree(root).unwrap();
+    let recommendations = get_recommendations(&tree);
+    assert_eq!(scans() - before, 1, "the project should be walked exactly once");
+
+    let child = |name: &str| tree.children.iter().find(|c| c.name == name).unwrap();
+    let names: Vec<&str> = tree.children.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["api", "web"]);
+    assert!(tree.has_memory);
+
+    let api = child("api");
+    assert_eq!((api.stats.file_count, api.stats.total_lines), (12, 120));
+    assert!(api.should_recommend);
+    let deepest = &api.children.iter().find(|c| c.name == "a").unwrap().children[0].children[0];
+    assert_eq!((deepest.stats.file_count, deepest.stats.total_lines), (1, 1000));
+
+    let web = child("web");
+    assert_eq!((web.stats.file_count, web.stats.total_lines), (2, 601));
+    assert!(web.has_memory && !web.should_recommend);
+
+    assert_eq!(recommendations.iter().filter(|p| p.ends_with("api")).count(), 1);
+    assert!(recommendations.iter().all(|p| !p.ends_with("web")));
+}
+
+#[test]
+fn test_partial_tree_keeps_stats_of_unloaded_levels() {
+    let dir = tempfile::tempdir().unwrap();
+    write_lines(&dir.path().join("pkg/src/lib/util.rs"), 7);
+
+    let options = TreeOptions { depth: Some(1), include_files: false };
+    let tree = build_tree(dir.path(), &options).unwrap();
+
+    let pkg = &tree.children[0];
+    assert!(pkg.children.is_empty() && pkg.has_children);
+    assert_eq!((pkg.stats.file_count, pkg.stats.total_lines), (1, 7));
+    assert_eq!(calculate_stats(&dir.path().join("pkg")).unwrap().total_lines, 7);
+}
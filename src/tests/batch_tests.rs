@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use crate::models::{BatchChange, BatchOperation};
+use crate::utils::batch::*;
+use crate::utils::hash::calculate_hash;
+
+fn disk(files: &[(&str, &str)]) -> HashMap<String, String> {
+    files.iter().map(|(p, c)| (p.to_string(), c.to_string())).collect()
+}
+
+fn run(files: &HashMap<String, String>, operations: &[BatchOperation]) -> Result<Vec<FilePlan>, BatchError> {
+    plan(operations, |path| files.get(path).cloned())
+}
+
+#[test]
+fn test_move_section_between_files() {
+    let root = "# Project\n\n## Build\nRun make.\n";
+    let files = disk(&[("CLAUDE.md", root)]);
+    let operations = vec![
+        BatchOperation::Update {
+            path: "CLAUDE.md".into(),
+            content: "# Project\n".into(),
+            base_hash: calculate_hash(root),
+        },
+        BatchOperation::Create { path: "packages/app/CLAUDE.md".into(), content: "## Build\nRun make.\n".into() },
+    ];
+
+    let plans = run(&files, &operations).unwrap();
+    let result = summarize(&plans, true);
+
+    let changes: Vec<(&str, BatchChange)> = result.files.iter().map(|f| (f.path.as_str(), f.change)).collect();
+    assert_eq!(changes, vec![("CLAUDE.md", BatchChange::Updated), ("packages/app/CLAUDE.md", BatchChange::Created)]);
+    assert!(result.dry_run);
+    assert!(result.diff.contains("--- a/CLAUDE.md\n+++ b/CLAUDE.md\n"));
+    assert!(result.diff.contains("-## Build\n"));
+    assert!(result.diff.contains("--- /dev/null\n+++ b/packages/app/CLAUDE.md\n"));
+    assert_eq!(result.files[1].content_hash, Some(calculate_hash("## Build\nRun make.\n")));
+}
+
+#[test]
+fn test_validation_sees_earlier_operations() {
+    let files = disk(&[("CLAUDE.md", "root\n"), ("docs/CLAUDE.md", "docs\n")]);
+    let docs_hash = calculate_hash("docs\n");
+
+    // Creating where a file was just deleted is fine; deleting it twice is not
+    let operations = vec![
+        BatchOperation::Delete { path: "docs/CLAUDE.md".into(), base_hash: docs_hash.clone() },
+        BatchOperation::Create { path: "docs/CLAUDE.md".into(), content: "new\n".into() },
+        BatchOperation::Delete { path: "docs/CLAUDE.md".into(), base_hash: docs_hash.clone() },
+    ];
+    assert_eq!(
+        run(&files, &operations),
+        Err(BatchError::Modified {
+            operation: 2,
+            path: "docs/CLAUDE.md".into(),
+            base_hash: docs_hash.clone(),
+            current_hash: calculate_hash("new\n"),
+        })
+    );
+
+    let operations = vec![BatchOperation::Create { path: "CLAUDE.md".into(), content: "x".into() }];
+    assert_eq!(run(&files, &operations), Err(BatchError::Exists { operation: 0, path: "CLAUDE.md".into() }));
+
+    let operations =
+        vec![BatchOperation::Update { path: "api/CLAUDE.md".into(), content: "x".into(), base_hash: docs_hash }];
+    assert_eq!(run(&files, &operations), Err(BatchError::NotFound { operation: 0, path: "api/CLAUDE.md".into() }));
+
+    assert_eq!(run(&files, &[]), Err(BatchError::Empty));
+}
+
+#[test]
+fn test_update_accepts_etag_and_keeps_format() {
+    let original = "\u{feff}line one\r\nline two\r\n";
+    let files = disk(&[("CLAUDE.md", original)]);
+    let operations = vec![BatchOperation::Update {
+        path: "CLAUDE.md".into(),
+        content: "line one\nline 2".into(),
+        base_hash: format!("\"{}\"", calculate_hash(original)),
+    }];
+
+    let plans = run(&files, &operations).unwrap();
+    assert_eq!(plans[0].content.as_deref(), Some("\u{feff}line one\r\nline 2\r\n"));
+}
+
+#[test]
+fn test_moves_and_deletes() {
+    let files = disk(&[("a/CLAUDE.md", "alpha\n"), ("b/CLAUDE.md", "beta\n")]);
+    let operations = vec![
+        BatchOperation::Move {
+            from: "a/CLAUDE.md".into(),
+            to: "c/CLAUDE.md".into(),
+            base_hash: calculate_hash("alpha\n"),
+        },
+        BatchOperation::Move {
+            from: "b/CLAUDE.md".into(),
+            to: "d/CLAUDE.md".into(),
+            base_hash: calculate_hash("beta\n"),
+        },
+        BatchOperation::Delete { path: "d/CLAUDE.md".into(), base_hash: calculate_hash("beta\n") },
+    ];
+
+    let plans = run(&files, &operations).unwrap();
+    let result = summarize(&plans, false);
+
+    let changes: Vec<(&str, BatchChange, Option<&str>)> =
+        result.files.iter().map(|f| (f.path.as_str(), f.change, f.from.as_deref())).collect();
+    assert_eq!(
+        changes,
+        vec![("b/CLAUDE.md", BatchChange::Deleted, None), ("c/CLAUDE.md", BatchChange::Moved, Some("a/CLAUDE.md")),]
+    );
+
+    // Moved content that is then deleted sends the original file to the trash
+    let source = plans.iter().find(|p| p.path == "b/CLAUDE.md").unwrap();
+    assert_eq!(source.removal, Some(Removal::Deleted));
+
+    assert!(result.diff.contains("--- a/a/CLAUDE.md\n+++ b/c/CLAUDE.md\n"));
+    assert!(result.diff.contains("--- a/b/CLAUDE.md\n+++ /dev/null\n"));
+}
@@ -0,0 +1,48 @@
+use std::fs;
+use std::process::Command;
+
+use crate::server::daemon;
+
+#[test]
+fn test_running_finds_this_program() {
+    let dir = tempfile::tempdir().unwrap();
+    let info = daemon::describe(dir.path(), Some(("127.0.0.1", 3999)), None, false);
+    daemon::register(dir.path(), &info).unwrap();
+
+    let running = daemon::running(dir.path()).unwrap().expect("registered server");
+    assert_eq!(running.pid, std::process::id());
+    daemon::unregister(dir.path());
+    assert!(daemon::running(dir.path()).unwrap().is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_reused_pid_is_not_the_server() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut other = Command::new("sleep").arg("30").spawn().unwrap();
+    let mut info = daemon::describe(dir.path(), Some(("127.0.0.1", 3999)), None, false);
+    info.pid = other.id();
+    daemon::register(dir.path(), &info).unwrap();
+
+    // `stop` would have signalled `sleep`; instead the stale files are cleaned up
+    assert!(daemon::stop(dir.path()).unwrap().is_none());
+    assert!(!dir.path().join(".cc-atlas/server.pid").exists());
+    assert!(other.try_wait().unwrap().is_none());
+    other.kill().unwrap();
+    other.wait().unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_log_file_is_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let log = daemon::log_path(dir.path());
+    fs::create_dir_all(log.parent().unwrap()).unwrap();
+    fs::write(&log, "old\n").unwrap();
+    fs::set_permissions(&log, fs::Permissions::from_mode(0o644)).unwrap();
+
+    daemon::open_log(&log).unwrap();
+    assert_eq!(fs::metadata(&log).unwrap().permissions().mode() & 0o777, 0o600);
+}
@@ -0,0 +1,79 @@
+use std::fs;
+use std::path::Path;
+
+use crate::services::docs::*;
+use crate::utils::sandbox::SandboxError;
+
+fn mkdirs(root: &Path, dirs: &[&str]) {
+    for dir in dirs {
+        fs::create_dir_all(root.join(dir)).unwrap();
+    }
+}
+
+fn root_paths(roots: &[DocRoot]) -> Vec<&str> {
+    roots.iter().map(|r| r.path.as_str()).collect()
+}
+
+fn write_config(root: &Path, json: &str) {
+    fs::create_dir_all(root.join(".cc-atlas")).unwrap();
+    fs::write(root.join(".cc-atlas/config.json"), json).unwrap();
+}
+
+#[test]
+fn test_detects_doc_folders() {
+    let dir = tempfile::tempdir().unwrap();
+    mkdirs(dir.path(), &["docs/adr", "api/documentation", ".claude/docs", "node_modules/pkg/docs", "a/b/c/docs"]);
+
+    let roots = doc_roots(dir.path()).unwrap();
+
+    // docs/adr sits inside docs, .claude/docs is added although .claude is ignored,
+    // and folders below the detection depth or in ignored directories are skipped
+    assert_eq!(root_paths(&roots), vec![".claude/docs", "api/documentation", "docs"]);
+    assert!(roots.iter().all(|r| r.dir.is_dir() && r.name == r.path));
+}
+
+#[test]
+fn test_config_overrides_detection() {
+    let dir = tempfile::tempdir().unwrap();
+    mkdirs(dir.path(), &["docs", "handbook"]);
+    write_config(
+        dir.path(),
+        r#"{"docs_roots": [
+            {"path": "handbook", "name": "Handbook"},
+            {"path": "missing"},
+            {"path": "../outside"},
+            {"path": "./handbook/"}
+        ]}"#,
+    );
+
+    let roots = doc_roots(dir.path()).unwrap();
+
+    // Missing and escaping paths are dropped, repeats collapse, and docs isn't detected
+    assert_eq!(root_paths(&roots), vec!["handbook"]);
+    assert_eq!(roots[0].name, "Handbook");
+}
+
+#[test]
+fn test_root_for_picks_the_innermost_root() {
+    let dir = tempfile::tempdir().unwrap();
+    mkdirs(dir.path(), &["docs/adr"]);
+    write_config(dir.path(), r#"{"docs_roots": [{"path": "docs"}, {"path": "docs/adr"}]}"#);
+    let roots = doc_roots(dir.path()).unwrap();
+
+    assert_eq!(root_for(&roots, "docs/adr/0001-use-rust.md").unwrap().path, "docs/adr");
+    assert_eq!(root_for(&roots, "docs/guide.md").unwrap().path, "docs");
+    assert_eq!(root_for(&roots, "docs").unwrap().path, "docs");
+    assert!(root_for(&roots, "docsite/index.md").is_none());
+}
+
+#[test]
+fn test_resolve_doc_path_stays_inside_a_root() {
+    let dir = tempfile::tempdir().unwrap();
+    mkdirs(dir.path(), &["docs", "src"]);
+    let roots = doc_roots(dir.path()).unwrap();
+
+    assert!(resolve_doc_path(dir.path(), &roots, "docs/guide.md").unwrap().ends_with("docs/guide.md"));
+    assert!(matches!(resolve_doc_path(dir.path(), &roots, "docs"), Err(SandboxError::OutsideAllowed(_))));
+    assert!(matches!(resolve_doc_path(dir.path(), &roots, "src/main.md"), Err(SandboxError::OutsideAllowed(_))));
+    assert!(matches!(resolve_doc_path(dir.path(), &roots, "docs/../src/a.md"), Err(SandboxError::Traversal(_))));
+}
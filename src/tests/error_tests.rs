@@ -0,0 +1,142 @@
+use axum::http::StatusCode;
+use std::io;
+
+use crate::models::{AdrStatus, ErrorCode};
+use crate::server::error::ServerError;
+use crate::services::chat_exporter::ChatError;
+use crate::services::history::HistoryError;
+use crate::services::mover::MoveError;
+use crate::services::trash::TrashError;
+use crate::utils::adr::AdrError;
+use crate::utils::batch::BatchError;
+use crate::utils::plans::PlanError;
+use crate::utils::sandbox::SandboxError;
+
+/// Map each error the way a handler sees it: wrapped in context by the service
+fn assert_maps<E>(cases: Vec<(E, ErrorCode)>)
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    for (err, code) in cases {
+        let cause = err.to_string();
+        let mapped = ServerError::from(anyhow::Error::new(err).context("Failed to handle request"));
+        assert_eq!(mapped.code, code, "{}", cause);
+        assert!(mapped.message.starts_with("Failed to handle request: "), "{}", mapped.message);
+        assert!(mapped.message.ends_with(&cause), "{}", mapped.message);
+    }
+}
+
+#[test]
+fn test_sandbox_errors() {
+    assert_maps(vec![
+        (SandboxError::Empty, ErrorCode::InvalidPath),
+        (SandboxError::Absolute("/etc".into()), ErrorCode::InvalidPath),
+        (SandboxError::Traversal("../x".into()), ErrorCode::InvalidPath),
+        (SandboxError::Escape("link".into()), ErrorCode::PathOutsideProject),
+        (SandboxError::NotMemoryFile("a.txt".into()), ErrorCode::NotAMemoryFile),
+        (SandboxError::OutsideAllowed("src/a.md".into()), ErrorCode::PathOutsideDocs),
+        (SandboxError::RootUnavailable("/gone".into()), ErrorCode::Internal),
+    ]);
+}
+
+#[test]
+fn test_adr_errors() {
+    assert_maps(vec![
+        (AdrError::NotFound(7), ErrorCode::AdrNotFound),
+        (AdrError::EmptyTitle, ErrorCode::InvalidRequest),
+        (
+            AdrError::InvalidTransition { from: AdrStatus::Deprecated, to: AdrStatus::Proposed },
+            ErrorCode::InvalidTransition,
+        ),
+        (AdrError::MissingSupersededBy, ErrorCode::InvalidRequest),
+        (AdrError::SelfSupersede(3), ErrorCode::InvalidRequest),
+        (AdrError::InvalidFrontMatter("status".into()), ErrorCode::InvalidMetadata),
+    ]);
+}
+
+#[test]
+fn test_plan_errors() {
+    assert_maps(vec![
+        (PlanError::NotAPlan("a.md".into()), ErrorCode::PlanNotFound),
+        (PlanError::NotATask(4), ErrorCode::NotATask),
+        (PlanError::TextChanged { line: 4, found: "- [ ] b".into() }, ErrorCode::TaskChanged),
+        (PlanError::Modified("a.md".into()), ErrorCode::HashMismatch),
+    ]);
+}
+
+#[test]
+fn test_chat_errors() {
+    assert_maps(vec![(ChatError::NotFound("abc".into()), ErrorCode::ChatNotFound)]);
+}
+
+#[test]
+fn test_history_errors() {
+    assert_maps(vec![
+        (HistoryError::RevisionNotFound("1-abc".into()), ErrorCode::RevisionNotFound),
+        (HistoryError::DifferentFiles("a.md".into(), "b.md".into()), ErrorCode::InvalidRequest),
+    ]);
+}
+
+#[test]
+fn test_trash_errors() {
+    assert_maps(vec![
+        (TrashError::EntryNotFound("1".into()), ErrorCode::TrashEntryNotFound),
+        (TrashError::PathOccupied("a.md".into()), ErrorCode::FileExists),
+    ]);
+}
+
+#[test]
+fn test_move_errors() {
+    assert_maps(vec![
+        (MoveError::SourceNotFound("a".into()), ErrorCode::FileNotFound),
+        (MoveError::DestinationExists("b".into()), ErrorCode::FileExists),
+        (MoveError::IntoItself("a".into()), ErrorCode::InvalidMove),
+        (MoveError::OutsideProject("../b".into()), ErrorCode::PathOutsideProject),
+    ]);
+}
+
+#[test]
+fn test_batch_errors() {
+    assert_maps(vec![
+        (BatchError::Empty, ErrorCode::InvalidRequest),
+        (BatchError::NotFound { operation: 0, path: "a.md".into() }, ErrorCode::FileNotFound),
+        (BatchError::Exists { operation: 1, path: "b.md".into() }, ErrorCode::FileExists),
+        (
+            BatchError::Modified {
+                operation: 2,
+                path: "c.md".into(),
+                base_hash: "old".into(),
+                current_hash: "new".into(),
+            },
+            ErrorCode::HashMismatch,
+        ),
+    ]);
+}
+
+#[test]
+fn test_io_errors() {
+    assert_maps(vec![
+        (io::Error::from(io::ErrorKind::NotFound), ErrorCode::FileNotFound),
+        (io::Error::from(io::ErrorKind::PermissionDenied), ErrorCode::PermissionDenied),
+        (io::Error::from(io::ErrorKind::AlreadyExists), ErrorCode::FileExists),
+        (io::Error::from(io::ErrorKind::InvalidData), ErrorCode::IoError),
+    ]);
+}
+
+#[test]
+fn test_untyped_errors_are_internal() {
+    let mapped = ServerError::from(anyhow::anyhow!("boom").context("Failed to handle request"));
+    assert_eq!(mapped.code, ErrorCode::Internal);
+    assert_eq!(mapped.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(mapped.message, "Failed to handle request: boom");
+}
+
+#[test]
+fn test_typed_cause_below_several_contexts() {
+    let err = anyhow::Error::new(TrashError::EntryNotFound("1".into()))
+        .context("Failed to find trash entry")
+        .context("Failed to restore");
+    let mapped = ServerError::from(err);
+    assert_eq!(mapped.code, ErrorCode::TrashEntryNotFound);
+    assert_eq!(mapped.message, "Failed to restore: Failed to find trash entry: Trash entry not found: 1");
+}
@@ -0,0 +1,132 @@
+use crate::models::{DocMetadata, DocSortField, DocsNode, SortOrder};
+use crate::utils::front_matter::*;
+
+const DOC: &str = "---\ntitle: Setup guide\nstatus: draft\nowner: platform\ntags: [onboarding, ci]\nupdated: 2024-05-01\nreviewers: 2\n---\n# Setup\n\nBody text.\n";
+
+fn file(name: &str, metadata: Option<DocMetadata>) -> DocsNode {
+    DocsNode {
+        path: format!("docs/{}.md", name),
+        name: name.to_string(),
+        is_file: true,
+        is_root: false,
+        metadata,
+        children: Vec::new(),
+    }
+}
+
+fn folder(path: &str, is_root: bool, children: Vec<DocsNode>) -> DocsNode {
+    DocsNode {
+        path: path.to_string(),
+        name: path.rsplit('/').next().unwrap().to_string(),
+        is_file: false,
+        is_root,
+        metadata: None,
+        children,
+    }
+}
+
+fn meta(status: &str, updated: Option<&str>, tags: &[&str]) -> Option<DocMetadata> {
+    Some(DocMetadata {
+        status: Some(status.to_string()),
+        updated: updated.map(str::to_string),
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_parses_front_matter_and_strips_it_from_body() {
+    let (metadata, body) = parse(DOC);
+    let metadata = metadata.unwrap();
+
+    assert_eq!(metadata.title.as_deref(), Some("Setup guide"));
+    assert_eq!(metadata.status.as_deref(), Some("draft"));
+    assert_eq!(metadata.owner.as_deref(), Some("platform"));
+    assert_eq!(metadata.tags, vec!["onboarding", "ci"]);
+    assert_eq!(metadata.updated.as_deref(), Some("2024-05-01"));
+    assert_eq!(metadata.extra["reviewers"], serde_json::json!(2));
+    assert_eq!(body, "# Setup\n\nBody text.\n");
+}
+
+#[test]
+fn test_ignores_text_that_is_not_front_matter() {
+    // No opening delimiter, or one that is never closed
+    assert_eq!(parse("# Title\n---\nx: 1\n---\n"), (None, "# Title\n---\nx: 1\n---\n"));
+    assert_eq!(parse("---\ntitle: x\n# Body\n"), (None, "---\ntitle: x\n# Body\n"));
+
+    // Invalid YAML is still stripped, but yields no metadata
+    assert_eq!(parse("---\n: [\n---\nBody\n"), (None, "Body\n"));
+    // Empty blocks, CRLF and comma-separated tags
+    assert_eq!(parse("---\n---\nBody"), (Some(DocMetadata::default()), "Body"));
+    let (metadata, body) = parse("---\r\ntags: a, b\r\n---\r\nBody\r\n");
+    assert_eq!(metadata.unwrap().tags, vec!["a", "b"]);
+    assert_eq!(body, "Body\r\n");
+}
+
+#[test]
+fn test_round_trips_blocks_and_metadata() {
+    let (block, body) = split(DOC);
+    assert_eq!(with_block(block, body), DOC);
+    // Replacing a body's own block rather than stacking a second one
+    assert_eq!(with_block("---\ntitle: New\n---\n", DOC), "---\ntitle: New\n---\n# Setup\n\nBody text.\n");
+    assert_eq!(with_block("", DOC), "# Setup\n\nBody text.\n");
+
+    let (metadata, _) = parse(DOC);
+    let rendered = render(metadata.as_ref().unwrap()).unwrap();
+    assert_eq!(parse(&format!("{}Body", rendered)).0, metadata);
+    assert_eq!(render(&DocMetadata::default()).unwrap(), "---\n---\n");
+}
+
+#[test]
+fn test_filters_tree_by_metadata() {
+    let tree = folder(
+        ".",
+        false,
+        vec![
+            folder(
+                "docs",
+                true,
+                vec![
+                    folder("docs/old", false, vec![file("legacy", meta("deprecated", None, &[]))]),
+                    file("a", meta("Draft", None, &["ci"])),
+                    file("b", meta("accepted", None, &[])),
+                    file("plain", None),
+                ],
+            ),
+            folder("adr", true, vec![file("c", meta("accepted", None, &[]))]),
+        ],
+    );
+
+    let filter = DocFilter { status: Some("draft".to_string()), ..Default::default() };
+    let filtered = filter_tree(tree.clone(), &filter).unwrap();
+    let docs = &filtered.children[0];
+    assert_eq!(docs.children.iter().map(|n| n.name.as_str()).collect::<Vec<_>>(), vec!["a"]);
+    // Roots stay even when nothing in them matches
+    assert!(filtered.children[1].is_root && filtered.children[1].children.is_empty());
+
+    let filter = DocFilter { tag: Some("CI".to_string()), ..Default::default() };
+    assert_eq!(filter_tree(tree.clone(), &filter).unwrap().children[0].children.len(), 1);
+    assert_eq!(filter_tree(tree.clone(), &DocFilter::default()).unwrap().children[0].children.len(), 4);
+}
+
+#[test]
+fn test_sorts_files_by_metadata_with_missing_values_last() {
+    let mut tree = folder(
+        "docs",
+        true,
+        vec![
+            file("a", meta("x", Some("2024-01-01"), &[])),
+            file("b", None),
+            folder("docs/sub", false, vec![]),
+            file("c", meta("x", Some("2024-03-01"), &[])),
+        ],
+    );
+
+    sort_tree(&mut tree, DocSortField::Updated, SortOrder::Desc);
+    let names: Vec<&str> = tree.children.iter().map(|n| n.name.as_str()).collect();
+    assert_eq!(names, vec!["sub", "c", "a", "b"]);
+
+    sort_tree(&mut tree, DocSortField::Updated, SortOrder::Asc);
+    let names: Vec<&str> = tree.children.iter().map(|n| n.name.as_str()).collect();
+    assert_eq!(names, vec!["sub", "a", "c", "b"]);
+}
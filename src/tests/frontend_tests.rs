@@ -0,0 +1,144 @@
+use axum::body::to_bytes;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use std::fs;
+use std::sync::Arc;
+
+use crate::server::auth::is_allowed_host;
+use crate::server::frontend::{serve_asset, serve_index, Frontend};
+use crate::server::AppState;
+
+async fn index_for(host: Option<&str>) -> (StatusCode, String) {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "<html><head></head></html>").unwrap();
+    let mut state = AppState::new(".".into(), "secret-token".into(), Frontend::Dir(dir.path().to_path_buf()));
+    state.allowed_hosts.push("atlas.lan".into());
+
+    let mut headers = HeaderMap::new();
+    if let Some(host) = host {
+        headers.insert(header::HOST, host.parse().unwrap());
+    }
+    let response = match serve_index(State(Arc::new(state)), headers).await {
+        Ok(page) => page.into_response(),
+        Err(e) => e.into_response(),
+    };
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8_lossy(&body).to_string())
+}
+
+#[tokio::test]
+async fn test_token_only_served_to_known_hosts() {
+    for host in ["localhost:3999", "127.0.0.1:3999", "[::1]:3999", "atlas.lan:3999", "LOCALHOST"] {
+        let (status, body) = index_for(Some(host)).await;
+        assert_eq!(status, StatusCode::OK, "{}", host);
+        assert!(body.contains("secret-token"), "{}", host);
+    }
+
+    // A rebound DNS name, or another name for this machine, gets no token
+    for host in [Some("evil.example:3999"), Some("192.168.1.20:3999"), Some("localhost.evil.example"), None] {
+        let (status, body) = index_for(host).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{:?}", host);
+        assert!(!body.contains("secret-token"), "{:?}", host);
+    }
+}
+
+#[test]
+fn test_allowed_host_ignores_port() {
+    let allowed = vec!["localhost".to_string(), "[::1]".to_string()];
+    assert!(is_allowed_host(Some("localhost"), &allowed));
+    assert!(is_allowed_host(Some("[::1]:80"), &allowed));
+    assert!(!is_allowed_host(Some("[::2]:80"), &allowed));
+    assert!(!is_allowed_host(Some(""), &allowed));
+}
+
+/// A built frontend on disk: the shell page, hashed bundles under `assets/` and an unhashed icon
+fn dist() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("assets")).unwrap();
+    fs::write(dir.path().join("index.html"), "<html><head></head><body>app</body></html>").unwrap();
+    fs::write(dir.path().join("assets/index-3f2a1c.js"), "console.log(1)").unwrap();
+    fs::write(dir.path().join("assets/index-9b8d7e.css"), "body{}").unwrap();
+    fs::write(dir.path().join("favicon.svg"), "<svg/>").unwrap();
+    dir
+}
+
+async fn asset(dist: &tempfile::TempDir, path: &str, if_none_match: Option<&str>) -> Response {
+    let state = AppState::new(".".into(), "secret-token".into(), Frontend::Dir(dist.path().to_path_buf()));
+    let mut headers = HeaderMap::new();
+    headers.insert(header::HOST, "localhost:3999".parse().unwrap());
+    if let Some(tag) = if_none_match {
+        headers.insert(header::IF_NONE_MATCH, tag.parse().unwrap());
+    }
+    match serve_asset(State(Arc::new(state)), path.parse::<Uri>().unwrap(), headers).await {
+        Ok(response) => response,
+        Err(e) => e.into_response(),
+    }
+}
+
+fn header_of(response: &Response, name: header::HeaderName) -> &str {
+    response.headers().get(name).and_then(|v| v.to_str().ok()).unwrap_or_default()
+}
+
+#[tokio::test]
+async fn test_assets_have_their_mime_types() {
+    let dist = dist();
+    for (path, mime) in [
+        ("/assets/index-3f2a1c.js", "text/javascript"),
+        ("/assets/index-9b8d7e.css", "text/css"),
+        ("/favicon.svg", "image/svg+xml"),
+    ] {
+        let response = asset(&dist, path, None).await;
+        assert_eq!(response.status(), StatusCode::OK, "{}", path);
+        assert_eq!(header_of(&response, header::CONTENT_TYPE), mime, "{}", path);
+    }
+}
+
+#[tokio::test]
+async fn test_only_hashed_assets_are_immutable() {
+    let dist = dist();
+    let bundle = asset(&dist, "/assets/index-3f2a1c.js", None).await;
+    assert_eq!(header_of(&bundle, header::CACHE_CONTROL), "public, max-age=31536000, immutable");
+
+    let icon = asset(&dist, "/favicon.svg", None).await;
+    assert_eq!(header_of(&icon, header::CACHE_CONTROL), "no-cache");
+}
+
+#[tokio::test]
+async fn test_matching_etag_is_not_modified() {
+    let dist = dist();
+    let first = asset(&dist, "/favicon.svg", None).await;
+    let etag = header_of(&first, header::ETAG).to_string();
+    assert!(etag.starts_with('"'), "{}", etag);
+
+    for tag in [etag.clone(), format!("W/{}", etag), format!("\"stale\", {}", etag)] {
+        let response = asset(&dist, "/favicon.svg", Some(&tag)).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED, "{}", tag);
+        assert_eq!(header_of(&response, header::ETAG), etag);
+        assert!(to_bytes(response.into_body(), usize::MAX).await.unwrap().is_empty());
+    }
+
+    fs::write(dist.path().join("favicon.svg"), "<svg></svg>").unwrap();
+    let changed = asset(&dist, "/favicon.svg", Some(&etag)).await;
+    assert_eq!(changed.status(), StatusCode::OK);
+    assert_ne!(header_of(&changed, header::ETAG), etag);
+}
+
+#[tokio::test]
+async fn test_app_routes_fall_back_to_index() {
+    let dist = dist();
+    for path in ["/", "/plans", "/docs/adr/0001-use-rust.md/history"] {
+        let response = asset(&dist, path, None).await;
+        assert_eq!(response.status(), StatusCode::OK, "{}", path);
+        assert_eq!(header_of(&response, header::CACHE_CONTROL), "no-store", "{}", path);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("secret-token"), "{}", path);
+    }
+
+    // Missing files, above all stale bundles, must not get the page in their place
+    for path in ["/assets/index-000000.js", "/assets/chunk", "/missing.png", "/api/nothing"] {
+        let response = asset(&dist, path, None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{}", path);
+    }
+}
@@ -0,0 +1,214 @@
+use axum::extract::{Path as AxumPath, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Json};
+use std::fs;
+use std::sync::Arc;
+
+use crate::models::{CreateAdrRequest, FileKind, MemoryScope, ResolveRequest, ResolveStrategy, SaveFileRequest};
+use crate::server::frontend::Frontend;
+use crate::server::handlers::{
+    create_adr, create_memory_file, get_memory_files, resolve_conflict, search, update_memory_file, MemoryListQuery,
+    SearchQuery,
+};
+use crate::server::AppState;
+use crate::utils::hash::{calculate_hash, to_etag};
+
+fn state(root: &std::path::Path) -> Arc<AppState> {
+    Arc::new(AppState::new(root.to_string_lossy().to_string(), "token".into(), Frontend::Embedded))
+}
+
+#[tokio::test]
+async fn test_list_includes_local_memory_files() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("api")).unwrap();
+    fs::write(dir.path().join("CLAUDE.md"), "shared\n").unwrap();
+    fs::write(dir.path().join("CLAUDE.local.md"), "mine\n").unwrap();
+    fs::write(dir.path().join("api/CLAUDE.md"), "api\n").unwrap();
+    fs::write(dir.path().join("api/NOTES.md"), "notes\n").unwrap();
+
+    let Json(files) =
+        get_memory_files(State(state(dir.path())), Query(MemoryListQuery { include: None })).await.unwrap();
+
+    let mut listed: Vec<(String, MemoryScope)> = files.into_iter().map(|f| (f.path, f.scope)).collect();
+    listed.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        listed,
+        vec![
+            ("CLAUDE.local.md".to_string(), MemoryScope::Local),
+            ("CLAUDE.md".to_string(), MemoryScope::Project),
+            ("api/CLAUDE.md".to_string(), MemoryScope::Directory),
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_saves_of_one_version() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("CLAUDE.md"), "v0\n").unwrap();
+    let state = state(dir.path());
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::IF_MATCH, to_etag(&calculate_hash("v0\n")).parse().unwrap());
+
+    let saves: Vec<_> = (1..=8)
+        .map(|i| {
+            let body = SaveFileRequest {
+                content: Some(format!("v{}\n", i)),
+                content_html: None,
+                base_hash: None,
+                metadata: None,
+            };
+            tokio::spawn(update_memory_file(
+                State(state.clone()),
+                AxumPath("CLAUDE.md".to_string()),
+                headers.clone(),
+                Json(body),
+            ))
+        })
+        .collect();
+
+    let mut statuses = Vec::new();
+    for save in saves {
+        statuses.push(match save.await.unwrap() {
+            Ok(saved) => saved.into_response().status(),
+            Err(e) => e.into_response().status(),
+        });
+    }
+
+    // Exactly one edit of v0 wins; the others see it and conflict
+    assert_eq!(statuses.iter().filter(|s| **s == StatusCode::OK).count(), 1, "{:?}", statuses);
+    assert_eq!(statuses.iter().filter(|s| **s == StatusCode::CONFLICT).count(), 7, "{:?}", statuses);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_creates_of_one_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = state(dir.path());
+
+    let creates: Vec<_> = (1..=8)
+        .map(|i| {
+            let body = SaveFileRequest {
+                content: Some(format!("v{}\n", i)),
+                content_html: None,
+                base_hash: None,
+                metadata: None,
+            };
+            tokio::spawn(create_memory_file(State(state.clone()), AxumPath("api/CLAUDE.md".to_string()), Json(body)))
+        })
+        .collect();
+
+    let mut statuses = Vec::new();
+    for create in creates {
+        statuses.push(match create.await.unwrap() {
+            Ok(created) => created.into_response().status(),
+            Err(e) => e.into_response().status(),
+        });
+    }
+
+    assert_eq!(statuses.iter().filter(|s| **s == StatusCode::OK).count(), 1, "{:?}", statuses);
+    assert_eq!(statuses.iter().filter(|s| **s == StatusCode::CONFLICT).count(), 7, "{:?}", statuses);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_adrs_get_distinct_numbers() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = state(dir.path());
+
+    let creates: Vec<_> = (1..=8)
+        .map(|i| {
+            let body = CreateAdrRequest { title: format!("Decision {}", i), supersedes: Vec::new() };
+            tokio::spawn(create_adr(State(state.clone()), Json(body)))
+        })
+        .collect();
+
+    let mut numbers = Vec::new();
+    for create in creates {
+        let Json(created) = create.await.unwrap().unwrap();
+        numbers.push(created.number);
+    }
+    numbers.sort();
+
+    assert_eq!(numbers, (1..=8).collect::<Vec<_>>());
+    let files = fs::read_dir(dir.path().join("docs/adr")).unwrap().count();
+    assert_eq!(files, 9, "eight ADRs and the index");
+}
+
+fn keep_mine(disk_hash: Option<String>) -> ResolveRequest {
+    ResolveRequest {
+        kind: FileKind::Memory,
+        path: "CLAUDE.md".into(),
+        strategy: ResolveStrategy::KeepMine,
+        base: "v0\n".into(),
+        mine: "mine\n".into(),
+        disk_hash,
+        resolutions: Vec::new(),
+    }
+}
+
+async fn resolve(state: &Arc<AppState>, headers: HeaderMap, request: ResolveRequest) -> StatusCode {
+    match resolve_conflict(State(state.clone()), headers, Json(request)).await {
+        Ok(saved) => saved.into_response().status(),
+        Err(e) => e.into_response().status(),
+    }
+}
+
+#[tokio::test]
+async fn test_resolve_requires_the_disk_version() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("CLAUDE.md");
+    fs::write(&file, "theirs\n").unwrap();
+    let state = state(dir.path());
+
+    assert_eq!(resolve(&state, HeaderMap::new(), keep_mine(None)).await, StatusCode::PRECONDITION_REQUIRED);
+
+    // Edited again after the conflict was shown
+    let stale = calculate_hash("theirs\n");
+    fs::write(&file, "newer\n").unwrap();
+    let mut headers = HeaderMap::new();
+    headers.insert(header::IF_MATCH, to_etag(&stale).parse().unwrap());
+    assert_eq!(resolve(&state, headers, keep_mine(None)).await, StatusCode::CONFLICT);
+    assert_eq!(fs::read_to_string(&file).unwrap(), "newer\n");
+
+    let current = calculate_hash("newer\n");
+    assert_eq!(resolve(&state, HeaderMap::new(), keep_mine(Some(current))).await, StatusCode::OK);
+    assert_eq!(fs::read_to_string(&file).unwrap(), "mine\n");
+}
+
+async fn search_for(state: &Arc<AppState>, q: &str) -> usize {
+    let query = SearchQuery { q: q.into(), kind: None, scope: None, dir: None, limit: None };
+    let Json(response) = search(State(state.clone()), Query(query)).await.unwrap();
+    response.total
+}
+
+#[tokio::test]
+async fn test_search_sees_saves_while_searches_run() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("CLAUDE.md"), "alpha\n").unwrap();
+    let state = state(dir.path());
+
+    let counts = search_concurrently(&state).await;
+    assert_eq!(counts, vec![1; 4]);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::IF_MATCH, to_etag(&calculate_hash("alpha\n")).parse().unwrap());
+    let body = SaveFileRequest { content: Some("bravo\n".into()), content_html: None, base_hash: None, metadata: None };
+    update_memory_file(State(state.clone()), AxumPath("CLAUDE.md".into()), headers, Json(body)).await.unwrap();
+
+    assert_eq!(search_for(&state, "alpha").await, 0);
+    assert_eq!(search_for(&state, "bravo").await, 1);
+}
+
+/// Several searches at once share one refresh of the index
+async fn search_concurrently(state: &Arc<AppState>) -> Vec<usize> {
+    let searches: Vec<_> = (0..4)
+        .map(|_| {
+            let state = state.clone();
+            tokio::spawn(async move { search_for(&state, "alpha").await })
+        })
+        .collect();
+    let mut counts = Vec::new();
+    for search in searches {
+        counts.push(search.await.unwrap());
+    }
+    counts
+}
@@ -0,0 +1,22 @@
+use crate::utils::hash::*;
+
+#[test]
+fn test_hash_is_stable() {
+    let a = calculate_hash("# Memory\n\nSome rules.");
+    let b = calculate_hash("# Memory\n\nSome rules.");
+    assert_eq!(a, b);
+    assert_eq!(a.len(), 64);
+}
+
+#[test]
+fn test_hash_changes_with_content() {
+    assert_ne!(calculate_hash("line\n"), calculate_hash("line\r\n"));
+}
+
+#[test]
+fn test_etag_round_trip() {
+    let hash = calculate_hash("content");
+    assert_eq!(from_etag(&to_etag(&hash)), hash);
+    assert_eq!(from_etag(&format!("W/\"{}\"", hash)), hash);
+    assert_eq!(from_etag(&hash), hash);
+}
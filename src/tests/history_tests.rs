@@ -0,0 +1,99 @@
+use std::fs;
+
+use crate::services::history::*;
+
+fn project() -> (tempfile::TempDir, std::path::PathBuf) {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("CLAUDE.md");
+    fs::write(&file, "first\n").unwrap();
+    (dir, file)
+}
+
+#[test]
+fn test_snapshot_list_and_read() {
+    let (dir, file) = project();
+
+    let revision = snapshot(dir.path(), &file, "update", "api").unwrap().unwrap();
+    assert_eq!(revision.path, "CLAUDE.md");
+    assert_eq!(revision.operation, "update");
+    assert_eq!(revision.size, 6);
+
+    let listed = list_revisions(dir.path(), Some("./CLAUDE.md")).unwrap();
+    assert_eq!(listed.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(), vec![revision.id.as_str()]);
+    assert!(list_revisions(dir.path(), Some("docs/CLAUDE.md")).unwrap().is_empty());
+
+    let stored = read_revision(dir.path(), &revision.id).unwrap();
+    assert_eq!(stored.content, "first\n");
+
+    let missing = read_revision(dir.path(), "nope").unwrap_err();
+    assert_eq!(missing.downcast_ref::<HistoryError>(), Some(&HistoryError::RevisionNotFound("nope".into())));
+}
+
+#[test]
+fn test_nothing_to_snapshot_before_create() {
+    let dir = tempfile::tempdir().unwrap();
+    let revision = snapshot(dir.path(), &dir.path().join("CLAUDE.md"), "create", "api").unwrap();
+    assert!(revision.is_none());
+}
+
+#[test]
+fn test_same_content_twice_gets_two_revisions() {
+    let (dir, file) = project();
+
+    // A revert and re-save within one second snapshots identical content again
+    let first = snapshot(dir.path(), &file, "update", "api").unwrap().unwrap();
+    let second = snapshot(dir.path(), &file, "update", "api").unwrap().unwrap();
+    assert_ne!(first.id, second.id);
+
+    let listed = list_revisions(dir.path(), None).unwrap();
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed[0].id, second.id);
+    assert_eq!(read_revision(dir.path(), &first.id).unwrap().revision.id, first.id);
+    assert_eq!(read_revision(dir.path(), &second.id).unwrap().revision.id, second.id);
+}
+
+#[test]
+fn test_restore_snapshots_what_it_replaces() {
+    let (dir, file) = project();
+    let original = snapshot(dir.path(), &file, "update", "api").unwrap().unwrap();
+    fs::write(&file, "second\n").unwrap();
+
+    let restored = restore_revision(dir.path(), &original.id, "cli").unwrap();
+    assert_eq!(restored.content, "first\n");
+    assert_eq!(fs::read_to_string(&file).unwrap(), "first\n");
+
+    let listed = list_revisions(dir.path(), Some("CLAUDE.md")).unwrap();
+    assert_eq!(listed.len(), 2);
+    let replaced = listed.iter().find(|r| r.operation == "restore").unwrap();
+    assert_eq!(replaced.source, "cli");
+    assert_eq!(read_revision(dir.path(), &replaced.id).unwrap().content, "second\n");
+
+    let diff = diff_revisions(dir.path(), &replaced.id, "current").unwrap();
+    assert!(diff.unified.contains("-second\n+first\n"));
+}
+
+#[test]
+fn test_snapshots_survive_concurrent_prunes() {
+    let (dir, file) = project();
+    let other = dir.path().join("NOTES.md");
+    let root = dir.path().to_path_buf();
+    let policy = crate::models::RetentionPolicy { max_per_file: 1, max_age_days: 90 };
+
+    // Every prune of NOTES.md drops a revision and so collects unreferenced objects
+    let pruner = std::thread::spawn(move || {
+        for i in 0..50 {
+            fs::write(&other, format!("notes {}\n", i)).unwrap();
+            snapshot(&root, &other, "update", "api").unwrap();
+            prune(&root, &policy, Some("NOTES.md")).unwrap();
+        }
+    });
+    for i in 0..50 {
+        fs::write(&file, format!("claude {}\n", i)).unwrap();
+        snapshot(dir.path(), &file, "update", "api").unwrap();
+    }
+    pruner.join().unwrap();
+
+    for revision in list_revisions(dir.path(), Some("CLAUDE.md")).unwrap() {
+        read_revision(dir.path(), &revision.id).unwrap();
+    }
+}
@@ -0,0 +1,140 @@
+use crate::models::{LinkKind, LinkStatus};
+use crate::utils::links::*;
+use std::collections::HashSet;
+
+#[test]
+fn test_extract_links_skips_code_and_external_targets() {
+    let content = "\
+See [setup](guides/setup.md#install) and ![diagram](<img/arch one.png> \"Arch\").
+External [site](https://example.com), [anchor](#top), `[code](not/a/link.md)`.
+[ref]: ../shared/notes.md
+```
+[fenced](ignored.md)
+```
+@docs/style.md and mail me@example.com or `@skipped.md`
+";
+
+    let links = extract_links(content, true);
+    let targets: Vec<(&str, LinkKind, usize)> = links.iter().map(|l| (l.target.as_str(), l.kind, l.line)).collect();
+
+    assert_eq!(
+        targets,
+        vec![
+            ("guides/setup.md#install", LinkKind::Inline, 1),
+            ("img/arch one.png", LinkKind::Inline, 1),
+            ("../shared/notes.md", LinkKind::Reference, 3),
+            ("docs/style.md", LinkKind::Import, 7),
+        ]
+    );
+
+    assert!(extract_links("@docs/style.md", false).is_empty());
+}
+
+#[test]
+fn test_path_helpers() {
+    assert_eq!(resolve_target("docs/guides/setup.md", "../api.md"), Some("docs/api.md".to_string()));
+    assert_eq!(resolve_target("CLAUDE.md", "./docs/x.md"), Some("docs/x.md".to_string()));
+    assert_eq!(resolve_target("CLAUDE.md", "../outside.md"), None);
+
+    assert_eq!(relative_to("docs/guides/setup.md", "docs/api.md"), "../api.md");
+    assert_eq!(relative_to("CLAUDE.md", "docs/api.md"), "docs/api.md");
+    assert_eq!(relative_to("src/CLAUDE.md", "src/notes.md"), "notes.md");
+
+    assert_eq!(split_fragment("a.md#part"), ("a.md", "#part"));
+    assert_eq!(split_fragment("a.md"), ("a.md", ""));
+}
+
+#[test]
+fn test_move_map_covers_folders() {
+    let mut moves = MoveMap::new();
+    moves.add("docs/guides", "docs/handbook/guides");
+
+    assert_eq!(moves.map("docs/guides"), Some("docs/handbook/guides".to_string()));
+    assert_eq!(moves.map("docs/guides/setup.md"), Some("docs/handbook/guides/setup.md".to_string()));
+    assert_eq!(moves.map("docs/guidesx.md"), None);
+}
+
+#[test]
+fn test_rewrite_links_to_moved_target() {
+    let existing: HashSet<&str> = ["docs/guides/setup.md", "docs/api.md"].into();
+    let mut moves = MoveMap::new();
+    moves.add("docs/guides/setup.md", "docs/start/install.md");
+
+    let content = "Read [setup](docs/guides/setup.md#linux) and [api](docs/api.md).\r\n@docs/guides/setup.md\r\n";
+    let (rewritten, changes) = rewrite_links(content, "CLAUDE.md", "CLAUDE.md", &moves, |p| existing.contains(p));
+
+    assert_eq!(
+        rewritten,
+        "Read [setup](docs/start/install.md#linux) and [api](docs/api.md).\r\n@docs/start/install.md\r\n"
+    );
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0].line, 1);
+    assert_eq!(changes[0].before, "docs/guides/setup.md#linux");
+    assert_eq!(changes[1].after, "docs/start/install.md");
+}
+
+#[test]
+fn test_rewrite_links_inside_moved_file() {
+    let existing: HashSet<&str> = ["docs/api.md", "docs/guides/setup.md"].into();
+    let mut moves = MoveMap::new();
+    moves.add("docs/guides", "docs/handbook/guides");
+
+    let content = "[api](../api.md) [sibling](./setup.md) [missing](../gone.md)\n";
+    let (rewritten, changes) =
+        rewrite_links(content, "docs/guides/intro.md", "docs/handbook/guides/intro.md", &moves, |p| {
+            existing.contains(p)
+        });
+
+    // Sibling stays put relative to the file since both moved; the broken link is left alone
+    assert_eq!(rewritten, "[api](../../api.md) [sibling](./setup.md) [missing](../gone.md)\n");
+    assert_eq!(changes.len(), 1);
+}
+
+#[test]
+fn test_heading_anchors_follow_github_slugs() {
+    assert_eq!(slugify("Getting Started!"), "getting-started");
+    assert_eq!(slugify("The `run_all` API (v2)"), "the-run_all-api-v2");
+
+    let anchors = heading_anchors("# Intro\n## Set-up & Install\n```\n# not a heading\n```\n## Intro\n");
+    let expected: HashSet<String> = ["intro", "set-up--install", "intro-1"].iter().map(|s| s.to_string()).collect();
+    assert_eq!(anchors, expected);
+}
+
+#[test]
+fn test_check_links_reports_missing_files_and_anchors() {
+    let content = "\
+[ok](setup.md#install) [gone](old.md) [bad anchor](setup.md#nope)
+[self](#usage) [up](../../../etc/passwd) [dir](../src/)
+ask @alice, see @../CLAUDE.md
+## Usage
+";
+    let files = ["docs/setup.md", "src", "CLAUDE.md"];
+    let links = check_links(
+        "docs/CLAUDE.md",
+        content,
+        |path| files.contains(&path) || path == "docs/CLAUDE.md",
+        |path| match path {
+            "docs/setup.md" => Some(heading_anchors("# Install\n")),
+            "docs/CLAUDE.md" => Some(heading_anchors(content)),
+            _ => None,
+        },
+    );
+
+    let summary: Vec<(&str, Option<&str>, LinkStatus)> =
+        links.iter().map(|l| (l.target.as_str(), l.resolved.as_deref(), l.status)).collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("setup.md#install", Some("docs/setup.md"), LinkStatus::Ok),
+            ("old.md", Some("docs/old.md"), LinkStatus::MissingFile),
+            ("setup.md#nope", Some("docs/setup.md"), LinkStatus::MissingAnchor),
+            ("#usage", Some("docs/CLAUDE.md"), LinkStatus::Ok),
+            ("../../../etc/passwd", None, LinkStatus::OutsideProject),
+            ("../src/", Some("src"), LinkStatus::Ok),
+            // `@alice` is a mention, not an import
+            ("../CLAUDE.md", Some("CLAUDE.md"), LinkStatus::Ok),
+        ]
+    );
+    assert_eq!(links[2].anchor.as_deref(), Some("nope"));
+    assert_eq!(links[6].kind, LinkKind::Import);
+}
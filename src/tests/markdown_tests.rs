@@ -5,7 +5,7 @@ use std::fs;
 fn test_basic_markdown() {
     let md = "# Hello\n\nThis is **bold** text.";
     let html = markdown_to_html(md);
-    assert!(html.contains("<h1>Hello</h1>"));
+    assert!(html.contains("<h1 id=\"user-content-hello\">Hello</h1>"));
     assert!(html.contains("<strong>bold</strong>"));
 }
 
@@ -14,7 +14,7 @@ fn test_round_trip_simple() {
     let original = "# Header\n\nThis is **bold** and *italic* text.\n\n- List item 1\n- List item 2";
     let html = markdown_to_html(original);
     let converted = html_to_markdown(&html).unwrap();
-    
+
     // Check key elements are preserved
     assert!(converted.contains("Header"));
     assert!(converted.contains("**bold**"));
@@ -28,7 +28,7 @@ fn test_round_trip_code_blocks() {
     let original = "```rust\nfn main() {\n    println!(\"Hello\");\n}\n```";
     let html = markdown_to_html(original);
     let converted = html_to_markdown(&html).unwrap();
-    
+
     // Check code block is preserved
     assert!(converted.contains("fn main()"));
     assert!(converted.contains("println!"));
@@ -39,7 +39,7 @@ fn test_round_trip_lists() {
     let original = "## Lists\n\n- Item 1\n- Item 2\n  - Nested\n\n1. First\n2. Second";
     let html = markdown_to_html(original);
     let converted = html_to_markdown(&html).unwrap();
-    
+
     assert!(converted.contains("Item 1"));
     assert!(converted.contains("Item 2"));
     assert!(converted.contains("Nested"));
@@ -52,7 +52,7 @@ fn test_round_trip_links() {
     let original = "[Link text](https://example.com)";
     let html = markdown_to_html(original);
     let converted = html_to_markdown(&html).unwrap();
-    
+
     assert!(converted.contains("Link text"));
     assert!(converted.contains("https://example.com"));
 }
@@ -62,7 +62,7 @@ fn test_round_trip_simple_table() {
     let original = "| Header 1 | Header 2 |\n|----------|----------|\n| Cell 1   | Cell 2   |";
     let html = markdown_to_html(original);
     let converted = html_to_markdown(&html).unwrap();
-    
+
     assert!(converted.contains("Header 1"));
     assert!(converted.contains("Header 2"));
     assert!(converted.contains("Cell 1"));
@@ -77,26 +77,26 @@ fn test_round_trip_complex_table() {
 | Dashboard | 🚧 In Progress | Medium | Needs UI polish |
 | API Integration | ❌ Not Started | Low | Waiting for specs |
 | **Bold text** | *Italic* | `code` | [Link](https://example.com) |"#;
-    
+
     let html = markdown_to_html(original);
     let converted = html_to_markdown(&html).unwrap();
-    
+
     // Check headers preserved
     assert!(converted.contains("Feature"));
     assert!(converted.contains("Status"));
     assert!(converted.contains("Priority"));
     assert!(converted.contains("Notes"));
-    
+
     // Check content preserved
     assert!(converted.contains("Authentication"));
     assert!(converted.contains("Dashboard"));
     assert!(converted.contains("API Integration"));
-    
+
     // Check emojis preserved
     assert!(converted.contains("✅") || converted.contains("Done"));
     assert!(converted.contains("🚧") || converted.contains("In Progress"));
     assert!(converted.contains("❌") || converted.contains("Not Started"));
-    
+
     // Check formatting preserved in cells
     assert!(converted.contains("Bold text") || converted.contains("**Bold text**"));
     assert!(converted.contains("Italic") || converted.contains("*Italic*"));
@@ -111,10 +111,10 @@ fn test_round_trip_table_alignment() {
 |:-----|:------:|------:|
 | L1   | C1     | R1    |
 | L2   | C2     | R2    |"#;
-    
+
     let html = markdown_to_html(original);
     let converted = html_to_markdown(&html).unwrap();
-    
+
     // Check all cells are preserved
     assert!(converted.contains("Left"));
     assert!(converted.contains("Center"));
@@ -134,15 +134,15 @@ fn test_round_trip_table_with_empty_cells() {
 | A    |      | C    |
 |      | B    |      |
 | X    | Y    | Z    |"#;
-    
+
     let html = markdown_to_html(original);
     let converted = html_to_markdown(&html).unwrap();
-    
+
     // Check headers
     assert!(converted.contains("Col1"));
     assert!(converted.contains("Col2"));
     assert!(converted.contains("Col3"));
-    
+
     // Check non-empty cells
     assert!(converted.contains("A"));
     assert!(converted.contains("B"));
@@ -159,10 +159,10 @@ fn test_round_trip_table_with_pipes_in_cells() {
 |---------|-------------|
 | `a \| b` | Pipe example |
 | `grep \| wc` | Count lines |"#;
-    
+
     let html = markdown_to_html(original);
     let converted = html_to_markdown(&html).unwrap();
-    
+
     assert!(converted.contains("Command"));
     assert!(converted.contains("Description"));
     assert!(converted.contains("Pipe example"));
@@ -172,7 +172,7 @@ fn test_round_trip_table_with_pipes_in_cells() {
     assert!(converted.contains("grep") && converted.contains("wc"));
 }
 
-#[test] 
+#[test]
 fn test_table_before_and_after_content() {
     let original = r#"# Document Title
 
@@ -185,10 +185,10 @@ Some text before the table.
 Some text after the table.
 
 ## Another Section"#;
-    
+
     let html = markdown_to_html(original);
     let converted = html_to_markdown(&html).unwrap();
-    
+
     // Check document structure is preserved
     assert!(converted.contains("Document Title"));
     assert!(converted.contains("Some text before the table"));
@@ -206,33 +206,32 @@ fn test_comprehensive_markdown_file() {
     if let Ok(original) = fs::read_to_string("src/test_markdown.md") {
         let html = markdown_to_html(&original);
         let converted = html_to_markdown(&html).unwrap();
-        
+
         // Check that key content is preserved (formatting may differ)
         assert!(converted.contains("Comprehensive Markdown Test Document"));
         assert!(converted.contains("**bold text**"));
-        assert!(converted.contains("let x = 42;"));  // Code content preserved
-        assert!(converted.contains("def hello():"));  // Python code preserved
-        
+        assert!(converted.contains("let x = 42;")); // Code content preserved
+        assert!(converted.contains("def hello():")); // Python code preserved
+
         // Check headers are preserved (may be Setext style instead of ATX)
         assert!(converted.contains("Headers") || converted.contains("## Headers"));
         assert!(converted.contains("Text Formatting"));
         assert!(converted.contains("Code Blocks"));
-        
+
         // Check lists preserved (may use * instead of -)
         assert!(converted.contains("First item"));
         assert!(converted.contains("Second item"));
-        
+
         // Check links preserved
         assert!(converted.contains("https://example.com"));
-        
+
         // Print for manual inspection
         println!("Original length: {}", original.len());
         println!("Converted length: {}", converted.len());
-        
+
         // Check that we didn't lose too much content
         // Allow for some variation due to formatting differences
-        assert!(converted.len() > original.len() / 2, 
-                "Too much content lost in conversion");
+        assert!(converted.len() > original.len() / 2, "Too much content lost in conversion");
     }
 }
 
@@ -241,7 +240,134 @@ fn test_round_trip_blockquotes() {
     let original = "> This is a quote\n> with multiple lines";
     let html = markdown_to_html(original);
     let converted = html_to_markdown(&html).unwrap();
-    
+
     assert!(converted.contains("This is a quote"));
     assert!(converted.contains("with multiple lines"));
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_heading_anchors_and_toc() {
+    let rendered = render("# Setup\n\n## Build `cargo`\n\ntext\n\n## Setup\n");
+
+    assert!(rendered.html.contains("<h1 id=\"user-content-setup\">Setup</h1>"));
+    assert!(rendered.html.contains("<h2 id=\"user-content-build-cargo\">Build <code>cargo</code></h2>"));
+    assert!(rendered.html.contains("<h2 id=\"user-content-setup-1\">Setup</h2>"));
+
+    let toc: Vec<(u8, &str, &str)> =
+        rendered.toc.iter().map(|e| (e.level, e.text.as_str(), e.anchor.as_str())).collect();
+    assert_eq!(
+        toc,
+        vec![
+            (1, "Setup", "user-content-setup"),
+            (2, "Build cargo", "user-content-build-cargo"),
+            (2, "Setup", "user-content-setup-1"),
+        ]
+    );
+}
+
+#[test]
+fn test_code_blocks_are_highlighted() {
+    let html = render("```rust\nfn main() {}\n```\n\n```nosuchlang\n<b>x</b>\n```\n").html;
+
+    assert!(html.contains("<pre><code class=\"language-rust\">"));
+    assert!(html.contains("class=\"hl-"));
+    assert!(html.contains("<pre><code class=\"language-nosuchlang\">&lt;b&gt;x&lt;/b&gt;\n</code></pre>"));
+    assert!(highlight_css().contains(".hl-"));
+
+    // Highlighting is markup only; the code still comes back as text
+    let converted = html_to_markdown(&html).unwrap();
+    assert!(converted.contains("fn main() {}"));
+}
+
+#[test]
+fn test_mermaid_passthrough() {
+    let html = render("```mermaid\ngraph TD\n  A --> B\n```\n").html;
+
+    assert!(
+        html.contains("<pre class=\"mermaid\"><code class=\"language-mermaid\">graph TD\n  A --&gt; B\n</code></pre>")
+    );
+    assert!(!html.contains("hl-"));
+}
+
+#[test]
+fn test_sanitizes_xss_vectors() {
+    let vectors = [
+        "<script>alert(1)</script>",
+        "<img src=x onerror=alert(1)>",
+        "<svg onload=alert(1)></svg>",
+        "<iframe src=\"https://evil.example\"></iframe>",
+        "<a href=\"javascript:alert(1)\">click</a>",
+        "<a href=\"JaVaScRiPt:alert(1)\">click</a>",
+        "<a href=\"&#106;avascript:alert(1)\">click</a>",
+        "[click](javascript:alert(1))",
+        "<a href=\"data:text/html,<script>alert(1)</script>\">click</a>",
+        "<div style=\"background:url(javascript:alert(1))\" onclick=\"alert(1)\">x</div>",
+        "<style>body { display: none }</style>",
+        "<form action=\"https://evil.example\"><input type=\"text\" name=\"token\"></form>",
+        "<object data=\"evil.swf\"></object><embed src=\"evil.swf\">",
+        "<meta http-equiv=\"refresh\" content=\"0;url=https://evil.example\">",
+        "```\"><script>alert(1)</script>\nx\n```",
+        "# <img src=x onerror=alert(1)> Title",
+    ];
+
+    for vector in vectors {
+        let html = render(vector).html.to_lowercase();
+        for banned in [
+            "<script",
+            "onerror",
+            "onload",
+            "onclick",
+            "javascript:",
+            "data:",
+            "<iframe",
+            "<style",
+            "<form",
+            "<object",
+            "<embed",
+            "<meta",
+            "<svg",
+            "type=\"text\"",
+        ] {
+            assert!(!html.contains(banned), "{:?} rendered {:?}", vector, html);
+        }
+    }
+}
+
+#[test]
+fn test_sanitizer_keeps_rendered_markup() {
+    let markdown = "# Title\n\n- [x] done\n\n| a | b |\n|:-:|--:|\n| 1 | 2 |\n\nNote[^1]\n\n[^1]: Footnote\n\n\
+                    ```rust\nlet x = 1;\n```\n\n```mermaid\ngraph TD\n```\n\n[docs](./docs/setup.md#install)";
+    let html = render(markdown).html;
+
+    assert!(html.contains("<h1 id=\"user-content-title\">Title</h1>"));
+    assert!(html.contains("<li><input disabled=\"\" type=\"checkbox\" checked=\"\">"));
+    assert!(html.contains("<th style=\"text-align: center\">a</th>"));
+    assert!(html.contains("<a href=\"#user-content-1\""));
+    assert!(html.contains("<div class=\"footnote-definition\" id=\"user-content-1\">"));
+    assert!(html.contains("<span class=\"hl-"));
+    assert!(html.contains("<pre class=\"mermaid\">"));
+    assert!(html.contains("href=\"./docs/setup.md#install\""));
+}
+
+#[test]
+fn test_ids_cannot_clobber_the_page() {
+    let html = render("<div id=\"root\">x</div>\n\n# App\n\n[top](#app) [abs](#user-content-app)\n").html;
+
+    assert!(html.contains("<div id=\"user-content-root\">x</div>"));
+    assert!(html.contains("<h1 id=\"user-content-app\">App</h1>"));
+    assert_eq!(html.matches("href=\"#user-content-app\"").count(), 2);
+    assert!(!html.contains("id=\"root\"") && !html.contains("id=\"app\""));
+}
+
+#[test]
+fn test_only_rendered_classes_survive() {
+    let html = render("<div class=\"modal mermaid\">x</div>\n\n<span class=\"hidden\">y</span>\n").html;
+    assert!(html.contains("<div class=\"mermaid\">x</div>"), "{}", html);
+    assert!(html.contains("<span>y</span>"), "{}", html);
+    assert!(!html.contains("modal") && !html.contains("hidden"), "{}", html);
+
+    let html = render("Note[^1]\n\n[^1]: Footnote\n\n```rust\nlet x = 1;\n```\n").html;
+    for class in ["footnote-reference", "footnote-definition", "footnote-definition-label", "language-rust", "hl-"] {
+        assert!(html.contains(&format!("class=\"{}", class)), "{} missing from {}", class, html);
+    }
+}
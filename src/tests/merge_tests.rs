@@ -0,0 +1,75 @@
+use crate::models::{HunkChoice, HunkResolution};
+use crate::utils::merge::*;
+
+const BASE: &str = "# Project\n\n## Rules\n- Use tabs\n- Run tests\n\n## Notes\nNothing yet.\n";
+
+#[test]
+fn test_non_overlapping_edits_merge_cleanly() {
+    let mine = BASE.replace("- Use tabs", "- Use spaces");
+    let theirs = BASE.replace("Nothing yet.", "Migrations run on deploy.");
+
+    let result = merge3(BASE, &mine, &theirs);
+    assert!(result.clean);
+    assert!(result.conflicts.is_empty());
+    assert!(result.content.contains("- Use spaces"));
+    assert!(result.content.contains("Migrations run on deploy."));
+}
+
+#[test]
+fn test_identical_edits_merge_cleanly() {
+    let edited = BASE.replace("- Run tests", "- Run tests before pushing");
+
+    let result = merge3(BASE, &edited, &edited);
+    assert!(result.clean);
+    assert_eq!(result.content, edited);
+}
+
+#[test]
+fn test_overlapping_edits_conflict() {
+    let mine = BASE.replace("- Use tabs", "- Use spaces");
+    let theirs = BASE.replace("- Use tabs", "- Use two-space indents");
+
+    let result = merge3(BASE, &mine, &theirs);
+    assert!(!result.clean);
+    assert_eq!(result.conflicts.len(), 1);
+
+    let conflict = &result.conflicts[0];
+    assert_eq!(conflict.base, "- Use tabs\n");
+    assert_eq!(conflict.mine, "- Use spaces\n");
+    assert_eq!(conflict.theirs, "- Use two-space indents\n");
+    assert_eq!(conflict.start_line, 4);
+
+    let lines: Vec<&str> = result.content.lines().collect();
+    assert_eq!(lines[3], "<<<<<<< mine");
+    assert_eq!(lines[4], "- Use spaces");
+    assert_eq!(lines[5], "=======");
+    assert_eq!(lines[6], "- Use two-space indents");
+    assert_eq!(lines[7], ">>>>>>> theirs");
+}
+
+#[test]
+fn test_apply_resolutions() {
+    let mine = BASE.replace("- Use tabs", "- Use spaces");
+    let theirs = BASE.replace("- Use tabs", "- Use two-space indents");
+
+    let unresolved = apply_resolutions(BASE, &mine, &theirs, &[]);
+    assert_eq!(unresolved, Err(vec![0]));
+
+    let take_theirs = [HunkResolution { hunk: 0, take: Some(HunkChoice::Theirs), content: None }];
+    assert_eq!(apply_resolutions(BASE, &mine, &theirs, &take_theirs), Ok(theirs.clone()));
+
+    let custom = [HunkResolution { hunk: 0, take: None, content: Some("- Use rustfmt\n".to_string()) }];
+    let resolved = apply_resolutions(BASE, &mine, &theirs, &custom).unwrap();
+    assert_eq!(resolved, BASE.replace("- Use tabs", "- Use rustfmt"));
+}
+
+#[test]
+fn test_missing_trailing_newline() {
+    let base = "a\nb\nc";
+    let mine = "A\nb\nc";
+    let theirs = "a\nb\nC";
+
+    let result = merge3(base, mine, theirs);
+    assert!(result.clean);
+    assert_eq!(result.content, "A\nb\nC");
+}
@@ -0,0 +1,40 @@
+use crate::utils::metrics::*;
+
+#[test]
+fn test_counters_render_with_labels() {
+    let registry = Registry::default();
+    registry.increment(HTTP_REQUESTS, &[("method", "GET"), ("route", "/api/v1/tree"), ("status", "200")]);
+    registry.increment(HTTP_REQUESTS, &[("method", "GET"), ("route", "/api/v1/tree"), ("status", "200")]);
+    registry.increment(HTTP_REQUESTS, &[("method", "PUT"), ("route", "/api/v1/tree"), ("status", "409")]);
+
+    let text = registry.render();
+    assert!(text.contains("# TYPE cc_atlas_http_requests_total counter"));
+    assert!(text.contains("cc_atlas_http_requests_total{method=\"GET\",route=\"/api/v1/tree\",status=\"200\"} 2"));
+    assert!(text.contains("cc_atlas_http_requests_total{method=\"PUT\",route=\"/api/v1/tree\",status=\"409\"} 1"));
+    assert_eq!(text.matches("# HELP cc_atlas_http_requests_total").count(), 1);
+}
+
+#[test]
+fn test_histogram_buckets_are_cumulative() {
+    let registry = Registry::default();
+    registry.observe(SCAN_DURATION, &[("phase", "build_tree")], 0.003);
+    registry.observe(SCAN_DURATION, &[("phase", "build_tree")], 0.2);
+    registry.observe(SCAN_DURATION, &[("phase", "build_tree")], 30.0);
+
+    let text = registry.render();
+    assert!(text.contains("# TYPE cc_atlas_scan_duration_seconds histogram"));
+    assert!(text.contains("cc_atlas_scan_duration_seconds_bucket{phase=\"build_tree\",le=\"0.001\"} 0"));
+    assert!(text.contains("cc_atlas_scan_duration_seconds_bucket{phase=\"build_tree\",le=\"0.005\"} 1"));
+    assert!(text.contains("cc_atlas_scan_duration_seconds_bucket{phase=\"build_tree\",le=\"0.25\"} 2"));
+    assert!(text.contains("cc_atlas_scan_duration_seconds_bucket{phase=\"build_tree\",le=\"10\"} 2"));
+    assert!(text.contains("cc_atlas_scan_duration_seconds_bucket{phase=\"build_tree\",le=\"+Inf\"} 3"));
+    assert!(text.contains("cc_atlas_scan_duration_seconds_count{phase=\"build_tree\"} 3"));
+}
+
+#[test]
+fn test_label_values_are_escaped() {
+    let registry = Registry::default();
+    registry.increment(CACHE_LOOKUPS, &[("cache", "a\"b\\c\nd")]);
+
+    assert!(registry.render().contains("cache=\"a\\\"b\\\\c\\nd\""));
+}
@@ -1,2 +1,50 @@
 #[cfg(test)]
-mod markdown_tests;
\ No newline at end of file
+mod adr_tests;
+#[cfg(test)]
+mod analyzer_tests;
+#[cfg(test)]
+mod batch_tests;
+#[cfg(test)]
+mod daemon_tests;
+#[cfg(test)]
+mod docs_tests;
+#[cfg(test)]
+mod error_tests;
+#[cfg(test)]
+mod front_matter_tests;
+#[cfg(test)]
+mod frontend_tests;
+#[cfg(test)]
+mod handlers_tests;
+#[cfg(test)]
+mod hash_tests;
+#[cfg(test)]
+mod history_tests;
+#[cfg(test)]
+mod links_tests;
+#[cfg(test)]
+mod markdown_tests;
+#[cfg(test)]
+mod merge_tests;
+#[cfg(test)]
+mod metrics_tests;
+#[cfg(test)]
+mod mover_tests;
+#[cfg(test)]
+mod paths_tests;
+#[cfg(test)]
+mod plans_tests;
+#[cfg(test)]
+mod routes_tests;
+#[cfg(test)]
+mod sandbox_tests;
+#[cfg(test)]
+mod search_tests;
+#[cfg(all(test, unix))]
+mod socket_tests;
+#[cfg(test)]
+mod time_tests;
+#[cfg(test)]
+mod trash_tests;
+#[cfg(test)]
+mod writer_tests;
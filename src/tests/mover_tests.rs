@@ -0,0 +1,102 @@
+use std::fs;
+use std::path::Path;
+
+use crate::services::history;
+use crate::services::mover::move_path;
+
+fn project() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("docs/guides")).unwrap();
+    fs::write(dir.path().join("CLAUDE.md"), "See [setup](docs/guides/setup.md).\n@docs/guides/setup.md\n").unwrap();
+    fs::write(dir.path().join("docs/guides/setup.md"), "# Setup\n\nBack to [api](../api.md).\n").unwrap();
+    fs::write(dir.path().join("docs/api.md"), "# API\n\n[setup](guides/setup.md)\n").unwrap();
+    dir
+}
+
+fn read(root: &Path, path: &str) -> String {
+    fs::read_to_string(root.join(path)).unwrap()
+}
+
+#[test]
+fn test_rename_file_rewrites_links() {
+    let dir = project();
+
+    let result = move_path(dir.path(), "docs/guides/setup.md", "docs/install.md", false, "api").unwrap();
+
+    assert_eq!(result.moved.len(), 1);
+    assert_eq!(result.moved[0].to, "docs/install.md");
+    assert!(!dir.path().join("docs/guides/setup.md").exists());
+    assert_eq!(read(dir.path(), "CLAUDE.md"), "See [setup](docs/install.md).\n@docs/install.md\n");
+    assert_eq!(read(dir.path(), "docs/api.md"), "# API\n\n[setup](install.md)\n");
+    // The moved file's own relative link follows it
+    assert_eq!(read(dir.path(), "docs/install.md"), "# Setup\n\nBack to [api](api.md).\n");
+
+    let mut rewritten: Vec<&str> = result.rewrites.iter().map(|r| r.path.as_str()).collect();
+    rewritten.sort();
+    assert_eq!(rewritten, vec!["CLAUDE.md", "docs/api.md", "docs/install.md"]);
+}
+
+#[test]
+fn test_move_folder() {
+    let dir = project();
+
+    let result = move_path(dir.path(), "docs/guides", "docs/handbook/guides", false, "api").unwrap();
+
+    assert_eq!(result.moved.len(), 1);
+    assert_eq!(result.moved[0].from, "docs/guides/setup.md");
+    assert_eq!(result.moved[0].to, "docs/handbook/guides/setup.md");
+    assert_eq!(read(dir.path(), "docs/handbook/guides/setup.md"), "# Setup\n\nBack to [api](../../api.md).\n");
+    assert_eq!(
+        read(dir.path(), "CLAUDE.md"),
+        "See [setup](docs/handbook/guides/setup.md).\n@docs/handbook/guides/setup.md\n"
+    );
+}
+
+#[test]
+fn test_dry_run_leaves_disk_untouched() {
+    let dir = project();
+
+    let result = move_path(dir.path(), "docs/guides/setup.md", "docs/install.md", true, "api").unwrap();
+
+    assert!(result.dry_run);
+    assert_eq!(result.rewrites.len(), 3);
+    assert!(dir.path().join("docs/guides/setup.md").exists());
+    assert!(!dir.path().join("docs/install.md").exists());
+    assert_eq!(read(dir.path(), "CLAUDE.md"), "See [setup](docs/guides/setup.md).\n@docs/guides/setup.md\n");
+    assert!(history::list_revisions(dir.path(), None).unwrap().is_empty());
+}
+
+#[test]
+fn test_rewritten_files_are_snapshotted() {
+    let dir = project();
+
+    move_path(dir.path(), "docs/guides/setup.md", "docs/install.md", false, "api").unwrap();
+
+    let revisions = history::list_revisions(dir.path(), None).unwrap();
+    let mut paths: Vec<&str> = revisions.iter().map(|r| r.path.as_str()).collect();
+    paths.sort();
+    // Snapshots are taken under the paths the files had before the move
+    assert_eq!(paths, vec!["CLAUDE.md", "docs/api.md", "docs/guides/setup.md"]);
+    assert!(revisions.iter().all(|r| r.operation == "move"));
+
+    let before = revisions.iter().find(|r| r.path == "CLAUDE.md").unwrap();
+    let stored = history::read_revision(dir.path(), &before.id).unwrap();
+    assert_eq!(stored.content, "See [setup](docs/guides/setup.md).\n@docs/guides/setup.md\n");
+}
+
+#[test]
+fn test_failed_rewrite_rolls_back_the_move() {
+    let dir = project();
+    // Too long a name for the temp file a rewrite writes first, so that rewrite fails after the rename
+    let long = format!("docs/{}.md", "n".repeat(240));
+    fs::write(dir.path().join(&long), "[setup](guides/setup.md)\n").unwrap();
+
+    let err = move_path(dir.path(), "docs/guides/setup.md", "docs/install.md", false, "api").unwrap_err();
+
+    assert!(format!("{:#}", err).contains("rolled back"), "{:#}", err);
+    assert!(!dir.path().join("docs/install.md").exists());
+    assert_eq!(read(dir.path(), "docs/guides/setup.md"), "# Setup\n\nBack to [api](../api.md).\n");
+    assert_eq!(read(dir.path(), "CLAUDE.md"), "See [setup](docs/guides/setup.md).\n@docs/guides/setup.md\n");
+    assert_eq!(read(dir.path(), "docs/api.md"), "# API\n\n[setup](guides/setup.md)\n");
+    assert_eq!(read(dir.path(), &long), "[setup](guides/setup.md)\n");
+}
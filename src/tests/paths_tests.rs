@@ -0,0 +1,47 @@
+use crate::models::MemoryScope;
+use crate::utils::paths::*;
+use std::fs;
+
+#[test]
+fn test_memory_scope() {
+    assert_eq!(memory_scope("CLAUDE.md"), MemoryScope::Project);
+    assert_eq!(memory_scope("./CLAUDE.md"), MemoryScope::Project);
+    assert_eq!(memory_scope("src/api/CLAUDE.md"), MemoryScope::Directory);
+    assert_eq!(memory_scope("CLAUDE.local.md"), MemoryScope::Local);
+    assert_eq!(memory_scope("src/CLAUDE.local.md"), MemoryScope::Local);
+}
+
+#[test]
+fn test_memory_file_response_metadata_only() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().to_string_lossy().to_string();
+    fs::create_dir(dir.path().join("src")).unwrap();
+    let file = dir.path().join("src/CLAUDE.md");
+    fs::write(&file, "# Source\n").unwrap();
+
+    let response = memory_file_response(&file, &root, false).unwrap();
+    assert_eq!(response.path, "src/CLAUDE.md");
+    assert_eq!(response.parent_path, "./src");
+    assert_eq!(response.scope, MemoryScope::Directory);
+    assert_eq!(response.size, 9);
+    assert!(response.modified_at.is_some());
+    assert!(response.content.is_none());
+    assert!(response.content_html.is_none());
+
+    let json = serde_json::to_value(&response).unwrap();
+    assert!(json.get("content").is_none());
+}
+
+#[test]
+fn test_memory_file_response_with_content() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().to_string_lossy().to_string();
+    let file = dir.path().join("CLAUDE.md");
+    fs::write(&file, "# Project\n").unwrap();
+
+    let response = memory_file_response(&file, &root, true).unwrap();
+    assert_eq!(response.scope, MemoryScope::Project);
+    assert_eq!(response.content.as_deref(), Some("# Project\n"));
+    assert!(response.content_html.unwrap().contains("<h1 id=\"user-content-project\">Project</h1>"));
+    assert_eq!(response.toc.unwrap()[0].anchor, "user-content-project");
+}
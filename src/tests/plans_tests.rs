@@ -0,0 +1,68 @@
+use crate::utils::plans::*;
+
+const PLAN: &str = "---\ntitle: Change watcher\n---\n# Watcher plan\n\n- [x] Pick a crate\n- [ ] Debounce events\n  - [X] Measure latency\n  * [ ] Tune interval\n1. [ ] Ship it\n\n```md\n- [ ] not a task\n```\n\n- [] not a task either\n- [x]also not\n";
+
+#[test]
+fn test_parse_tasks() {
+    let tasks = parse_tasks(PLAN);
+    let summary: Vec<(usize, &str, bool, usize)> =
+        tasks.iter().map(|t| (t.line, t.text.as_str(), t.checked, t.depth)).collect();
+
+    assert_eq!(
+        summary,
+        vec![
+            (6, "Pick a crate", true, 0),
+            (7, "Debounce events", false, 0),
+            (8, "Measure latency", true, 1),
+            (9, "Tune interval", false, 1),
+            (10, "Ship it", false, 0),
+        ]
+    );
+}
+
+#[test]
+fn test_summary_counts_and_status() {
+    let plan = summarize("docs/plans/change-watcher.md", PLAN, "2026-01-01T00:00:00Z".to_string(), false);
+    assert_eq!(plan.title, "Change watcher");
+    assert_eq!((plan.total, plan.done, plan.open, plan.percent), (5, 2, 3, 40));
+    assert_eq!(plan.status, "in_progress");
+    assert!(plan.tasks.is_none());
+
+    let declared = "# Rollout\n\n**Status:** Blocked\n\n- [ ] Ask ops\n";
+    let plan = summarize("docs/plans/rollout.md", declared, String::new(), true);
+    assert_eq!(plan.title, "Rollout");
+    assert_eq!(plan.status, "Blocked");
+    assert_eq!(plan.tasks.map(|t| t.len()), Some(1));
+
+    let empty = summarize("docs/plans/idea.md", "Just notes\n", String::new(), false);
+    assert_eq!((empty.title.as_str(), empty.status.as_str(), empty.percent), ("idea", "not_started", 0));
+
+    assert_eq!(percent(2, 3), 66);
+    assert_eq!(percent(3, 3), 100);
+}
+
+#[test]
+fn test_toggle_changes_only_the_checkbox() {
+    let content = "# Plan\r\n\r\n- [ ] First  \r\n- [x] Second\r\n";
+
+    let (checked, task) = toggle_task(content, 3, None, Some("First")).unwrap();
+    assert!(task.checked);
+    assert_eq!(checked, "# Plan\r\n\r\n- [x] First  \r\n- [x] Second\r\n");
+
+    let (unchecked, _) = toggle_task(&checked, 4, Some(false), None).unwrap();
+    assert_eq!(unchecked, "# Plan\r\n\r\n- [x] First  \r\n- [ ] Second\r\n");
+
+    // Setting the state it already has leaves the file alone
+    let (same, _) = toggle_task(content, 4, Some(true), None).unwrap();
+    assert_eq!(same, content);
+}
+
+#[test]
+fn test_toggle_rejects_stale_requests() {
+    assert_eq!(toggle_task(PLAN, 4, None, None), Err(PlanError::NotATask(4)));
+    assert_eq!(toggle_task(PLAN, 13, None, None), Err(PlanError::NotATask(13)));
+    assert_eq!(
+        toggle_task(PLAN, 7, None, Some("Pick a crate")),
+        Err(PlanError::TextChanged { line: 7, found: "Debounce events".to_string() })
+    );
+}
@@ -0,0 +1,85 @@
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request, StatusCode};
+use axum::Router;
+use std::fs;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use crate::server::frontend::Frontend;
+use crate::server::routes::create_router;
+use crate::server::{AppState, ServerConfig};
+
+fn router(root: &std::path::Path) -> Router {
+    let state = AppState::new(root.to_string_lossy().to_string(), "token".into(), Frontend::Embedded);
+    let config = ServerConfig {
+        host: "127.0.0.1".into(),
+        port: 3999,
+        project: root.to_string_lossy().to_string(),
+        token: Some("token".into()),
+        allowed_origins: Vec::new(),
+        frontend_dir: None,
+        socket: None,
+        socket_mode: 0o600,
+        tcp: true,
+    };
+    create_router(Arc::new(state), &config)
+}
+
+async fn get(router: &Router, uri: &str, token: Option<&str>) -> (StatusCode, String) {
+    let mut request = Request::builder().uri(uri);
+    if let Some(token) = token {
+        request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+    let response = router.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8_lossy(&body).to_string())
+}
+
+#[tokio::test]
+async fn test_openapi_is_public_and_describes_the_routes() {
+    let dir = tempfile::tempdir().unwrap();
+    let router = router(dir.path());
+
+    let (status, body) = get(&router, "/api/v1/openapi.json", None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let spec: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let paths = spec["paths"].as_object().unwrap();
+    for path in ["/memory-files", "/memory-files/{path}", "/docs/files/{path}", "/plans/toggle", "/adr/{number}/status"]
+    {
+        assert!(paths.contains_key(path), "{} missing from {:?}", path, paths.keys().collect::<Vec<_>>());
+    }
+    assert_eq!(spec["servers"][0]["url"], "/api/v1");
+}
+
+#[tokio::test]
+async fn test_both_prefixes_reach_the_same_handlers() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("CLAUDE.md"), "# Project\n").unwrap();
+    let router = router(dir.path());
+
+    let mut bodies = Vec::new();
+    for prefix in ["/api/v1", "/api"] {
+        for endpoint in ["/memory-files", "/memory-files/CLAUDE.md", "/plans"] {
+            let uri = format!("{}{}", prefix, endpoint);
+            let (status, _) = get(&router, &uri, None).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED, "{}", uri);
+            let (status, _) = get(&router, &uri, Some("wrong")).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED, "{}", uri);
+
+            let (status, body) = get(&router, &uri, Some("token")).await;
+            assert_eq!(status, StatusCode::OK, "{}: {}", uri, body);
+            bodies.push(body);
+        }
+
+        // Unknown endpoints are JSON 404s, never the dashboard page
+        let (status, body) = get(&router, &format!("{}/nothing", prefix), Some("token")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{}", prefix);
+        assert!(serde_json::from_str::<serde_json::Value>(&body).is_ok(), "{}", body);
+    }
+
+    let (v1, legacy) = bodies.split_at(bodies.len() / 2);
+    assert_eq!(v1, legacy);
+    assert!(v1[1].contains("# Project"), "{}", v1[1]);
+}
@@ -0,0 +1,109 @@
+use crate::utils::sandbox::*;
+use std::fs;
+use tempfile::TempDir;
+
+fn project() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("src/api")).unwrap();
+    fs::write(dir.path().join("CLAUDE.md"), "# Root").unwrap();
+    dir
+}
+
+#[test]
+fn test_resolves_paths_inside_root() {
+    let dir = project();
+    let root = dir.path().canonicalize().unwrap();
+
+    assert_eq!(resolve_path(dir.path(), "CLAUDE.md").unwrap(), root.join("CLAUDE.md"));
+    assert_eq!(resolve_path(dir.path(), "./src/api/CLAUDE.md").unwrap(), root.join("src/api/CLAUDE.md"));
+    // Parents that don't exist yet are fine for creates
+    assert_eq!(resolve_path(dir.path(), "new/dir/CLAUDE.md").unwrap(), root.join("new/dir/CLAUDE.md"));
+}
+
+#[test]
+fn test_rejects_parent_traversal() {
+    let dir = project();
+
+    assert!(matches!(resolve_path(dir.path(), "../../.bashrc"), Err(SandboxError::Traversal(_))));
+    assert!(matches!(resolve_path(dir.path(), "src/../../outside.md"), Err(SandboxError::Traversal(_))));
+    // Even traversal that would land back inside the root is refused
+    assert!(matches!(resolve_path(dir.path(), "src/../CLAUDE.md"), Err(SandboxError::Traversal(_))));
+}
+
+#[test]
+fn test_rejects_absolute_paths() {
+    let dir = project();
+
+    assert!(matches!(resolve_path(dir.path(), "/etc/passwd"), Err(SandboxError::Absolute(_))));
+    let inside = dir.path().join("CLAUDE.md").to_string_lossy().to_string();
+    assert!(matches!(resolve_path(dir.path(), &inside), Err(SandboxError::Absolute(_))));
+}
+
+#[test]
+fn test_rejects_empty_paths() {
+    let dir = project();
+
+    assert_eq!(resolve_path(dir.path(), ""), Err(SandboxError::Empty));
+    assert_eq!(resolve_path(dir.path(), "./"), Err(SandboxError::Empty));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_rejects_symlink_escapes() {
+    let dir = project();
+    let outside = tempfile::tempdir().unwrap();
+    fs::write(outside.path().join("CLAUDE.md"), "# Outside").unwrap();
+
+    // Symlinked directory pointing out of the project
+    std::os::unix::fs::symlink(outside.path(), dir.path().join("linked")).unwrap();
+    assert!(matches!(resolve_path(dir.path(), "linked/CLAUDE.md"), Err(SandboxError::Escape(_))));
+    assert!(matches!(resolve_path(dir.path(), "linked/new/CLAUDE.md"), Err(SandboxError::Escape(_))));
+
+    // Symlinked file pointing out of the project
+    std::os::unix::fs::symlink(outside.path().join("CLAUDE.md"), dir.path().join("src/CLAUDE.md")).unwrap();
+    assert!(matches!(resolve_path(dir.path(), "src/CLAUDE.md"), Err(SandboxError::Escape(_))));
+
+    // Dangling symlink whose target can't be checked
+    std::os::unix::fs::symlink("/nonexistent/target", dir.path().join("src/api/CLAUDE.md")).unwrap();
+    assert!(matches!(resolve_path(dir.path(), "src/api/CLAUDE.md"), Err(SandboxError::Escape(_))));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_allows_symlinks_within_root() {
+    let dir = project();
+    std::os::unix::fs::symlink(dir.path().join("src"), dir.path().join("alias")).unwrap();
+
+    assert!(resolve_path(dir.path(), "alias/api/CLAUDE.md").is_ok());
+}
+
+#[test]
+fn test_memory_paths_require_memory_file_names() {
+    let dir = project();
+
+    assert!(resolve_memory_path(dir.path(), "CLAUDE.md").is_ok());
+    assert!(resolve_memory_path(dir.path(), "src/CLAUDE.local.md").is_ok());
+    assert!(matches!(resolve_memory_path(dir.path(), "src/main.rs"), Err(SandboxError::NotMemoryFile(_))));
+    assert!(matches!(resolve_memory_path(dir.path(), ".bashrc"), Err(SandboxError::NotMemoryFile(_))));
+    assert!(matches!(resolve_memory_path(dir.path(), "../CLAUDE.md"), Err(SandboxError::Traversal(_))));
+}
+
+#[test]
+fn test_resolve_within_allowed_directories() {
+    let dir = project();
+    let root = dir.path().canonicalize().unwrap();
+    let allowed = ["docs", "packages/web/docs"];
+
+    assert_eq!(resolve_within(dir.path(), &allowed, "docs/guide.md").unwrap(), root.join("docs/guide.md"));
+    assert_eq!(
+        resolve_within(dir.path(), &allowed, "./packages/web/docs/api.md").unwrap(),
+        root.join("packages/web/docs/api.md")
+    );
+
+    // The root itself, siblings sharing a prefix and other folders are all outside
+    assert!(matches!(resolve_within(dir.path(), &allowed, "docs"), Err(SandboxError::OutsideAllowed(_))));
+    assert!(matches!(resolve_within(dir.path(), &allowed, "docs-old/x.md"), Err(SandboxError::OutsideAllowed(_))));
+    assert!(matches!(resolve_within(dir.path(), &allowed, "src/CLAUDE.md"), Err(SandboxError::OutsideAllowed(_))));
+    assert!(matches!(resolve_within(dir.path(), &[], "docs/guide.md"), Err(SandboxError::OutsideAllowed(_))));
+    assert!(matches!(resolve_within(dir.path(), &allowed, "docs/../x.md"), Err(SandboxError::Traversal(_))));
+}
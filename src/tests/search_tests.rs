@@ -0,0 +1,140 @@
+use crate::models::{FileKind, MemoryScope};
+use crate::utils::search::*;
+
+fn memory(path: &str, scope: MemoryScope) -> DocumentInfo {
+    DocumentInfo {
+        kind: FileKind::Memory,
+        path: path.to_string(),
+        project_path: path.to_string(),
+        scope: Some(scope),
+        modified: 0,
+    }
+}
+
+fn doc(path: &str) -> DocumentInfo {
+    DocumentInfo {
+        kind: FileKind::Docs,
+        path: path.to_string(),
+        project_path: path.to_string(),
+        scope: None,
+        modified: 0,
+    }
+}
+
+fn sample_index() -> SearchIndex {
+    let mut index = SearchIndex::new();
+    index.upsert(
+        memory("CLAUDE.md", MemoryScope::Project),
+        "# Project\n\n## Database\n\n### Migrations\n\nAlways write a down migration.\n\n## Style\n\nUse rustfmt.\n",
+    );
+    index.upsert(memory("src/api/CLAUDE.md", MemoryScope::Directory), "# API\n\nHandlers return typed errors.\n");
+    index.upsert(doc("docs/guides/setup.md"), "# Setup\n\nRun the migration tool after install.\n");
+    index
+}
+
+#[test]
+fn test_tokenize_and_headings() {
+    assert_eq!(tokenize("Don't re-run `cargo`!"), vec!["don", "t", "re", "run", "cargo"]);
+    assert_eq!(parse_heading("## Database ##"), Some((2, "Database".to_string())));
+    assert_eq!(parse_heading("#hashtag"), None);
+    assert_eq!(parse_heading("    # indented code"), None);
+}
+
+#[test]
+fn test_search_ranks_with_heading_context() {
+    let index = sample_index();
+    let (total, hits) = index.search("migration", &SearchFilters::default(), 10);
+
+    assert_eq!(total, 2);
+    assert_eq!(hits[0].path, "CLAUDE.md");
+    assert_eq!(hits[0].headings, vec!["Project", "Database", "Migrations"]);
+    assert_eq!(hits[0].line, 7);
+    assert_eq!(hits[0].snippet, "Always write a down migration.");
+    assert_eq!(hits[0].highlights, vec![[20, 29]]);
+    assert_eq!(hits[1].kind, FileKind::Docs);
+}
+
+#[test]
+fn test_search_prefers_sections_matching_all_terms() {
+    let index = sample_index();
+    let (_, hits) = index.search("migration install", &SearchFilters::default(), 10);
+    assert_eq!(hits[0].path, "docs/guides/setup.md");
+}
+
+#[test]
+fn test_search_filters() {
+    let index = sample_index();
+
+    let docs_only = SearchFilters { kind: Some(FileKind::Docs), ..Default::default() };
+    let (total, _) = index.search("migration", &docs_only, 10);
+    assert_eq!(total, 1);
+
+    let directory = SearchFilters { scope: Some(MemoryScope::Directory), ..Default::default() };
+    assert_eq!(index.search("handlers", &directory, 10).0, 1);
+    assert_eq!(index.search("migration", &directory, 10).0, 0);
+
+    let under_src = SearchFilters { dir: Some("./src/".to_string()), ..Default::default() };
+    assert_eq!(index.search("typed", &under_src, 10).0, 1);
+    assert_eq!(index.search("migration", &under_src, 10).0, 0);
+
+    let under_guides = SearchFilters { dir: Some("docs/guides".to_string()), ..Default::default() };
+    assert_eq!(index.search("migration", &under_guides, 10).0, 1);
+}
+
+#[test]
+fn test_upsert_and_remove_keep_index_current() {
+    let mut index = sample_index();
+
+    index.upsert(memory("CLAUDE.md", MemoryScope::Project), "# Project\n\nNothing here.\n");
+    assert_eq!(index.search("migration", &SearchFilters::default(), 10).0, 1);
+
+    assert!(index.remove("docs/guides/setup.md"));
+    assert_eq!(index.search("migration", &SearchFilters::default(), 10).0, 0);
+
+    assert_eq!(index.remove_dir("src"), 1);
+    assert_eq!(index.len(), 1);
+}
+
+#[test]
+fn test_prefix_matches_and_long_snippets() {
+    let mut index = SearchIndex::new();
+    let long_line = format!("{} the migrations live in db {}", "word ".repeat(60), "tail ".repeat(60));
+    index.upsert(memory("CLAUDE.md", MemoryScope::Project), &long_line);
+
+    let (_, hits) = index.search("migr", &SearchFilters::default(), 10);
+    let snippet = &hits[0].snippet;
+    assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+    assert!(snippet.chars().count() <= 162);
+
+    let [start, end] = hits[0].highlights[0];
+    let highlighted: String = snippet.chars().skip(start).take(end - start).collect();
+    assert_eq!(highlighted, "migrations");
+}
+
+#[test]
+fn test_cache_applies_changes_read_outside_it() {
+    use crate::services::search::{Change, SearchCache};
+    use std::sync::Arc;
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("CLAUDE.md"), "# Project\n").unwrap();
+    std::fs::create_dir(dir.path().join("api")).unwrap();
+
+    let mut cache = SearchCache::default();
+    assert!(!cache.is_built());
+    let refresh = cache.begin_refresh().run(dir.path()).unwrap();
+    let searching = cache.finish_refresh(refresh);
+
+    // A folder only marks the index stale; the index a search holds isn't copied for it
+    cache.apply(Some(Change::read(dir.path(), &dir.path().join("api"))));
+    assert_eq!(Arc::strong_count(&searching), 2);
+    assert!(cache.current().is_none());
+
+    let refresh = cache.begin_refresh().run(dir.path()).unwrap();
+    cache.finish_refresh(refresh);
+    std::fs::write(dir.path().join("api/CLAUDE.md"), "# API\n").unwrap();
+    cache.apply(Some(Change::read(dir.path(), &dir.path().join("api/CLAUDE.md"))));
+    let index = cache.current().expect("still fresh");
+    assert!(index.document("api/CLAUDE.md").is_some());
+    assert!(searching.document("api/CLAUDE.md").is_none());
+}
@@ -0,0 +1,33 @@
+use std::fs;
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::os::unix::net::UnixStream;
+
+use crate::server::socket;
+
+#[tokio::test]
+async fn test_bind_creates_socket_with_mode() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("atlas.sock");
+
+    let _listener = socket::bind(&path, 0o600).unwrap();
+    let metadata = fs::symlink_metadata(&path).unwrap();
+    assert!(metadata.file_type().is_socket());
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+    assert!(UnixStream::connect(&path).is_ok());
+
+    // Nothing is left behind next to it
+    let entries: Vec<_> = fs::read_dir(dir.path()).unwrap().map(|e| e.unwrap().file_name()).collect();
+    assert_eq!(entries, vec!["atlas.sock"]);
+}
+
+#[tokio::test]
+async fn test_bind_replaces_stale_socket_but_not_a_live_one() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("atlas.sock");
+
+    drop(socket::bind(&path, 0o600).unwrap());
+    let live = socket::bind(&path, 0o660).unwrap();
+    assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o660);
+    assert!(socket::bind(&path, 0o600).is_err());
+    drop(live);
+}
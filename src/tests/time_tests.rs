@@ -0,0 +1,14 @@
+use crate::utils::time::*;
+
+#[test]
+fn test_format_rfc3339() {
+    assert_eq!(format_rfc3339(0), "1970-01-01T00:00:00Z");
+    assert_eq!(format_rfc3339(951_782_400), "2000-02-29T00:00:00Z");
+    assert_eq!(format_rfc3339(1_735_689_599), "2024-12-31T23:59:59Z");
+}
+
+#[test]
+fn test_format_date() {
+    assert_eq!(format_date(0), "1970-01-01");
+    assert_eq!(format_date(1_735_689_599), "2024-12-31");
+}
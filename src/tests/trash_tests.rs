@@ -0,0 +1,76 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use crate::services::trash::*;
+
+#[test]
+fn test_trash_and_restore() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("docs/guides")).unwrap();
+    fs::write(dir.path().join("docs/guides/setup.md"), "setup\n").unwrap();
+    fs::write(dir.path().join("CLAUDE.md"), "root\n").unwrap();
+
+    let folder = move_to_trash(dir.path(), &dir.path().join("docs/guides"), "api").unwrap();
+    let file = move_to_trash(dir.path(), &dir.path().join("CLAUDE.md"), "cli").unwrap();
+    assert!(folder.is_dir && !file.is_dir);
+    assert!(!dir.path().join("CLAUDE.md").exists());
+    assert_eq!(list_trash(dir.path()).unwrap().len(), 2);
+
+    restore(dir.path(), &folder.id).unwrap();
+    restore(dir.path(), &file.id).unwrap();
+    assert_eq!(fs::read_to_string(dir.path().join("docs/guides/setup.md")).unwrap(), "setup\n");
+    assert_eq!(fs::read_to_string(dir.path().join("CLAUDE.md")).unwrap(), "root\n");
+    assert!(list_trash(dir.path()).unwrap().is_empty());
+}
+
+#[test]
+fn test_concurrent_deletions_get_their_own_slots() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    for i in 0..16 {
+        fs::create_dir_all(root.join(format!("pkg{}", i))).unwrap();
+        fs::write(root.join(format!("pkg{}/CLAUDE.md", i)), format!("package {}\n", i)).unwrap();
+    }
+
+    // Deletions within the same second pick ids from the same timestamp
+    let entries: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..16)
+            .map(|i| {
+                scope.spawn(move || move_to_trash(root, &root.join(format!("pkg{}/CLAUDE.md", i)), "api").unwrap())
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let ids: HashSet<&str> = entries.iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(ids.len(), 16);
+    assert_eq!(list_trash(root).unwrap().len(), 16);
+
+    // Nothing was overwritten: every file comes back with its own content
+    for entry in &entries {
+        restore(root, &entry.id).unwrap();
+    }
+    for i in 0..16 {
+        let content = fs::read_to_string(root.join(format!("pkg{}/CLAUDE.md", i))).unwrap();
+        assert_eq!(content, format!("package {}\n", i));
+    }
+}
+
+#[test]
+fn test_purge_and_occupied_restore() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("CLAUDE.md");
+    fs::write(&file, "old\n").unwrap();
+    let entry = move_to_trash(dir.path(), &file, "api").unwrap();
+
+    fs::write(&file, "new\n").unwrap();
+    let occupied = restore(dir.path(), &entry.id).unwrap_err();
+    assert_eq!(occupied.downcast_ref::<TrashError>(), Some(&TrashError::PathOccupied("CLAUDE.md".into())));
+
+    let purged = purge(dir.path(), Some(&entry.id)).unwrap();
+    assert_eq!(purged.len(), 1);
+    assert!(list_trash(dir.path()).unwrap().is_empty());
+    assert!(fs::read_dir(dir.path().join(".cc-atlas/trash/items")).unwrap().next().is_none());
+    assert!(Path::new(&file).exists());
+}
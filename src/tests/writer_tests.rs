@@ -0,0 +1,87 @@
+use crate::utils::writer::*;
+use std::fs;
+
+#[test]
+fn test_detect_format() {
+    let format = detect_format("\u{feff}# Title\r\n\r\nBody\r\n");
+    assert_eq!(format.line_ending, LineEnding::CrLf);
+    assert!(format.bom);
+    assert!(format.final_newline);
+
+    let format = detect_format("# Title\n\nBody");
+    assert_eq!(format.line_ending, LineEnding::Lf);
+    assert!(!format.bom);
+    assert!(!format.final_newline);
+}
+
+#[test]
+fn test_apply_format_restores_conventions() {
+    let original = detect_format("\u{feff}a\r\nb\r\n");
+    assert_eq!(apply_format("a\nb\nc", &original), "\u{feff}a\r\nb\r\nc\r\n");
+
+    let original = detect_format("a\nb");
+    assert_eq!(apply_format("a\r\nb\r\nc\n", &original), "a\nb\nc");
+}
+
+#[test]
+fn test_write_preserving_keeps_existing_format() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("CLAUDE.md");
+    fs::write(&path, "\u{feff}# Rules\r\n- one\r\n").unwrap();
+
+    let written = write_preserving(&path, "# Rules\n- one\n- two").unwrap();
+    assert_eq!(written, "\u{feff}# Rules\r\n- one\r\n- two\r\n");
+    assert_eq!(fs::read_to_string(&path).unwrap(), written);
+}
+
+#[test]
+fn test_write_preserving_new_file_is_verbatim() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("nested/CLAUDE.md");
+
+    let written = write_preserving(&path, "# New\n").unwrap();
+    assert_eq!(written, "# New\n");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "# New\n");
+}
+
+#[test]
+fn test_write_atomic_leaves_no_temp_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("notes.md");
+    write_atomic(&path, b"first").unwrap();
+    write_atomic(&path, b"second").unwrap();
+
+    let names: Vec<_> = fs::read_dir(dir.path()).unwrap().map(|e| e.unwrap().file_name()).collect();
+    assert_eq!(names, vec!["notes.md"]);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+}
+
+#[test]
+fn test_write_new_refuses_existing_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("docs/adr/0001-first.md");
+    write_new(&path, b"first").unwrap();
+
+    let err = write_new(&path, b"second").unwrap_err();
+    assert_eq!(err.downcast_ref::<std::io::Error>().unwrap().kind(), std::io::ErrorKind::AlreadyExists);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "first");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_write_preserves_permissions_and_symlinks() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("script.md");
+    fs::write(&path, "x\n").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+
+    let link = dir.path().join("link.md");
+    std::os::unix::fs::symlink(&path, &link).unwrap();
+
+    write_preserving(&link, "y").unwrap();
+    assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "y\n");
+    assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o640);
+}
@@ -0,0 +1,332 @@
+use std::fmt;
+
+use super::front_matter;
+use super::search::parse_heading;
+use crate::models::{AdrStatus, AdrSummary, DocMetadata};
+
+/// Generated index page, rewritten after every ADR change
+pub const INDEX_FILE: &str = "README.md";
+/// Optional per-project template for new ADRs
+pub const TEMPLATE_FILE: &str = "template.md";
+
+/// Used when the ADR directory has no `template.md`.
+/// Placeholders: `{{number}}`, `{{title}}`, `{{date}}` and `{{status}}`.
+pub const DEFAULT_TEMPLATE: &str = "\
+---
+title: {{title}}
+status: {{status}}
+updated: {{date}}
+---
+# {{number}}. {{title}}
+
+## Status
+
+Proposed
+
+## Context
+
+What is the issue that we're seeing that is motivating this decision or change?
+
+## Decision
+
+What is the change that we're proposing and/or doing?
+
+## Consequences
+
+What becomes easier or more difficult to do because of this change?
+";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdrError {
+    NotFound(u32),
+    EmptyTitle,
+    InvalidTransition { from: AdrStatus, to: AdrStatus },
+    MissingSupersededBy,
+    SelfSupersede(u32),
+    InvalidFrontMatter(String),
+}
+
+impl fmt::Display for AdrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AdrError::NotFound(number) => write!(f, "ADR {:04} not found", number),
+            AdrError::EmptyTitle => write!(f, "ADR title must not be empty"),
+            AdrError::InvalidTransition { from, to } => {
+                write!(f, "Cannot change ADR status from {} to {}", status_name(*from), status_name(*to))
+            }
+            AdrError::MissingSupersededBy => write!(f, "Superseding an ADR needs the ADR that replaces it"),
+            AdrError::SelfSupersede(number) => write!(f, "ADR {:04} cannot supersede itself", number),
+            AdrError::InvalidFrontMatter(path) => write!(f, "Front matter is not valid YAML: {}", path),
+        }
+    }
+}
+
+impl std::error::Error for AdrError {}
+
+/// Lower-case name as stored in documents
+pub fn status_name(status: AdrStatus) -> &'static str {
+    match status {
+        AdrStatus::Proposed => "proposed",
+        AdrStatus::Accepted => "accepted",
+        AdrStatus::Superseded => "superseded",
+        AdrStatus::Deprecated => "deprecated",
+    }
+}
+
+/// Parse a status word, ignoring case and anything after it ("Superseded by ADR 7")
+pub fn parse_status(text: &str) -> Option<AdrStatus> {
+    let word = text.split_whitespace().next()?.trim_matches(|c: char| !c.is_alphabetic());
+    match word.to_lowercase().as_str() {
+        "proposed" | "draft" => Some(AdrStatus::Proposed),
+        "accepted" => Some(AdrStatus::Accepted),
+        "superseded" => Some(AdrStatus::Superseded),
+        "deprecated" => Some(AdrStatus::Deprecated),
+        _ => None,
+    }
+}
+
+/// Proposed ADRs can be accepted, and anything live can be deprecated or superseded; those two are final
+pub fn can_transition(from: AdrStatus, to: AdrStatus) -> bool {
+    use AdrStatus::*;
+    matches!((from, to), (Proposed, Accepted) | (Proposed | Accepted, Deprecated) | (Proposed | Accepted, Superseded))
+}
+
+/// The number an ADR file name starts with, e.g. 12 for `0012-use-postgres.md`
+pub fn parse_number(file_name: &str) -> Option<u32> {
+    let stem = file_name.strip_suffix(".md")?;
+    let digits: String = stem.chars().take_while(|c| c.is_ascii_digit()).collect();
+    let rest = &stem[digits.len()..];
+
+    if digits.is_empty() || !(rest.is_empty() || rest.starts_with(['-', '_', '.', ' '])) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+pub fn next_number(existing: impl IntoIterator<Item = u32>) -> u32 {
+    existing.into_iter().max().map_or(1, |n| n + 1)
+}
+
+/// `0007-use-postgres-for-events.md`
+pub fn file_name(number: u32, title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+
+    if slug.is_empty() {
+        format!("{:04}.md", number)
+    } else {
+        format!("{:04}-{}.md", number, slug)
+    }
+}
+
+pub fn render_template(template: &str, number: u32, title: &str, date: &str) -> String {
+    let fill = |text: &str, title: &str| {
+        text.replace("{{number}}", &format!("{:04}", number))
+            .replace("{{title}}", title)
+            .replace("{{date}}", date)
+            .replace("{{status}}", status_name(AdrStatus::Proposed))
+    };
+
+    // Quote the title inside front matter so a colon can't break the YAML
+    let (block, body) = front_matter::split(template);
+    let quoted = serde_json::to_string(title).unwrap_or_default();
+    format!("{}{}", fill(block, &quoted), fill(body, title))
+}
+
+/// Read an ADR from its content. Front matter wins; hand-written ADRs fall back to
+/// the first `#` heading and the line under `## Status`.
+pub fn summarize(number: u32, path: &str, content: &str) -> AdrSummary {
+    let (metadata, body) = front_matter::parse(content);
+    let metadata = metadata.unwrap_or_default();
+
+    let title = metadata
+        .title
+        .clone()
+        .unwrap_or_else(|| heading_title(body).unwrap_or_else(|| path.rsplit('/').next().unwrap_or(path).to_string()));
+    let status = metadata
+        .status
+        .as_deref()
+        .and_then(parse_status)
+        .or_else(|| status_line(body).and_then(|(_, line)| parse_status(line)))
+        .unwrap_or(AdrStatus::Proposed);
+
+    AdrSummary {
+        number,
+        title,
+        status,
+        path: path.to_string(),
+        date: metadata.updated.clone().or_else(|| extra_text(&metadata, "date")),
+        supersedes: numbers(metadata.extra.get("supersedes")),
+        superseded_by: numbers(metadata.extra.get("superseded_by")).first().copied(),
+    }
+}
+
+/// Rewrite an ADR's status, supersede links and date, keeping a `## Status` section in step
+pub fn update_content(
+    content: &str,
+    path: &str,
+    status: Option<AdrStatus>,
+    superseded_by: Option<u32>,
+    add_supersedes: Option<u32>,
+    date: &str,
+) -> Result<String, AdrError> {
+    let (block, body) = front_matter::split(content);
+    let (metadata, _) = front_matter::parse(content);
+    if !block.is_empty() && metadata.is_none() {
+        return Err(AdrError::InvalidFrontMatter(path.to_string()));
+    }
+
+    let mut metadata = metadata.unwrap_or_default();
+    let mut body = body.to_string();
+
+    if metadata.title.is_none() {
+        metadata.title = heading_title(&body);
+    }
+    if let Some(status) = status {
+        metadata.status = Some(status_name(status).to_string());
+        metadata.updated = Some(date.to_string());
+        body = set_status_line(&body, status, superseded_by);
+    }
+    if let Some(number) = superseded_by {
+        metadata.extra.insert("superseded_by".to_string(), number.into());
+    }
+    if let Some(number) = add_supersedes {
+        let mut list = numbers(metadata.extra.get("supersedes"));
+        if !list.contains(&number) {
+            list.push(number);
+            list.sort_unstable();
+        }
+        metadata.extra.insert("supersedes".to_string(), list.into());
+    }
+
+    let block = front_matter::render(&metadata).map_err(|_| AdrError::InvalidFrontMatter(path.to_string()))?;
+    Ok(front_matter::with_block(&block, &body))
+}
+
+/// The generated index page. `path` of each summary is linked relative to the ADR directory.
+pub fn render_index(adrs: &[AdrSummary], link: impl Fn(&AdrSummary) -> String) -> String {
+    let mut out = String::from("# Architecture Decision Records\n\n");
+    out.push_str("<!-- Generated by cc-atlas; changes to this file are overwritten. -->\n\n");
+
+    if adrs.is_empty() {
+        out.push_str("No decisions recorded yet.\n");
+        return out;
+    }
+
+    out.push_str("| ADR | Title | Status | Date |\n|-----|-------|--------|------|\n");
+    for adr in adrs {
+        let mut status = capitalize(status_name(adr.status));
+        if let Some(by) = adr.superseded_by.and_then(|n| adrs.iter().find(|a| a.number == n)) {
+            status = format!("{} by [{:04}]({})", status, by.number, link(by));
+        }
+        if !adr.supersedes.is_empty() {
+            let replaced: Vec<String> = adr
+                .supersedes
+                .iter()
+                .filter_map(|n| adrs.iter().find(|a| a.number == *n))
+                .map(|old| format!("[{:04}]({})", old.number, link(old)))
+                .collect();
+            if !replaced.is_empty() {
+                status = format!("{}, supersedes {}", status, replaced.join(", "));
+            }
+        }
+
+        out.push_str(&format!(
+            "| [{:04}]({}) | {} | {} | {} |\n",
+            adr.number,
+            link(adr),
+            adr.title.replace('|', "\\|"),
+            status,
+            adr.date.as_deref().unwrap_or("")
+        ));
+    }
+
+    out
+}
+
+/// First `#` heading, without a leading "12." or "ADR 12:" number
+fn heading_title(body: &str) -> Option<String> {
+    let (_, text) = body.lines().find_map(|line| parse_heading(line).filter(|(level, _)| *level == 1))?;
+    let trimmed = text.trim_start_matches("ADR").trim_start_matches("adr").trim_start();
+    let after_number = trimmed.trim_start_matches(|c: char| c.is_ascii_digit());
+
+    if after_number.len() < trimmed.len() {
+        let title = after_number.trim_start_matches([':', '.', '-', ' ']).trim();
+        if !title.is_empty() {
+            return Some(title.to_string());
+        }
+    }
+    Some(text)
+}
+
+/// Byte offset and text of the first non-empty line under a `## Status` heading
+fn status_line(body: &str) -> Option<(usize, &str)> {
+    let mut offset = 0;
+    let mut in_status = false;
+
+    for line in body.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+
+        if let Some((_, text)) = parse_heading(line) {
+            if in_status {
+                return None; // Empty section
+            }
+            in_status = text.eq_ignore_ascii_case("status");
+            continue;
+        }
+        if in_status && !line.trim().is_empty() {
+            return Some((start, line.trim_end_matches(['\r', '\n'])));
+        }
+    }
+
+    None
+}
+
+fn set_status_line(body: &str, status: AdrStatus, superseded_by: Option<u32>) -> String {
+    let Some((start, line)) = status_line(body) else {
+        return body.to_string();
+    };
+
+    let mut text = capitalize(status_name(status));
+    if let Some(number) = superseded_by.filter(|_| status == AdrStatus::Superseded) {
+        text = format!("{} by ADR {:04}", text, number);
+    }
+
+    let mut updated = body.to_string();
+    updated.replace_range(start..start + line.len(), &text);
+    updated
+}
+
+fn numbers(value: Option<&serde_json::Value>) -> Vec<u32> {
+    let as_number = |v: &serde_json::Value| match v {
+        serde_json::Value::Number(n) => n.as_u64().map(|n| n as u32),
+        serde_json::Value::String(s) => s.trim().trim_start_matches("ADR").trim().parse().ok(),
+        _ => None,
+    };
+
+    match value {
+        Some(serde_json::Value::Array(items)) => items.iter().filter_map(as_number).collect(),
+        Some(value) => as_number(value).into_iter().collect(),
+        None => Vec::new(),
+    }
+}
+
+fn extra_text(metadata: &DocMetadata, key: &str) -> Option<String> {
+    match metadata.extra.get(key)? {
+        serde_json::Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars.next().map(|first| first.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
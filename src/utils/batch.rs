@@ -0,0 +1,210 @@
+use similar::TextDiff;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::{hash, writer};
+use crate::models::{BatchChange, BatchFileChange, BatchOperation, BatchResult};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchError {
+    Empty,
+    NotFound { operation: usize, path: String },
+    Exists { operation: usize, path: String },
+    Modified { operation: usize, path: String, base_hash: String, current_hash: String },
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BatchError::Empty => write!(f, "Batch has no operations"),
+            BatchError::NotFound { operation, path } => {
+                write!(f, "Operation {}: file not found: {}", operation, path)
+            }
+            BatchError::Exists { operation, path } => {
+                write!(f, "Operation {}: file already exists: {}", operation, path)
+            }
+            BatchError::Modified { operation, path, .. } => {
+                write!(f, "Operation {}: {} has changed since base_hash", operation, path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+/// Why a file that existed before the batch is gone afterwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Removal {
+    Deleted,
+    Moved,
+}
+
+/// One file touched by a batch: what it held before and what it will hold after
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilePlan {
+    pub path: String,
+    pub original: Option<String>,
+    pub content: Option<String>,
+    pub moved_from: Option<String>, // The pre-batch file this content was moved from
+    pub removal: Option<Removal>,
+}
+
+impl FilePlan {
+    /// Whether applying the batch changes this file at all
+    pub fn is_changed(&self) -> bool {
+        self.original != self.content || self.moved_from.is_some()
+    }
+}
+
+/// Check every operation against the files as earlier operations leave them and work out
+/// each file's final content. `read` returns a file's current content, or `None` if it doesn't exist.
+///
+/// Operations are numbered from 0 in errors. Paths must already be normalized, so one file has one name.
+pub fn plan(
+    operations: &[BatchOperation],
+    mut read: impl FnMut(&str) -> Option<String>,
+) -> Result<Vec<FilePlan>, BatchError> {
+    if operations.is_empty() {
+        return Err(BatchError::Empty);
+    }
+
+    let mut files: BTreeMap<String, FilePlan> = BTreeMap::new();
+    let mut slot = |files: &mut BTreeMap<String, FilePlan>, path: &str| {
+        if !files.contains_key(path) {
+            let original = read(path);
+            files.insert(
+                path.to_string(),
+                FilePlan {
+                    path: path.to_string(),
+                    content: original.clone(),
+                    original,
+                    moved_from: None,
+                    removal: None,
+                },
+            );
+        }
+    };
+
+    for (operation, op) in operations.iter().enumerate() {
+        match op {
+            BatchOperation::Create { path, content } => {
+                slot(&mut files, path);
+                let file = files.get_mut(path).expect("slot was just filled");
+                if file.content.is_some() {
+                    return Err(BatchError::Exists { operation, path: path.clone() });
+                }
+                file.content = Some(content.clone());
+                file.removal = None;
+            }
+            BatchOperation::Update { path, content, base_hash } => {
+                slot(&mut files, path);
+                let file = files.get_mut(path).expect("slot was just filled");
+                let current = check_base(file, operation, base_hash)?;
+                // Keep the file's line endings, BOM and final newline, as a single save does
+                file.content = Some(writer::apply_format(content, &writer::detect_format(current)));
+            }
+            BatchOperation::Delete { path, base_hash } => {
+                slot(&mut files, path);
+                let file = files.get_mut(path).expect("slot was just filled");
+                check_base(file, operation, base_hash)?;
+                file.content = None;
+                file.removal = Some(Removal::Deleted);
+
+                // Deleting moved content deletes the file it came from, so that one goes to the trash
+                if let Some(from) = file.moved_from.take() {
+                    if let Some(source) = files.get_mut(&from) {
+                        source.removal = Some(Removal::Deleted);
+                    }
+                }
+            }
+            BatchOperation::Move { from, to, base_hash } => {
+                if from == to {
+                    return Err(BatchError::Exists { operation, path: to.clone() });
+                }
+
+                slot(&mut files, from);
+                let source = files.get_mut(from).expect("slot was just filled");
+                check_base(source, operation, base_hash)?;
+                let content = source.content.take();
+                let moved_from = source.moved_from.take().or_else(|| source.original.is_some().then(|| from.clone()));
+                source.removal = Some(Removal::Moved);
+
+                slot(&mut files, to);
+                let destination = files.get_mut(to).expect("slot was just filled");
+                if destination.content.is_some() {
+                    return Err(BatchError::Exists { operation, path: to.clone() });
+                }
+                destination.content = content;
+                destination.moved_from = moved_from;
+                destination.removal = None;
+            }
+        }
+    }
+
+    Ok(files.into_values().collect())
+}
+
+/// The file's content so far, if `base_hash` matches it
+fn check_base<'a>(file: &'a FilePlan, operation: usize, base_hash: &str) -> Result<&'a str, BatchError> {
+    let Some(current) = file.content.as_deref() else {
+        return Err(BatchError::NotFound { operation, path: file.path.clone() });
+    };
+
+    let base_hash = hash::from_etag(base_hash);
+    let current_hash = hash::calculate_hash(current);
+    if current_hash != base_hash {
+        return Err(BatchError::Modified { operation, path: file.path.clone(), base_hash, current_hash });
+    }
+    Ok(current)
+}
+
+/// Net change per file plus one combined unified diff, as if the plan had been applied
+pub fn summarize(files: &[FilePlan], dry_run: bool) -> BatchResult {
+    let originals: BTreeMap<&str, &str> =
+        files.iter().filter_map(|f| Some((f.path.as_str(), f.original.as_deref()?))).collect();
+
+    let mut changes = Vec::new();
+    let mut diff = String::new();
+
+    for file in files.iter().filter(|f| f.is_changed()) {
+        let change = match (&file.content, &file.moved_from) {
+            // Reported, and diffed, at the destination
+            (None, _) if file.removal == Some(Removal::Moved) => continue,
+            (None, _) => BatchChange::Deleted,
+            (Some(_), Some(_)) => BatchChange::Moved,
+            (Some(_), None) if file.original.is_none() => BatchChange::Created,
+            (Some(_), None) => BatchChange::Updated,
+        };
+
+        let (old_label, old) = match &file.moved_from {
+            Some(from) => (format!("a/{}", from), originals.get(from.as_str()).copied()),
+            None => (format!("a/{}", file.path), file.original.as_deref()),
+        };
+        let old_label = if old.is_some() { old_label } else { "/dev/null".to_string() };
+        let new_label = match file.content {
+            Some(_) => format!("b/{}", file.path),
+            None => "/dev/null".to_string(),
+        };
+        let unified = TextDiff::from_lines(old.unwrap_or_default(), file.content.as_deref().unwrap_or_default())
+            .unified_diff()
+            .header(&old_label, &new_label)
+            .to_string();
+
+        if unified.is_empty() {
+            // A pure rename has no hunks; still say where the file went
+            diff.push_str(&format!("--- {}\n+++ {}\n", old_label, new_label));
+        } else {
+            diff.push_str(&unified);
+        }
+
+        changes.push(BatchFileChange {
+            path: file.path.clone(),
+            change,
+            from: file.moved_from.clone(),
+            content_hash: file.content.as_deref().map(hash::calculate_hash),
+            trash_id: None,
+        });
+    }
+
+    BatchResult { dry_run, files: changes, diff }
+}
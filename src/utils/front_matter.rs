@@ -0,0 +1,207 @@
+use anyhow::Result;
+use serde_yaml::Value as Yaml;
+use std::cmp::Ordering;
+
+use crate::models::{DocMetadata, DocSortField, DocsNode, SortOrder};
+
+/// Split a leading `---` front matter block from the body.
+///
+/// Returns the whole block including its delimiter lines (empty if there is none) and the body.
+pub fn split(content: &str) -> (&str, &str) {
+    let Some(first_line_end) = content.find('\n') else {
+        return ("", content);
+    };
+    if content[..first_line_end].trim_end() != "---" {
+        return ("", content);
+    }
+
+    let mut offset = first_line_end + 1;
+    for line in content[offset..].split_inclusive('\n') {
+        offset += line.len();
+        let trimmed = line.trim_end();
+        if trimmed == "---" || trimmed == "..." {
+            return content.split_at(offset);
+        }
+    }
+
+    ("", content) // Never closed, so not front matter
+}
+
+/// Parse the front matter into metadata and return it with the body.
+///
+/// A block that isn't valid YAML still counts as front matter for stripping, but yields no metadata.
+pub fn parse(content: &str) -> (Option<DocMetadata>, &str) {
+    let (block, body) = split(content);
+    if block.is_empty() {
+        return (None, body);
+    }
+
+    let metadata = serde_yaml::from_str::<Yaml>(yaml_text(block)).ok().and_then(|yaml| match yaml {
+        Yaml::Null => Some(DocMetadata::default()),
+        Yaml::Mapping(mapping) => Some(from_mapping(mapping)),
+        _ => None,
+    });
+
+    (metadata, body)
+}
+
+/// Serialize metadata as a complete front matter block
+pub fn render(metadata: &DocMetadata) -> Result<String> {
+    let yaml = serde_yaml::to_string(metadata)?;
+    let yaml = if yaml.trim() == "{}" { "" } else { yaml.as_str() };
+    Ok(format!("---\n{}---\n", yaml))
+}
+
+/// Put a front matter block back in front of a body, replacing any block the body already has
+pub fn with_block(block: &str, body: &str) -> String {
+    let (_, body) = split(body);
+    if block.is_empty() {
+        return body.to_string();
+    }
+
+    let mut output = block.to_string();
+    if !output.ends_with('\n') {
+        output.push('\n');
+    }
+    output.push_str(body);
+    output
+}
+
+/// Which docs to keep in a filtered tree; unset fields match anything
+#[derive(Debug, Clone, Default)]
+pub struct DocFilter {
+    pub status: Option<String>,
+    pub owner: Option<String>,
+    pub tag: Option<String>,
+}
+
+impl DocFilter {
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none() && self.owner.is_none() && self.tag.is_none()
+    }
+
+    pub fn matches(&self, metadata: Option<&DocMetadata>) -> bool {
+        let field_matches = |wanted: &Option<String>, actual: Option<&String>| match wanted {
+            Some(wanted) => actual.is_some_and(|a| a.eq_ignore_ascii_case(wanted)),
+            None => true,
+        };
+
+        field_matches(&self.status, metadata.and_then(|m| m.status.as_ref()))
+            && field_matches(&self.owner, metadata.and_then(|m| m.owner.as_ref()))
+            && match &self.tag {
+                Some(tag) => metadata.is_some_and(|m| m.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))),
+                None => true,
+            }
+    }
+}
+
+/// Drop files that don't match and folders left empty; doc roots always stay
+pub fn filter_tree(node: DocsNode, filter: &DocFilter) -> Option<DocsNode> {
+    if filter.is_empty() {
+        return Some(node);
+    }
+    if node.is_file {
+        return filter.matches(node.metadata.as_ref()).then_some(node);
+    }
+
+    let children: Vec<DocsNode> = node.children.into_iter().filter_map(|child| filter_tree(child, filter)).collect();
+
+    if children.is_empty() && !node.is_root && node.path != "." {
+        return None;
+    }
+    Some(DocsNode { children, ..node })
+}
+
+/// Sort files within each folder by a metadata field; folders stay first, by name.
+///
+/// Files missing the field go last in either order, and ties fall back to the name.
+pub fn sort_tree(node: &mut DocsNode, field: DocSortField, order: SortOrder) {
+    node.children.sort_by(|a, b| compare(a, b, field, order));
+    for child in &mut node.children {
+        sort_tree(child, field, order);
+    }
+}
+
+fn compare(a: &DocsNode, b: &DocsNode, field: DocSortField, order: SortOrder) -> Ordering {
+    let by_name = || a.name.to_lowercase().cmp(&b.name.to_lowercase());
+
+    if a.is_file != b.is_file {
+        return a.is_file.cmp(&b.is_file); // Folders first
+    }
+    if !a.is_file {
+        return by_name();
+    }
+
+    let (key_a, key_b) = (sort_key(a, field), sort_key(b, field));
+    let ordering = match (key_a, key_b) {
+        (Some(x), Some(y)) => match order {
+            SortOrder::Asc => x.cmp(&y),
+            SortOrder::Desc => y.cmp(&x),
+        },
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    };
+
+    ordering.then_with(by_name)
+}
+
+fn sort_key(node: &DocsNode, field: DocSortField) -> Option<String> {
+    let metadata = node.metadata.as_ref();
+    let value = match field {
+        DocSortField::Name => Some(&node.name),
+        DocSortField::Title => metadata.and_then(|m| m.title.as_ref()),
+        DocSortField::Status => metadata.and_then(|m| m.status.as_ref()),
+        DocSortField::Owner => metadata.and_then(|m| m.owner.as_ref()),
+        DocSortField::Updated => metadata.and_then(|m| m.updated.as_ref()),
+    };
+    value.map(|v| v.to_lowercase())
+}
+
+/// The YAML between the delimiter lines of a block
+fn yaml_text(block: &str) -> &str {
+    let start = block.find('\n').map(|i| i + 1).unwrap_or(block.len());
+    let end = block.trim_end().rfind('\n').map(|i| i + 1).unwrap_or(start);
+    &block[start..end.max(start)]
+}
+
+fn from_mapping(mapping: serde_yaml::Mapping) -> DocMetadata {
+    let mut metadata = DocMetadata::default();
+
+    for (key, value) in mapping {
+        let Some(key) = scalar(&key) else {
+            continue;
+        };
+
+        match (key.as_str(), scalar(&value)) {
+            ("title", Some(text)) => metadata.title = Some(text),
+            ("status", Some(text)) => metadata.status = Some(text),
+            ("owner", Some(text)) => metadata.owner = Some(text),
+            ("updated", Some(text)) => metadata.updated = Some(text),
+            ("tags", Some(text)) => {
+                // `tags: a, b` as well as a list
+                metadata.tags = text.split(',').map(str::trim).filter(|t| !t.is_empty()).map(str::to_string).collect();
+            }
+            ("tags", None) if value.is_sequence() => {
+                metadata.tags = value.as_sequence().into_iter().flatten().filter_map(scalar).collect();
+            }
+            _ => {
+                if let Ok(json) = serde_json::to_value(&value) {
+                    metadata.extra.insert(key, json);
+                }
+            }
+        }
+    }
+
+    metadata
+}
+
+/// Strings, numbers and booleans as text; `None` for anything structured
+fn scalar(value: &Yaml) -> Option<String> {
+    match value {
+        Yaml::String(text) => Some(text.clone()),
+        Yaml::Number(number) => Some(number.to_string()),
+        Yaml::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
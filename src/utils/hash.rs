@@ -0,0 +1,27 @@
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+
+/// Calculate the SHA-256 hash of file content as a hex string
+pub fn calculate_hash(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Read a file and hash its content
+pub fn file_hash(path: &Path) -> Result<String> {
+    let content = fs::read_to_string(path)?;
+    Ok(calculate_hash(&content))
+}
+
+/// Format a hash as a strong ETag header value
+pub fn to_etag(hash: &str) -> String {
+    format!("\"{}\"", hash)
+}
+
+/// Extract the hash from an `If-Match` / `ETag` header value
+pub fn from_etag(value: &str) -> String {
+    value.trim().trim_start_matches("W/").trim_matches('"').to_string()
+}
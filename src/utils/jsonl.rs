@@ -0,0 +1,51 @@
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use super::writer;
+
+/// Take an index's lock, even if a thread panicked while holding it; the index itself is
+/// only ever appended to or atomically replaced, so it can't be left half-written
+pub fn lock(index_lock: &'static Mutex<()>) -> MutexGuard<'static, ()> {
+    index_lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Every record in a JSON-lines file; a missing file has none
+pub fn read<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let records = fs::read_to_string(path)?
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok()) // Skip torn or malformed lines
+        .collect();
+
+    Ok(records)
+}
+
+/// Add one record, creating the file and its folder if needed
+pub fn append<T: Serialize>(path: &Path, record: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+/// Replace the file with exactly `records`
+pub fn write<T: Serialize>(path: &Path, records: &[T]) -> Result<()> {
+    let mut content = String::new();
+    for record in records {
+        content.push_str(&serde_json::to_string(record)?);
+        content.push('\n');
+    }
+
+    writer::write_atomic(path, content.as_bytes())
+}
@@ -0,0 +1,387 @@
+use std::collections::{HashMap, HashSet};
+
+use super::sandbox::MEMORY_FILE_NAMES;
+use super::search::parse_heading;
+use crate::models::{LinkChange, LinkInfo, LinkKind, LinkStatus};
+
+/// A link target found in markdown source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkRef {
+    pub kind: LinkKind,
+    pub line: usize,          // 1-based
+    pub target: String,       // As written, including any #fragment or ?query
+    pub span: (usize, usize), // Byte range of `target` within its line
+}
+
+/// Files moved or renamed, as project-relative paths; a directory entry covers everything below it
+#[derive(Debug, Clone, Default)]
+pub struct MoveMap {
+    entries: Vec<(String, String)>,
+}
+
+impl MoveMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, from: &str, to: &str) {
+        self.entries.push((from.trim_end_matches('/').to_string(), to.trim_end_matches('/').to_string()));
+    }
+
+    /// Where `path` ends up after the moves, or `None` if it doesn't move
+    pub fn map(&self, path: &str) -> Option<String> {
+        self.entries.iter().find_map(|(from, to)| {
+            if path == from {
+                Some(to.clone())
+            } else {
+                path.strip_prefix(from.as_str())
+                    .filter(|rest| rest.starts_with('/'))
+                    .map(|rest| format!("{}{}", to, rest))
+            }
+        })
+    }
+}
+
+/// Whether `@imports` are meaningful in this file
+pub fn supports_imports(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    MEMORY_FILE_NAMES.contains(&name)
+}
+
+/// Find local link targets, skipping fenced code blocks and inline code
+pub fn extract_links(content: &str, imports: bool) -> Vec<LinkRef> {
+    scan(content, imports, false)
+}
+
+/// Like `extract_links`, but also keeps same-file `#anchor` links
+pub fn extract_references(content: &str, imports: bool) -> Vec<LinkRef> {
+    scan(content, imports, true)
+}
+
+fn scan(content: &str, imports: bool, anchors: bool) -> Vec<LinkRef> {
+    let mut links = Vec::new();
+    let mut in_fence = false;
+
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let code = code_spans(line);
+        let in_code = |pos: usize| code.iter().any(|(start, end)| pos >= *start && pos < *end);
+        let mut push = |kind, span: (usize, usize)| {
+            let target = &line[span.0..span.1];
+            if is_local_target(target) || (anchors && target.len() > 1 && target.starts_with('#')) {
+                links.push(LinkRef { kind, line: index + 1, target: target.to_string(), span });
+            }
+        };
+
+        if let Some(span) = reference_target(line) {
+            push(LinkKind::Reference, span);
+            continue;
+        }
+
+        let mut search = 0;
+        while let Some(found) = line[search..].find("](") {
+            let open = search + found + 2;
+            search = open;
+            if in_code(open) {
+                continue;
+            }
+            if let Some(span) = inline_target(line, open) {
+                push(LinkKind::Inline, span);
+            }
+        }
+
+        if imports {
+            for (pos, _) in line.match_indices('@') {
+                let at_boundary = pos == 0 || line[..pos].ends_with(char::is_whitespace);
+                if at_boundary && !in_code(pos) {
+                    let rest = &line[pos + 1..];
+                    let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                    let target = rest[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')']);
+                    if !target.is_empty() {
+                        push(LinkKind::Import, (pos + 1, pos + 1 + target.len()));
+                    }
+                }
+            }
+        }
+    }
+
+    links
+}
+
+/// GitHub-style anchor for a heading: lowercased, punctuation dropped, spaces as hyphens
+pub fn slugify(heading: &str) -> String {
+    heading
+        .trim()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Anchors a file's headings can be linked by; repeated headings get `-1`, `-2`, ...
+pub fn heading_anchors(content: &str) -> HashSet<String> {
+    let mut anchors = HashSet::new();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let mut in_fence = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        if let Some((_, text)) = parse_heading(line) {
+            let slug = slugify(&text);
+            let count = counts.entry(slug.clone()).or_default();
+            anchors.insert(if *count == 0 { slug } else { format!("{}-{}", slug, count) });
+            *count += 1;
+        }
+    }
+
+    anchors
+}
+
+/// Resolve and check every link in `content`, a file at the project-relative `source`.
+///
+/// `exists` answers whether a project-relative path exists; `anchors` returns the heading
+/// anchors of a markdown file, or `None` when it can't be read.
+pub fn check_links(
+    source: &str,
+    content: &str,
+    exists: impl Fn(&str) -> bool,
+    mut anchors: impl FnMut(&str) -> Option<HashSet<String>>,
+) -> Vec<LinkInfo> {
+    let imports = supports_imports(source);
+
+    extract_references(content, imports)
+        .into_iter()
+        // `@name` is usually a mention rather than an import
+        .filter(|link| link.kind != LinkKind::Import || link.target.contains(['/', '.']))
+        .map(|link| {
+            let (path, suffix) = split_fragment(&link.target);
+            let anchor = suffix
+                .strip_prefix('#')
+                .map(|a| a.split('?').next().unwrap_or(a).to_string())
+                .filter(|a| !a.is_empty());
+
+            let resolved = if path.is_empty() { Some(source.to_string()) } else { resolve_target(source, path) };
+
+            let status = match &resolved {
+                None => LinkStatus::OutsideProject,
+                Some(target) if !exists(target) => LinkStatus::MissingFile,
+                Some(target) => match &anchor {
+                    Some(anchor) if is_markdown(target) => match anchors(target) {
+                        Some(found) if !found.contains(&anchor.to_lowercase()) => LinkStatus::MissingAnchor,
+                        _ => LinkStatus::Ok,
+                    },
+                    _ => LinkStatus::Ok,
+                },
+            };
+
+            LinkInfo {
+                source: source.to_string(),
+                line: link.line,
+                kind: link.kind,
+                target: link.target,
+                resolved,
+                anchor,
+                status,
+            }
+        })
+        .collect()
+}
+
+/// Split a target into its path and any `#fragment` or `?query` suffix
+pub fn split_fragment(target: &str) -> (&str, &str) {
+    let cut = target.find(['#', '?']).unwrap_or(target.len());
+    target.split_at(cut)
+}
+
+/// Resolve a link path against the directory of `file`; `None` if it climbs out of the project
+pub fn resolve_target(file: &str, target: &str) -> Option<String> {
+    let mut parts: Vec<&str> = file.split('/').collect();
+    parts.pop(); // The file name itself
+
+    for part in target.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            part => parts.push(part),
+        }
+    }
+
+    Some(parts.join("/"))
+}
+
+/// Relative link from `file` to the project-relative path `target`
+pub fn relative_to(file: &str, target: &str) -> String {
+    let from: Vec<&str> = file.split('/').collect();
+    let from = &from[..from.len() - 1];
+    let to: Vec<&str> = target.split('/').filter(|p| !p.is_empty()).collect();
+
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let mut parts = vec![".."; from.len() - common];
+    parts.extend(&to[common..]);
+
+    if parts.is_empty() {
+        ".".to_string()
+    } else {
+        parts.join("/")
+    }
+}
+
+/// Rewrite the links in a file at `old_path` that is ending up at `new_path`.
+///
+/// Links whose target moved are pointed at the new location, and if the file itself
+/// moved its relative links are recomputed. Targets that didn't exist are left alone.
+pub fn rewrite_links(
+    content: &str,
+    old_path: &str,
+    new_path: &str,
+    moves: &MoveMap,
+    exists: impl Fn(&str) -> bool,
+) -> (String, Vec<LinkChange>) {
+    let file_moved = old_path != new_path;
+    let links = extract_links(content, supports_imports(old_path));
+    let mut changes = Vec::new();
+    let mut replacements: Vec<(usize, (usize, usize), String)> = Vec::new();
+
+    for link in links {
+        let (path, suffix) = split_fragment(&link.target);
+        let Some(old_target) = resolve_target(old_path, path) else {
+            continue;
+        };
+
+        let mapped = moves.map(&old_target);
+        if (mapped.is_none() && !file_moved) || !exists(&old_target) {
+            continue;
+        }
+
+        let new_target = mapped.unwrap_or(old_target);
+        let mut relative = relative_to(new_path, &new_target);
+        if path.starts_with("./") && !relative.starts_with('.') {
+            relative = format!("./{}", relative);
+        }
+        if path.ends_with('/') && !relative.ends_with('/') {
+            relative.push('/');
+        }
+
+        let replacement = format!("{}{}", relative, suffix);
+        if replacement != link.target {
+            changes.push(LinkChange { line: link.line, before: link.target.clone(), after: replacement.clone() });
+            replacements.push((link.line, link.span, replacement));
+        }
+    }
+
+    if replacements.is_empty() {
+        return (content.to_string(), changes);
+    }
+
+    let mut output = String::with_capacity(content.len());
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        let mut line = line.to_string();
+        // Right to left so earlier spans stay valid
+        for (_, (start, end), replacement) in replacements.iter().rev().filter(|(l, _, _)| *l == index + 1) {
+            line.replace_range(start..end, replacement);
+        }
+        output.push_str(&line);
+    }
+
+    (output, changes)
+}
+
+fn is_markdown(path: &str) -> bool {
+    path.to_ascii_lowercase().ends_with(".md")
+}
+
+fn is_local_target(target: &str) -> bool {
+    !(target.is_empty()
+        || target.starts_with('#')
+        || target.starts_with('/')
+        || target.starts_with('~')
+        || target.contains("://")
+        || target.starts_with("mailto:"))
+}
+
+/// Byte ranges covered by inline code spans on a line
+fn code_spans(line: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut open: Option<(usize, usize)> = None; // (start, backtick run length)
+    let bytes = line.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] != b'`' {
+            i += 1;
+            continue;
+        }
+        let run = bytes[i..].iter().take_while(|&&b| b == b'`').count();
+        match open {
+            Some((start, len)) if len == run => {
+                spans.push((start, i + run));
+                open = None;
+            }
+            None => open = Some((i, run)),
+            _ => {}
+        }
+        i += run;
+    }
+
+    spans
+}
+
+/// Target of a reference definition such as `[docs]: ./guide.md "Title"`
+fn reference_target(line: &str) -> Option<(usize, usize)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 || !line[indent..].starts_with('[') {
+        return None;
+    }
+
+    let close = line[indent..].find(']')? + indent;
+    if !line[close + 1..].starts_with(':') {
+        return None;
+    }
+    target_span(line, close + 2)
+}
+
+/// Target of an inline link whose `(` ends just before `open`
+fn inline_target(line: &str, open: usize) -> Option<(usize, usize)> {
+    target_span(line, open).filter(|(_, end)| {
+        // The destination must be followed by a title or the closing paren
+        let rest = line[*end..].trim_start_matches('>');
+        rest.starts_with(')') || rest.starts_with(char::is_whitespace)
+    })
+}
+
+fn target_span(line: &str, from: usize) -> Option<(usize, usize)> {
+    let rest = &line[from..];
+    let start = from + (rest.len() - rest.trim_start_matches([' ', '\t']).len());
+
+    if line[start..].starts_with('<') {
+        let end = line[start + 1..].find('>')? + start + 1;
+        return Some((start + 1, end));
+    }
+
+    let end = line[start..].find(|c: char| c.is_whitespace() || c == ')').map(|i| start + i).unwrap_or(line.len());
+
+    (end > start).then_some((start, end))
+}
@@ -1,20 +1,206 @@
-use pulldown_cmark::{html, Options, Parser};
+use ammonia::Builder;
 use anyhow::Result;
+use pulldown_cmark::escape::escape_html;
+use pulldown_cmark::{html, CodeBlockKind, Event, Options, Parser, Tag};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use syntect::highlighting::ThemeSet;
+use syntect::html::{css_for_theme_with_class_style, ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
 
-/// Convert markdown text to HTML
-pub fn markdown_to_html(markdown: &str) -> String {
+use super::links::slugify;
+use crate::models::TocEntry;
+
+/// Highlighted tokens get `hl-`-prefixed scope classes, styled by [`highlight_css`]
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+const THEME: &str = "InspiredGitHub";
+
+/// Every `id` in rendered HTML starts with this, so a heading or raw HTML can't take over one of the dashboard's
+pub const ID_PREFIX: &str = "user-content-";
+
+static SYNTAXES: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+
+/// What rendered HTML may contain: ammonia's safe defaults plus the markup `render` itself produces
+static SANITIZER: LazyLock<Builder<'static>> = LazyLock::new(|| {
+    let mut builder = Builder::default();
+    builder
+        .add_tags(["input"])
+        .add_tag_attributes("input", ["type", "checked", "disabled"])
+        .add_tag_attributes("pre", ["class"])
+        .add_tag_attributes("code", ["class"])
+        .add_tag_attributes("span", ["class"])
+        .add_tag_attributes("sup", ["class"])
+        .add_tag_attributes("div", ["class", "id"])
+        .add_tag_attributes("th", ["style"])
+        .add_tag_attributes("td", ["style"])
+        .attribute_filter(|element, attribute, value| match (element, attribute) {
+            // Task list checkboxes only; nothing a form could be built from
+            ("input", "type") => (value == "checkbox").then_some(Cow::Borrowed(value)),
+            // Table column alignment is the only inline style
+            (_, "style") => matches!(value, "text-align: left" | "text-align: center" | "text-align: right")
+                .then_some(Cow::Borrowed(value)),
+            // Only the classes `render` emits, so raw HTML can't borrow the dashboard's styling
+            (_, "class") => {
+                let classes: Vec<&str> = value.split_ascii_whitespace().filter(|c| is_rendered_class(c)).collect();
+                (!classes.is_empty()).then(|| Cow::Owned(classes.join(" ")))
+            }
+            // In-page links follow their target's id into the prefix
+            ("a", "href") if value.starts_with('#') && !value[1..].starts_with(ID_PREFIX) => {
+                Some(Cow::Owned(format!("#{}{}", ID_PREFIX, &value[1..])))
+            }
+            _ => Some(Cow::Borrowed(value)),
+        })
+        .id_prefix(Some(ID_PREFIX));
+    for heading in ["h1", "h2", "h3", "h4", "h5", "h6"] {
+        builder.add_tag_attributes(heading, ["id"]);
+    }
+    builder
+});
+
+/// Markdown rendered to HTML, with an outline of its headings
+pub struct Rendered {
+    pub html: String,
+    pub toc: Vec<TocEntry>,
+}
+
+/// A heading whose text is still being collected
+struct OpenHeading {
+    level: u8,
+    start: usize, // Where its opening tag goes in the event list
+    text: String,
+}
+
+fn options() -> Options {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_STRIKETHROUGH);
     options.insert(Options::ENABLE_TABLES);
     options.insert(Options::ENABLE_FOOTNOTES);
     options.insert(Options::ENABLE_TASKLISTS);
-    
-    let parser = Parser::new_ext(markdown, options);
-    
+    options
+}
+
+/// Convert markdown to HTML with highlighted code blocks and linkable headings.
+///
+/// Raw HTML in the markdown is sanitized: scripts, event handlers and `javascript:` URLs are
+/// dropped, since the dashboard shows the result in an origin that can write to the project.
+/// Headings get the same slugs links use (`-1`, `-2`, ... for repeats), behind [`ID_PREFIX`] like
+/// every other id, and are listed in the TOC.
+/// Mermaid blocks are left as escaped source in `<pre class="mermaid">` for the client to draw.
+pub fn render(markdown: &str) -> Rendered {
+    let mut events = Vec::new();
+    let mut toc = Vec::new();
+    let mut anchors: HashMap<String, usize> = HashMap::new();
+    let mut heading: Option<OpenHeading> = None;
+    let mut code: Option<(String, String)> = None; // Language and source of a fenced block
+
+    for event in Parser::new_ext(markdown, options()) {
+        match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
+                let language = info.split(|c: char| c.is_whitespace() || c == ',').next().unwrap_or_default();
+                code = Some((language.to_string(), String::new()));
+            }
+            Event::Text(text) if code.is_some() => {
+                if let Some((_, source)) = &mut code {
+                    source.push_str(&text);
+                }
+            }
+            Event::End(Tag::CodeBlock(CodeBlockKind::Fenced(_))) => {
+                if let Some((language, source)) = code.take() {
+                    events.push(Event::Html(fenced_block(&language, &source).into()));
+                }
+            }
+            Event::Start(Tag::Heading(level, ..)) => {
+                heading = Some(OpenHeading { level: level as u8, start: events.len(), text: String::new() });
+            }
+            Event::End(Tag::Heading(..)) => {
+                if let Some(open) = heading.take() {
+                    let anchor = unique_anchor(&mut anchors, &open.text);
+                    let tag = format!("<h{} id=\"{}\">", open.level, escape(&anchor));
+                    events.insert(open.start, Event::Html(tag.into()));
+                    events.push(Event::Html(format!("</h{}>\n", open.level).into()));
+                    toc.push(TocEntry { level: open.level, text: open.text.trim().to_string(), anchor });
+                }
+            }
+            Event::Text(ref text) | Event::Code(ref text) if heading.is_some() => {
+                if let Some(open) = &mut heading {
+                    open.text.push_str(text);
+                }
+                events.push(event);
+            }
+            event => events.push(event),
+        }
+    }
+
     let mut html_output = String::new();
-    html::push_html(&mut html_output, parser);
-    
-    html_output
+    html::push_html(&mut html_output, events.into_iter());
+    Rendered { html: SANITIZER.clean(&html_output).to_string(), toc }
+}
+
+/// Just the HTML of [`render`], for callers that don't need the TOC
+pub fn markdown_to_html(markdown: &str) -> String {
+    render(markdown).html
+}
+
+/// Stylesheet for the classes highlighted code blocks use
+pub fn highlight_css() -> &'static str {
+    static CSS: LazyLock<String> = LazyLock::new(|| {
+        let themes = ThemeSet::load_defaults();
+        css_for_theme_with_class_style(&themes.themes[THEME], CLASS_STYLE).unwrap_or_default()
+    });
+    &CSS
+}
+
+/// Highlighting scopes, fence languages, mermaid blocks and pulldown-cmark's footnote markup
+fn is_rendered_class(class: &str) -> bool {
+    class.starts_with("hl-")
+        || class.starts_with("language-")
+        || matches!(class, "mermaid" | "footnote-reference" | "footnote-definition" | "footnote-definition-label")
+}
+
+fn unique_anchor(anchors: &mut HashMap<String, usize>, text: &str) -> String {
+    let slug = slugify(text);
+    let slug = if slug.is_empty() { "section".to_string() } else { slug };
+    let seen = anchors.entry(slug.clone()).or_insert(0);
+    let anchor = if *seen == 0 { slug } else { format!("{}-{}", slug, seen) };
+    *seen += 1;
+    format!("{}{}", ID_PREFIX, anchor)
+}
+
+fn fenced_block(language: &str, source: &str) -> String {
+    let class = if language.is_empty() { String::new() } else { format!(" class=\"language-{}\"", escape(language)) };
+    if language.eq_ignore_ascii_case("mermaid") {
+        return format!("<pre class=\"mermaid\"><code{}>{}</code></pre>\n", class, escape(source));
+    }
+
+    let body = find_syntax(language).and_then(|syntax| highlight(syntax, source)).unwrap_or_else(|| escape(source));
+    format!("<pre><code{}>{}</code></pre>\n", class, body)
+}
+
+/// The bundled syntaxes, plus a few common fence names they don't know
+fn find_syntax(language: &str) -> Option<&'static SyntaxReference> {
+    let token = match language.to_ascii_lowercase().as_str() {
+        "ts" | "typescript" | "tsx" | "jsx" => "js".to_string(),
+        "shell" | "zsh" | "console" => "sh".to_string(),
+        "" => return None,
+        other => other.to_string(),
+    };
+    SYNTAXES.find_syntax_by_token(&token)
+}
+
+fn highlight(syntax: &SyntaxReference, source: &str) -> Option<String> {
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, &SYNTAXES, CLASS_STYLE);
+    for line in LinesWithEndings::from(source) {
+        generator.parse_html_for_line_which_includes_newline(line).ok()?;
+    }
+    Some(generator.finalize())
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    let _ = escape_html(&mut escaped, text);
+    escaped
 }
 
 /// Convert HTML back to markdown
@@ -22,4 +208,4 @@ pub fn html_to_markdown(html: &str) -> Result<String> {
     // Use html2md for conversion
     let markdown = html2md::parse_html(html);
     Ok(markdown)
-}
\ No newline at end of file
+}
@@ -0,0 +1,210 @@
+use crate::models::{HunkChoice, HunkResolution, MergeConflict, MergeResult};
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+
+const MARKER_MINE: &str = "<<<<<<< mine";
+const MARKER_SEPARATOR: &str = "=======";
+const MARKER_THEIRS: &str = ">>>>>>> theirs";
+
+/// A run of merged output: either agreed lines or a conflicting hunk
+enum Region<'a> {
+    Resolved(Vec<&'a str>),
+    Conflict { base: Vec<&'a str>, mine: Vec<&'a str>, theirs: Vec<&'a str> },
+}
+
+/// Line-based three-way merge of `mine` and `theirs` against their common `base`
+pub fn merge3(base: &str, mine: &str, theirs: &str) -> MergeResult {
+    let regions = merge_regions(base, mine, theirs);
+
+    let mut content = String::new();
+    let mut conflicts = Vec::new();
+    let mut line = 1;
+
+    for region in &regions {
+        match region {
+            Region::Resolved(lines) => {
+                line += lines.len();
+                push_lines(&mut content, lines);
+            }
+            Region::Conflict { base, mine, theirs } => {
+                conflicts.push(MergeConflict {
+                    id: conflicts.len(),
+                    start_line: line,
+                    base: base.concat(),
+                    mine: mine.concat(),
+                    theirs: theirs.concat(),
+                });
+                line += mine.len() + theirs.len() + 3;
+
+                push_marker(&mut content, MARKER_MINE);
+                push_lines(&mut content, mine);
+                push_marker(&mut content, MARKER_SEPARATOR);
+                push_lines(&mut content, theirs);
+                push_marker(&mut content, MARKER_THEIRS);
+            }
+        }
+    }
+
+    MergeResult { clean: conflicts.is_empty(), content, conflicts }
+}
+
+/// Re-run the merge and apply a resolution to every conflicting hunk.
+///
+/// Returns the ids of hunks left unresolved if any remain.
+pub fn apply_resolutions(
+    base: &str,
+    mine: &str,
+    theirs: &str,
+    resolutions: &[HunkResolution],
+) -> Result<String, Vec<usize>> {
+    let regions = merge_regions(base, mine, theirs);
+
+    let mut content = String::new();
+    let mut unresolved = Vec::new();
+    let mut hunk = 0;
+
+    for region in &regions {
+        match region {
+            Region::Resolved(lines) => push_lines(&mut content, lines),
+            Region::Conflict { base, mine, theirs } => {
+                let resolution = resolutions.iter().find(|r| r.hunk == hunk);
+
+                match resolution {
+                    Some(HunkResolution { content: Some(text), .. }) => {
+                        if !text.is_empty() {
+                            push_line(&mut content, text);
+                        }
+                    }
+                    Some(HunkResolution { take: Some(choice), .. }) => match choice {
+                        HunkChoice::Mine => push_lines(&mut content, mine),
+                        HunkChoice::Theirs => push_lines(&mut content, theirs),
+                        HunkChoice::Base => push_lines(&mut content, base),
+                        HunkChoice::Both => {
+                            push_lines(&mut content, mine);
+                            push_lines(&mut content, theirs);
+                        }
+                    },
+                    _ => unresolved.push(hunk),
+                }
+
+                hunk += 1;
+            }
+        }
+    }
+
+    if unresolved.is_empty() {
+        Ok(content)
+    } else {
+        Err(unresolved)
+    }
+}
+
+fn merge_regions<'a>(base: &'a str, mine: &'a str, theirs: &'a str) -> Vec<Region<'a>> {
+    let base_lines: Vec<&str> = base.split_inclusive('\n').collect();
+    let mine_lines: Vec<&str> = mine.split_inclusive('\n').collect();
+    let theirs_lines: Vec<&str> = theirs.split_inclusive('\n').collect();
+
+    let match_mine = match_lines(&base_lines, &mine_lines);
+    let match_theirs = match_lines(&base_lines, &theirs_lines);
+
+    let mut regions = Vec::new();
+    let (mut o, mut a, mut b) = (0, 0, 0);
+
+    loop {
+        // Lines unchanged on both sides since the last unstable chunk
+        let mut stable = 0;
+        while o + stable < base_lines.len()
+            && match_mine[o + stable] == Some(a + stable)
+            && match_theirs[o + stable] == Some(b + stable)
+        {
+            stable += 1;
+        }
+
+        if stable > 0 {
+            push_resolved(&mut regions, &base_lines[o..o + stable]);
+            o += stable;
+            a += stable;
+            b += stable;
+            continue;
+        }
+
+        // Next base line that both sides kept marks the end of the unstable chunk
+        let next = (o..base_lines.len())
+            .find_map(|i| match (match_mine[i], match_theirs[i]) {
+                (Some(ma), Some(mb)) => Some((i, ma, mb)),
+                _ => None,
+            })
+            .unwrap_or((base_lines.len(), mine_lines.len(), theirs_lines.len()));
+
+        let (next_o, next_a, next_b) = next;
+        if next_o == o && next_a == a && next_b == b {
+            break;
+        }
+
+        let chunk_base = &base_lines[o..next_o];
+        let chunk_mine = &mine_lines[a..next_a];
+        let chunk_theirs = &theirs_lines[b..next_b];
+
+        if chunk_mine == chunk_base {
+            push_resolved(&mut regions, chunk_theirs);
+        } else if chunk_theirs == chunk_base || chunk_mine == chunk_theirs {
+            push_resolved(&mut regions, chunk_mine);
+        } else {
+            regions.push(Region::Conflict {
+                base: chunk_base.to_vec(),
+                mine: chunk_mine.to_vec(),
+                theirs: chunk_theirs.to_vec(),
+            });
+        }
+
+        o = next_o;
+        a = next_a;
+        b = next_b;
+    }
+
+    regions
+}
+
+/// For each base line, the index of the line it is matched to in `other`
+fn match_lines(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+    let mut matches = vec![None; base.len()];
+
+    for op in capture_diff_slices(Algorithm::Myers, base, other) {
+        if let DiffOp::Equal { old_index, new_index, len } = op {
+            for i in 0..len {
+                matches[old_index + i] = Some(new_index + i);
+            }
+        }
+    }
+
+    matches
+}
+
+fn push_resolved<'a>(regions: &mut Vec<Region<'a>>, lines: &[&'a str]) {
+    if lines.is_empty() {
+        return;
+    }
+    if let Some(Region::Resolved(existing)) = regions.last_mut() {
+        existing.extend_from_slice(lines);
+    } else {
+        regions.push(Region::Resolved(lines.to_vec()));
+    }
+}
+
+fn push_lines(out: &mut String, lines: &[&str]) {
+    for line in lines {
+        push_line(out, line);
+    }
+}
+
+/// Append a line, making sure whatever precedes it is newline-terminated
+fn push_line(out: &mut String, line: &str) {
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push_str(line);
+}
+
+fn push_marker(out: &mut String, marker: &str) {
+    push_line(out, marker);
+    out.push('\n');
+}
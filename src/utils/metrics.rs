@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{LazyLock, Mutex};
+use std::time::Instant;
+
+pub const HTTP_REQUESTS: &str = "cc_atlas_http_requests_total";
+pub const HTTP_DURATION: &str = "cc_atlas_http_request_duration_seconds";
+pub const SCAN_DURATION: &str = "cc_atlas_scan_duration_seconds";
+pub const CACHE_LOOKUPS: &str = "cc_atlas_cache_lookups_total";
+
+const DESCRIPTIONS: &[(&str, &str)] = &[
+    (HTTP_REQUESTS, "HTTP requests handled, by method, route and status"),
+    (HTTP_DURATION, "Time to handle an HTTP request"),
+    (SCAN_DURATION, "Time spent in each analyzer phase"),
+    (CACHE_LOOKUPS, "Cache lookups, by cache and whether they hit"),
+];
+
+/// Upper bounds in seconds; wide enough for a big monorepo scan
+const BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+type Labels = Vec<(&'static str, String)>;
+
+enum Series {
+    Counter(u64),
+    Histogram { counts: Vec<u64>, sum: f64, count: u64 },
+}
+
+/// Counters and histograms, rendered in the Prometheus text format
+#[derive(Default)]
+pub struct Registry {
+    series: Mutex<BTreeMap<(&'static str, Labels), Series>>,
+}
+
+static GLOBAL: LazyLock<Registry> = LazyLock::new(Registry::default);
+
+impl Registry {
+    pub fn increment(&self, name: &'static str, labels: &[(&'static str, &str)]) {
+        let mut series = self.series.lock().unwrap();
+        let entry = series.entry((name, owned(labels))).or_insert(Series::Counter(0));
+        if let Series::Counter(value) = entry {
+            *value += 1;
+        }
+    }
+
+    pub fn observe(&self, name: &'static str, labels: &[(&'static str, &str)], seconds: f64) {
+        let mut series = self.series.lock().unwrap();
+        let entry = series.entry((name, owned(labels))).or_insert_with(|| Series::Histogram {
+            counts: vec![0; BUCKETS.len()],
+            sum: 0.0,
+            count: 0,
+        });
+        if let Series::Histogram { counts, sum, count } = entry {
+            for (bucket, bound) in counts.iter_mut().zip(BUCKETS) {
+                if seconds <= *bound {
+                    *bucket += 1;
+                }
+            }
+            *sum += seconds;
+            *count += 1;
+        }
+    }
+
+    /// A counter's value, or how many observations a histogram has had
+    pub fn count(&self, name: &'static str, labels: &[(&'static str, &str)]) -> u64 {
+        match self.series.lock().unwrap().get(&(name, owned(labels))) {
+            Some(Series::Counter(value)) => *value,
+            Some(Series::Histogram { count, .. }) => *count,
+            None => 0,
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let series = self.series.lock().unwrap();
+        let mut out = String::new();
+        let mut current = None;
+
+        for ((name, labels), value) in series.iter() {
+            if current != Some(*name) {
+                current = Some(*name);
+                let help = DESCRIPTIONS.iter().find(|(n, _)| n == name).map_or("", |(_, h)| *h);
+                let kind = if matches!(value, Series::Counter(_)) { "counter" } else { "histogram" };
+                let _ = writeln!(out, "# HELP {} {}", name, help);
+                let _ = writeln!(out, "# TYPE {} {}", name, kind);
+            }
+
+            match value {
+                Series::Counter(value) => {
+                    let _ = writeln!(out, "{}{} {}", name, format_labels(labels, None), value);
+                }
+                Series::Histogram { counts, sum, count } => {
+                    for (bucket, bound) in counts.iter().zip(BUCKETS) {
+                        let le = bound.to_string();
+                        let _ = writeln!(out, "{}_bucket{} {}", name, format_labels(labels, Some(&le)), bucket);
+                    }
+                    let _ = writeln!(out, "{}_bucket{} {}", name, format_labels(labels, Some("+Inf")), count);
+                    let _ = writeln!(out, "{}_sum{} {}", name, format_labels(labels, None), sum);
+                    let _ = writeln!(out, "{}_count{} {}", name, format_labels(labels, None), count);
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// The process-wide registry served at `/metrics`
+pub fn global() -> &'static Registry {
+    &GLOBAL
+}
+
+/// Run one analyzer phase, recording how long it took
+pub fn time_phase<T>(phase: &'static str, f: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = f();
+    let elapsed = started.elapsed();
+
+    GLOBAL.observe(SCAN_DURATION, &[("phase", phase)], elapsed.as_secs_f64());
+    tracing::debug!(phase, elapsed_ms = elapsed.as_secs_f64() * 1000.0, "scan phase finished");
+    result
+}
+
+pub fn cache_lookup(cache: &'static str, hit: bool) {
+    GLOBAL.increment(CACHE_LOOKUPS, &[("cache", cache), ("result", if hit { "hit" } else { "miss" })]);
+}
+
+fn owned(labels: &[(&'static str, &str)]) -> Labels {
+    labels.iter().map(|(k, v)| (*k, v.to_string())).collect()
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut parts: Vec<String> = labels.iter().map(|(key, value)| format!("{}=\"{}\"", key, escape(value))).collect();
+    if let Some(le) = le {
+        parts.push(format!("le=\"{}\"", le));
+    }
+
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", parts.join(","))
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
@@ -1,2 +1,15 @@
+pub mod adr;
+pub mod batch;
+pub mod front_matter;
+pub mod hash;
+pub mod jsonl;
+pub mod links;
 pub mod markdown;
+pub mod merge;
+pub mod metrics;
 pub mod paths;
+pub mod plans;
+pub mod sandbox;
+pub mod search;
+pub mod time;
+pub mod writer;
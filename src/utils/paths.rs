@@ -1,52 +1,67 @@
+use super::{hash, markdown, time};
+use crate::models::{DirectoryInfo, MemoryFileResponse, MemoryScope};
+use anyhow::{Context, Result};
+use std::fs;
 use std::path::{Path, PathBuf};
-use crate::models::{DirectoryInfo, MemoryFile, MemoryFileResponse};
-use super::markdown;
 
-pub fn convert_to_responses(files: Vec<MemoryFile>, project_root: &str) -> Vec<MemoryFileResponse> {
-    files
-        .into_iter()
-        .map(|file| {
-            let html = markdown::markdown_to_html(&file.content);
-            let parent_path = file
-                .path
-                .parent()
-                .and_then(|p| p.strip_prefix(project_root).ok())
-                .map(|p| format!("./{}", p.display()))
-                .unwrap_or_else(|| ".".to_string());
-
-            MemoryFileResponse {
-                path: file.relative_path.clone(),
-                content: file.content,
-                content_html: html,
-                exists: file.path.exists(),
-                parent_path,
-            }
-        })
-        .collect()
+/// Build the API view of a memory file; content and rendered HTML are only read when asked for
+pub fn memory_file_response(path: &Path, project_root: &str, include_content: bool) -> Result<MemoryFileResponse> {
+    let content = fs::read_to_string(path)?;
+    let metadata = fs::metadata(path)?;
+
+    let relative_path = path.strip_prefix(project_root).unwrap_or(path).to_string_lossy().to_string();
+    let parent_path = path
+        .parent()
+        .and_then(|p| p.strip_prefix(project_root).ok())
+        .map(|p| format!("./{}", p.display()))
+        .unwrap_or_else(|| ".".to_string());
+
+    let rendered = include_content.then(|| markdown::render(&content));
+    Ok(MemoryFileResponse {
+        scope: memory_scope(&relative_path),
+        content_hash: hash::calculate_hash(&content),
+        toc: rendered.as_ref().map(|r| r.toc.clone()),
+        content_html: rendered.map(|r| r.html),
+        content: include_content.then_some(content),
+        exists: true,
+        parent_path,
+        size: metadata.len(),
+        modified_at: metadata.modified().ok().map(|t| time::format_rfc3339(time::to_unix(t))),
+        path: relative_path,
+    })
+}
+
+/// Classify a project-relative memory file path
+pub fn memory_scope(relative_path: &str) -> MemoryScope {
+    let path = Path::new(relative_path.trim_start_matches("./"));
+
+    if path.file_name().is_some_and(|n| n == "CLAUDE.local.md") {
+        MemoryScope::Local
+    } else if path.parent().is_none_or(|p| p.as_os_str().is_empty()) {
+        MemoryScope::Project
+    } else {
+        MemoryScope::Directory
+    }
 }
 
 pub fn convert_tree_paths(mut tree: DirectoryInfo, project_root: &str) -> DirectoryInfo {
     let root_path = Path::new(project_root);
 
     // Convert the tree path to relative
-    tree.path = tree
-        .path
-        .strip_prefix(root_path)
-        .map(|p| {
-            if p.as_os_str().is_empty() {
-                PathBuf::from(".")
-            } else {
-                PathBuf::from(format!("./{}", p.display()))
-            }
-        })
-        .unwrap_or_else(|_| PathBuf::from("."));
+    tree.path =
+        tree.path
+            .strip_prefix(root_path)
+            .map(|p| {
+                if p.as_os_str().is_empty() {
+                    PathBuf::from(".")
+                } else {
+                    PathBuf::from(format!("./{}", p.display()))
+                }
+            })
+            .unwrap_or_else(|_| PathBuf::from("."));
 
     // Recursively convert children
-    tree.children = tree
-        .children
-        .into_iter()
-        .map(|child| convert_tree_paths(child, project_root))
-        .collect();
+    tree.children = tree.children.into_iter().map(|child| convert_tree_paths(child, project_root)).collect();
 
     tree
 }
@@ -54,10 +69,24 @@ pub fn convert_tree_paths(mut tree: DirectoryInfo, project_root: &str) -> Direct
 pub fn to_relative_paths(paths: Vec<PathBuf>, project_root: &str) -> Vec<String> {
     paths
         .into_iter()
-        .filter_map(|path| {
-            path.strip_prefix(project_root)
-                .ok()
-                .map(|p| format!("./{}", p.display()))
-        })
+        .filter_map(|path| path.strip_prefix(project_root).ok().map(|p| format!("./{}", p.display())))
         .collect()
 }
+
+/// Path of `file_path` relative to the canonical project `root`, resolving symlinked parents.
+///
+/// The file itself need not exist, so this works just before a create or just after a delete.
+pub fn project_relative(root: &Path, file_path: &Path) -> Result<String> {
+    let canonical_parent =
+        file_path.parent().map(|p| p.canonicalize()).transpose()?.unwrap_or_else(|| root.to_path_buf());
+    let file_name = file_path.file_name().context("Path has no file name")?;
+
+    let relative = canonical_parent
+        .join(file_name)
+        .strip_prefix(root)
+        .context("File is outside the project root")?
+        .to_string_lossy()
+        .to_string();
+
+    Ok(relative)
+}
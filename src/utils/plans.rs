@@ -0,0 +1,231 @@
+use std::fmt;
+
+use super::search::parse_heading;
+use super::{front_matter, hash};
+use crate::models::{PlanSummary, PlanTask};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanError {
+    NotAPlan(String),
+    NotATask(usize),
+    TextChanged { line: usize, found: String },
+    Modified(String),
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlanError::NotAPlan(path) => write!(f, "Not a plan: {}", path),
+            PlanError::NotATask(line) => write!(f, "Line {} is not a task-list item", line),
+            PlanError::TextChanged { line, found } => {
+                write!(f, "Task on line {} has changed; it now reads \"{}\"", line, found)
+            }
+            PlanError::Modified(path) => write!(f, "Plan has changed since it was loaded: {}", path),
+        }
+    }
+}
+
+impl std::error::Error for PlanError {}
+
+/// Every `- [ ]` / `- [x]` item outside code fences and front matter, in file order
+pub fn parse_tasks(content: &str) -> Vec<PlanTask> {
+    scan(content).into_iter().map(|(task, _)| task).collect()
+}
+
+/// The plan's own status: front matter `status`, else a `Status:` line in the body
+pub fn declared_status(content: &str) -> Option<String> {
+    let (metadata, body) = front_matter::parse(content);
+    if let Some(status) = metadata.and_then(|m| m.status) {
+        return Some(status);
+    }
+
+    let mut in_fence = false;
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        // `Status: Draft`, `**Status:** Draft` or `**Status**: Draft`
+        let plain = trimmed.replace("**", "").replace("__", "");
+        let Some((key, value)) = plain.split_once(':') else {
+            continue;
+        };
+        if key.trim().eq_ignore_ascii_case("status") && !value.trim().is_empty() {
+            return Some(value.trim().to_string());
+        }
+    }
+
+    None
+}
+
+/// Whole percent complete, rounded down; a plan without tasks is 0% done
+pub fn percent(done: usize, total: usize) -> u8 {
+    (done * 100).checked_div(total).unwrap_or(0) as u8
+}
+
+/// Summarize a plan. `updated_at` is passed in because it comes from the file, not the content.
+pub fn summarize(path: &str, content: &str, updated_at: String, include_tasks: bool) -> PlanSummary {
+    let tasks = parse_tasks(content);
+    let total = tasks.len();
+    let done = tasks.iter().filter(|t| t.checked).count();
+
+    let status = declared_status(content).unwrap_or_else(|| {
+        match done {
+            0 => "not_started",
+            _ if done == total => "done",
+            _ => "in_progress",
+        }
+        .to_string()
+    });
+
+    PlanSummary {
+        path: path.to_string(),
+        title: title(path, content),
+        status,
+        total,
+        done,
+        open: total - done,
+        percent: percent(done, total),
+        updated_at,
+        content_hash: hash::calculate_hash(content),
+        tasks: include_tasks.then_some(tasks),
+    }
+}
+
+/// Check or uncheck the task on `line` (1-based), flipping it when `checked` is `None`.
+///
+/// Only the character between the brackets changes; every other byte is kept as is.
+/// With `expected_text`, the task must still read that, so a stale line number can't hit the wrong item.
+pub fn toggle_task(
+    content: &str,
+    line: usize,
+    checked: Option<bool>,
+    expected_text: Option<&str>,
+) -> Result<(String, PlanTask), PlanError> {
+    let (mut task, offset) =
+        scan(content).into_iter().find(|(task, _)| task.line == line).ok_or(PlanError::NotATask(line))?;
+
+    if let Some(expected) = expected_text {
+        if expected.trim() != task.text {
+            return Err(PlanError::TextChanged { line, found: task.text });
+        }
+    }
+
+    let checked = checked.unwrap_or(!task.checked);
+    if checked == task.checked {
+        return Ok((content.to_string(), task)); // Already there; keeps an `X` as written
+    }
+
+    task.checked = checked;
+    let mut updated = content.to_string();
+    updated.replace_range(offset..offset + 1, if checked { "x" } else { " " });
+    Ok((updated, task))
+}
+
+/// Tasks with the byte offset of the character inside their brackets
+fn scan(content: &str) -> Vec<(PlanTask, usize)> {
+    let (block, _) = front_matter::split(content);
+    let mut tasks = Vec::new();
+    let mut indents: Vec<usize> = Vec::new();
+    let mut in_fence = false;
+    let mut offset = 0;
+
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        let start = offset;
+        offset += line.len();
+        if start < block.len() {
+            continue;
+        }
+
+        let text = line.trim_end_matches(['\r', '\n']);
+        let trimmed = text.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence || trimmed.is_empty() {
+            continue;
+        }
+
+        let indent = indent_width(text);
+        let Some((mark_at, rest)) = checkbox(trimmed) else {
+            if indent == 0 {
+                indents.clear(); // A paragraph or heading ends the list
+            }
+            continue;
+        };
+
+        while indents.last().is_some_and(|&i| i >= indent) {
+            indents.pop();
+        }
+        let depth = indents.len();
+        indents.push(indent);
+
+        let mark_offset = start + (text.len() - trimmed.len()) + mark_at;
+        tasks.push((
+            PlanTask {
+                line: index + 1,
+                text: rest.trim().to_string(),
+                checked: &content[mark_offset..mark_offset + 1] != " ",
+                depth,
+            },
+            mark_offset,
+        ));
+    }
+
+    tasks
+}
+
+/// For a list item like `- [x] text` or `1. [ ] text`: the offset of the mark and the text after `]`
+fn checkbox(item: &str) -> Option<(usize, &str)> {
+    let after_marker = if let Some(rest) = item.strip_prefix(['-', '*', '+']) {
+        rest
+    } else {
+        let digits = item.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 || digits > 9 {
+            return None;
+        }
+        item[digits..].strip_prefix(['.', ')'])?
+    };
+
+    let spaces = after_marker.len() - after_marker.trim_start_matches([' ', '\t']).len();
+    if spaces == 0 {
+        return None;
+    }
+
+    let box_start = item.len() - after_marker.len() + spaces;
+    let rest = &item[box_start..];
+    let mark = rest.get(1..2)?;
+    if !rest.starts_with('[') || rest.get(2..3) != Some("]") || !matches!(mark, " " | "x" | "X") {
+        return None;
+    }
+
+    let text = &rest[3..];
+    if !(text.is_empty() || text.starts_with([' ', '\t'])) {
+        return None;
+    }
+    Some((box_start + 1, text))
+}
+
+fn indent_width(line: &str) -> usize {
+    line.chars().take_while(|c| c.is_whitespace()).map(|c| if c == '\t' { 4 } else { 1 }).sum()
+}
+
+/// Front matter title, else the first `#` heading, else the file name
+fn title(path: &str, content: &str) -> String {
+    let (metadata, body) = front_matter::parse(content);
+    metadata
+        .and_then(|m| m.title)
+        .or_else(|| {
+            body.lines().find_map(|line| parse_heading(line).filter(|(level, _)| *level == 1)).map(|(_, text)| text)
+        })
+        .unwrap_or_else(|| {
+            let name = path.rsplit('/').next().unwrap_or(path);
+            name.strip_suffix(".md").unwrap_or(name).to_string()
+        })
+}
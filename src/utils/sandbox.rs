@@ -0,0 +1,103 @@
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// File names the memory endpoints are allowed to touch
+pub const MEMORY_FILE_NAMES: &[&str] = &["CLAUDE.md", "CLAUDE.local.md"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SandboxError {
+    Empty,
+    Absolute(String),
+    Traversal(String),
+    Escape(String),
+    NotMemoryFile(String),
+    OutsideAllowed(String),
+    RootUnavailable(String),
+}
+
+impl fmt::Display for SandboxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SandboxError::Empty => write!(f, "Path is empty"),
+            SandboxError::Absolute(path) => write!(f, "Absolute paths are not allowed: {}", path),
+            SandboxError::Traversal(path) => write!(f, "Path traversal is not allowed: {}", path),
+            SandboxError::Escape(path) => write!(f, "Path resolves outside the project: {}", path),
+            SandboxError::NotMemoryFile(path) => write!(f, "Not a memory file: {}", path),
+            SandboxError::OutsideAllowed(path) => write!(f, "Not inside a documentation root: {}", path),
+            SandboxError::RootUnavailable(msg) => write!(f, "Project root unavailable: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SandboxError {}
+
+/// Resolve a client-supplied relative path to a location inside `root`.
+///
+/// Rejects absolute paths and `..` components outright, then canonicalises the
+/// deepest existing ancestor so symlinks cannot point the write elsewhere.
+pub fn resolve_path(root: &Path, relative: &str) -> Result<PathBuf, SandboxError> {
+    let root = root.canonicalize().map_err(|e| SandboxError::RootUnavailable(e.to_string()))?;
+
+    let normalized = normalize(relative)?;
+    let target = root.join(&normalized);
+
+    // Find the deepest part of the path that exists (including dangling symlinks)
+    let mut existing = target.as_path();
+    while fs::symlink_metadata(existing).is_err() {
+        existing = match existing.parent() {
+            Some(parent) => parent,
+            None => break,
+        };
+    }
+
+    let resolved = existing.canonicalize().map_err(|_| SandboxError::Escape(relative.to_string()))?;
+
+    if !resolved.starts_with(&root) {
+        return Err(SandboxError::Escape(relative.to_string()));
+    }
+
+    Ok(target)
+}
+
+/// Resolve a path that must lie strictly inside one of the `allowed` project-relative directories
+pub fn resolve_within(root: &Path, allowed: &[&str], relative: &str) -> Result<PathBuf, SandboxError> {
+    let normalized = normalize(relative)?;
+    let inside = allowed.iter().any(|dir| normalized.starts_with(dir) && normalized != Path::new(dir));
+
+    if !inside {
+        return Err(SandboxError::OutsideAllowed(relative.to_string()));
+    }
+
+    resolve_path(root, relative)
+}
+
+/// Resolve a path for the memory endpoints, which only operate on memory files
+pub fn resolve_memory_path(root: &Path, relative: &str) -> Result<PathBuf, SandboxError> {
+    let file_name = Path::new(relative).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+    if !MEMORY_FILE_NAMES.contains(&file_name.as_str()) {
+        return Err(SandboxError::NotMemoryFile(relative.to_string()));
+    }
+
+    resolve_path(root, relative)
+}
+
+/// Strip `.` components, rejecting anything absolute, empty or climbing with `..`
+fn normalize(relative: &str) -> Result<PathBuf, SandboxError> {
+    let mut normalized = PathBuf::new();
+    for component in Path::new(relative).components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => return Err(SandboxError::Traversal(relative.to_string())),
+            Component::RootDir | Component::Prefix(_) => return Err(SandboxError::Absolute(relative.to_string())),
+        }
+    }
+
+    if normalized.as_os_str().is_empty() {
+        return Err(SandboxError::Empty);
+    }
+
+    Ok(normalized)
+}
@@ -0,0 +1,393 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use crate::models::{FileKind, MemoryScope, SearchHit};
+
+const SNIPPET_CHARS: usize = 160;
+const SNIPPET_LEAD_CHARS: usize = 40;
+const MIN_PREFIX_LEN: usize = 3;
+const HEADING_BOOST: f64 = 2.0;
+const PREFIX_WEIGHT: f64 = 0.5;
+
+/// Where an indexed document lives
+#[derive(Debug, Clone)]
+pub struct DocumentInfo {
+    pub kind: FileKind,
+    pub path: String,               // Path as used by that kind's API
+    pub project_path: String,       // Relative to the project root; the index key
+    pub scope: Option<MemoryScope>, // Memory files only
+    pub modified: u64,              // Unix seconds, used to skip unchanged files on refresh
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilters {
+    pub kind: Option<FileKind>,
+    pub scope: Option<MemoryScope>,
+    pub dir: Option<String>, // Only files at or below this project-relative directory
+}
+
+impl SearchFilters {
+    fn matches(&self, info: &DocumentInfo) -> bool {
+        if self.kind.is_some_and(|kind| kind != info.kind) {
+            return false;
+        }
+        if self.scope.is_some() && self.scope != info.scope {
+            return false;
+        }
+
+        match self.dir.as_deref().map(normalize_dir) {
+            Some(dir) if !dir.is_empty() => {
+                info.project_path == dir || info.project_path.starts_with(&format!("{}/", dir))
+            }
+            _ => true,
+        }
+    }
+}
+
+/// A heading-delimited part of a document; the unit results are ranked by
+#[derive(Clone)]
+struct Section {
+    headings: Vec<String>, // Enclosing headings, outermost first
+    heading_terms: HashSet<String>,
+    start: usize, // 0-based line range
+    end: usize,
+}
+
+#[derive(Clone)]
+struct Document {
+    info: DocumentInfo,
+    lines: Vec<String>,
+    sections: Vec<Section>,
+    terms: HashSet<String>,
+}
+
+/// In-memory inverted index over memory files and docs, keyed by project-relative path
+#[derive(Clone, Default)]
+pub struct SearchIndex {
+    documents: HashMap<String, Document>,
+    postings: HashMap<String, HashMap<(String, usize), usize>>, // term -> (path, section) -> frequency
+    section_count: usize,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    pub fn document(&self, project_path: &str) -> Option<&DocumentInfo> {
+        self.documents.get(project_path).map(|d| &d.info)
+    }
+
+    pub fn paths(&self) -> Vec<String> {
+        self.documents.keys().cloned().collect()
+    }
+
+    /// Add a document, replacing any previous version at the same path
+    pub fn upsert(&mut self, info: DocumentInfo, content: &str) {
+        let key = info.project_path.clone();
+        self.remove(&key);
+
+        let lines: Vec<String> = content.lines().map(str::to_string).collect();
+        let sections = split_sections(&lines);
+        let mut terms = HashSet::new();
+
+        for (index, section) in sections.iter().enumerate() {
+            let mut counts: HashMap<String, usize> = HashMap::new();
+            for line in &lines[section.start..section.end] {
+                for term in tokenize(line) {
+                    *counts.entry(term).or_default() += 1;
+                }
+            }
+
+            for (term, count) in counts {
+                self.postings.entry(term.clone()).or_default().insert((key.clone(), index), count);
+                terms.insert(term);
+            }
+        }
+
+        self.section_count += sections.len();
+        self.documents.insert(key, Document { info, lines, sections, terms });
+    }
+
+    /// Drop a document; returns whether it was indexed
+    pub fn remove(&mut self, project_path: &str) -> bool {
+        let Some(document) = self.documents.remove(project_path) else {
+            return false;
+        };
+
+        for term in &document.terms {
+            if let Some(postings) = self.postings.get_mut(term) {
+                postings.retain(|(path, _), _| path != project_path);
+                if postings.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+
+        self.section_count -= document.sections.len();
+        true
+    }
+
+    /// Drop every document at or below a directory
+    pub fn remove_dir(&mut self, dir: &str) -> usize {
+        let prefix = format!("{}/", normalize_dir(dir));
+        let doomed: Vec<String> = self.documents.keys().filter(|path| path.starts_with(&prefix)).cloned().collect();
+
+        for path in &doomed {
+            self.remove(path);
+        }
+        doomed.len()
+    }
+
+    /// Rank sections against the query; returns the total match count and the top `limit` hits
+    pub fn search(&self, query: &str, filters: &SearchFilters, limit: usize) -> (usize, Vec<SearchHit>) {
+        let terms = unique_terms(query);
+        if terms.is_empty() {
+            return (0, Vec::new());
+        }
+
+        let total_sections = self.section_count.max(1) as f64;
+        let mut scores: HashMap<(&str, usize), (f64, HashSet<usize>)> = HashMap::new();
+
+        for (term_index, term) in terms.iter().enumerate() {
+            for (token, weight) in self.expand(term) {
+                let postings = &self.postings[token];
+                let idf = (1.0 + total_sections / postings.len() as f64).ln();
+
+                for ((path, section), frequency) in postings {
+                    let document = &self.documents[path];
+                    if !filters.matches(&document.info) {
+                        continue;
+                    }
+
+                    let mut score = weight * idf * (1.0 + (*frequency as f64).ln());
+                    if document.sections[*section].heading_terms.contains(token) {
+                        score *= HEADING_BOOST;
+                    }
+
+                    let entry = scores.entry((path.as_str(), *section)).or_default();
+                    entry.0 += score;
+                    entry.1.insert(term_index);
+                }
+            }
+        }
+
+        // Sections containing every query term outrank partial matches
+        let mut ranked: Vec<((&str, usize), f64)> = scores
+            .into_iter()
+            .map(|(key, (score, matched))| {
+                let coverage = matched.len() as f64 / terms.len() as f64;
+                (key, score * coverage * coverage)
+            })
+            .collect();
+
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal).then_with(|| a.0.cmp(&b.0)));
+
+        let total = ranked.len();
+        let hits = ranked
+            .into_iter()
+            .take(limit)
+            .map(|((path, section), score)| self.hit(path, section, &terms, score))
+            .collect();
+
+        (total, hits)
+    }
+
+    /// Index terms a query term matches: itself, plus longer words it prefixes
+    fn expand<'a>(&'a self, term: &str) -> Vec<(&'a String, f64)> {
+        let mut matches = Vec::new();
+        for token in self.postings.keys() {
+            if token == term {
+                matches.push((token, 1.0));
+            } else if term.chars().count() >= MIN_PREFIX_LEN && token.starts_with(term) {
+                matches.push((token, PREFIX_WEIGHT));
+            }
+        }
+        matches
+    }
+
+    fn hit(&self, path: &str, section_index: usize, terms: &[String], score: f64) -> SearchHit {
+        let document = &self.documents[path];
+        let section = &document.sections[section_index];
+
+        // Show the line matching the most distinct terms, preferring body text over the heading
+        let (line_index, _) = (section.start..section.end)
+            .map(|i| {
+                let line = &document.lines[i];
+                let count = matched_terms(line, terms);
+                let body = count > 0 && parse_heading(line).is_none();
+                (i, count * 2 + body as usize)
+            })
+            .fold((section.start, 0), |best, (i, rank)| if rank > best.1 { (i, rank) } else { best });
+
+        let (snippet, highlights) = snippet(&document.lines[line_index], terms);
+
+        SearchHit {
+            kind: document.info.kind,
+            path: document.info.path.clone(),
+            scope: document.info.scope,
+            headings: section.headings.clone(),
+            line: line_index + 1,
+            snippet,
+            highlights,
+            score,
+        }
+    }
+}
+
+/// Lowercased words, split on anything that isn't a letter or digit
+pub fn tokenize(text: &str) -> Vec<String> {
+    words(text).into_iter().map(|(_, _, word)| word).collect()
+}
+
+/// Parse an ATX heading (`## Title`) into its level and text
+pub fn parse_heading(line: &str) -> Option<(usize, String)> {
+    let trimmed = line.trim_start();
+    if line.len() - trimmed.len() > 3 {
+        return None; // Indented code, not a heading
+    }
+
+    let level = trimmed.chars().take_while(|&c| c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+
+    let rest = &trimmed[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+
+    let text = rest.trim().trim_end_matches('#').trim_end();
+    Some((level, text.to_string()))
+}
+
+fn split_sections(lines: &[String]) -> Vec<Section> {
+    let mut sections = Vec::new();
+    let mut stack: Vec<(usize, String)> = Vec::new();
+    let mut start = 0;
+    let mut in_fence = false;
+
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        if let Some((level, text)) = parse_heading(line) {
+            if i > start {
+                sections.push(section(&stack, start, i));
+            }
+            stack.retain(|(l, _)| *l < level);
+            stack.push((level, text));
+            start = i;
+        }
+    }
+
+    if lines.len() > start || sections.is_empty() {
+        sections.push(section(&stack, start, lines.len()));
+    }
+
+    sections
+}
+
+fn section(stack: &[(usize, String)], start: usize, end: usize) -> Section {
+    Section {
+        headings: stack.iter().map(|(_, text)| text.clone()).collect(),
+        heading_terms: stack.last().map(|(_, text)| tokenize(text).into_iter().collect()).unwrap_or_default(),
+        start,
+        end,
+    }
+}
+
+/// Words with their char offsets
+fn words(text: &str) -> Vec<(usize, usize, String)> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut start = 0;
+
+    for (i, c) in text.chars().enumerate() {
+        if c.is_alphanumeric() {
+            if current.is_empty() {
+                start = i;
+            }
+            current.extend(c.to_lowercase());
+        } else if !current.is_empty() {
+            words.push((start, i, std::mem::take(&mut current)));
+        }
+    }
+    if !current.is_empty() {
+        words.push((start, text.chars().count(), current));
+    }
+
+    words
+}
+
+fn unique_terms(query: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    tokenize(query).into_iter().filter(|t| seen.insert(t.clone())).collect()
+}
+
+fn term_matches(word: &str, term: &str) -> bool {
+    word == term || (term.chars().count() >= MIN_PREFIX_LEN && word.starts_with(term))
+}
+
+fn matched_terms(line: &str, terms: &[String]) -> usize {
+    let words = tokenize(line);
+    terms.iter().filter(|t| words.iter().any(|w| term_matches(w, t))).count()
+}
+
+/// Cut a long line down around its first match and mark matching words as char ranges
+fn snippet(line: &str, terms: &[String]) -> (String, Vec<[usize; 2]>) {
+    let line = line.trim();
+    let chars: Vec<char> = line.chars().collect();
+
+    let first_match = words(line)
+        .into_iter()
+        .find(|(_, _, w)| terms.iter().any(|t| term_matches(w, t)))
+        .map(|(start, _, _)| start)
+        .unwrap_or(0);
+
+    let text = if chars.len() <= SNIPPET_CHARS {
+        line.to_string()
+    } else {
+        let start = first_match.saturating_sub(SNIPPET_LEAD_CHARS).min(chars.len() - SNIPPET_CHARS);
+        let end = start + SNIPPET_CHARS;
+        let mut text: String = chars[start..end].iter().collect();
+        if start > 0 {
+            text.insert(0, '…');
+        }
+        if end < chars.len() {
+            text.push('…');
+        }
+        text
+    };
+
+    let highlights = words(&text)
+        .into_iter()
+        .filter(|(_, _, w)| terms.iter().any(|t| term_matches(w, t)))
+        .map(|(start, end, _)| [start, end])
+        .collect();
+
+    (text, highlights)
+}
+
+fn normalize_dir(dir: &str) -> String {
+    let dir = dir.trim_start_matches("./").trim_end_matches('/');
+    if dir == "." {
+        String::new()
+    } else {
+        dir.to_string()
+    }
+}
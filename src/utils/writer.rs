@@ -0,0 +1,156 @@
+use anyhow::{Context, Result};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const BOM: &str = "\u{feff}";
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+}
+
+/// Text conventions of an existing file that a save should keep
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextFormat {
+    pub line_ending: LineEnding,
+    pub bom: bool,
+    pub final_newline: bool,
+}
+
+/// Detect line endings, BOM and trailing newline of existing file content
+pub fn detect_format(existing: &str) -> TextFormat {
+    let bom = existing.starts_with(BOM);
+    let crlf = existing.matches("\r\n").count();
+    let lf = existing.matches('\n').count();
+
+    TextFormat {
+        // Mixed files follow the majority
+        line_ending: if crlf > 0 && crlf * 2 >= lf { LineEnding::CrLf } else { LineEnding::Lf },
+        bom,
+        final_newline: existing.ends_with('\n'),
+    }
+}
+
+/// Rewrite editor content to match a file's original conventions
+pub fn apply_format(content: &str, format: &TextFormat) -> String {
+    let body = content.strip_prefix(BOM).unwrap_or(content).replace("\r\n", "\n");
+
+    let body = match (format.final_newline, body.ends_with('\n')) {
+        (true, false) if !body.is_empty() => format!("{}\n", body),
+        (false, true) => body.trim_end_matches('\n').to_string(),
+        _ => body,
+    };
+
+    let body = match format.line_ending {
+        LineEnding::CrLf => body.replace('\n', "\r\n"),
+        LineEnding::Lf => body,
+    };
+
+    if format.bom {
+        format!("{}{}", BOM, body)
+    } else {
+        body
+    }
+}
+
+/// Save text over a file, keeping the existing file's formatting and permissions.
+///
+/// Returns the exact content that ended up on disk.
+pub fn write_preserving(path: &Path, content: &str) -> Result<String> {
+    let target = resolve_target(path);
+
+    let final_content = match fs::read_to_string(&target) {
+        Ok(existing) => apply_format(content, &detect_format(&existing)),
+        Err(_) => content.to_string(),
+    };
+
+    write_atomic(&target, final_content.as_bytes())?;
+    Ok(final_content)
+}
+
+/// Write via a temp file in the same directory, fsync, then rename over the target.
+///
+/// A crash leaves either the old file or the new one, never a truncated mix.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let target = resolve_target(path);
+    let dir = target.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(dir)?;
+
+    let temp = temp_path(&target);
+    let existing = fs::metadata(&target).ok().map(|metadata| metadata.permissions());
+    let result = (|| -> Result<()> {
+        // The temp file gets the target's permissions before any content goes in,
+        // so a private file is never readable by others while it is rewritten
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        if let Some(permissions) = &existing {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            options.mode(permissions.mode() & 0o7777);
+        }
+        let mut file = options.open(&temp)?;
+        if let Some(permissions) = existing {
+            file.set_permissions(permissions)?; // The umask may have masked bits off
+        }
+
+        file.write_all(bytes)?;
+        file.sync_all()?;
+
+        fs::rename(&temp, &target)?;
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result.with_context(|| format!("Failed to write {}", target.display()))?;
+
+    sync_dir(dir);
+    Ok(())
+}
+
+/// Create a file that must not exist yet; fails with `AlreadyExists` rather than replacing one
+pub fn write_new(path: &Path, bytes: &[u8]) -> Result<()> {
+    let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(dir)?;
+
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    let written = file.write_all(bytes).and_then(|_| file.sync_all());
+    if let Err(e) = written {
+        let _ = fs::remove_file(path);
+        return Err(anyhow::Error::new(e).context(format!("Failed to write {}", path.display())));
+    }
+
+    sync_dir(dir);
+    Ok(())
+}
+
+/// Write through symlinks instead of replacing the link with a regular file
+fn resolve_target(path: &Path) -> PathBuf {
+    match fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_symlink() => fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn temp_path(target: &Path) -> PathBuf {
+    let name = target.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    target.with_file_name(format!(".{}.cc-atlas-{}-{}.tmp", name, std::process::id(), counter))
+}
+
+/// Persist the rename itself; best-effort since not every platform supports it
+fn sync_dir(dir: &Path) {
+    #[cfg(unix)]
+    if let Ok(handle) = File::open(dir) {
+        let _ = handle.sync_all();
+    }
+    #[cfg(not(unix))]
+    let _ = dir;
+}